@@ -3,6 +3,27 @@ pub use sea_orm_migration::prelude::*;
 mod util;
 mod m20250613_083042_init;
 mod m20250615_063806_generate_users;
+mod m20261018_020114_work_schedule;
+mod m20261018_041532_work_date;
+mod m20261018_063210_attendance_checkout;
+mod m20261018_081947_leave;
+mod m20261018_094215_lateness;
+mod m20261018_103358_checkin_policy;
+mod m20261018_112604_attendance_correction;
+mod m20261018_120931_public_holiday;
+mod m20261018_125512_unique_work_date;
+mod m20261018_132740_attendance_type;
+mod m20261018_140215_overtime_approval;
+mod m20261018_143120_overtime_preapproval;
+mod m20261018_151045_overtime_time_range;
+mod m20261018_161204_reimbursement_receipt;
+mod m20261018_164410_reimbursement_category;
+mod m20261018_171530_reimbursement_approval;
+mod m20261018_174205_reimbursement_withdrawal;
+mod m20261018_180245_exchange_rate;
+mod m20261018_184520_reimbursement_flag;
+mod m20261018_192010_payroll_run;
+mod m20261018_201530_tax_profile;
 
 pub struct Migrator;
 
@@ -12,6 +33,27 @@ impl MigratorTrait for Migrator {
         vec![
             Box::new(m20250613_083042_init::Migration),
             Box::new(m20250615_063806_generate_users::Migration),
+            Box::new(m20261018_020114_work_schedule::Migration),
+            Box::new(m20261018_041532_work_date::Migration),
+            Box::new(m20261018_063210_attendance_checkout::Migration),
+            Box::new(m20261018_081947_leave::Migration),
+            Box::new(m20261018_094215_lateness::Migration),
+            Box::new(m20261018_103358_checkin_policy::Migration),
+            Box::new(m20261018_112604_attendance_correction::Migration),
+            Box::new(m20261018_120931_public_holiday::Migration),
+            Box::new(m20261018_125512_unique_work_date::Migration),
+            Box::new(m20261018_132740_attendance_type::Migration),
+            Box::new(m20261018_140215_overtime_approval::Migration),
+            Box::new(m20261018_143120_overtime_preapproval::Migration),
+            Box::new(m20261018_151045_overtime_time_range::Migration),
+            Box::new(m20261018_161204_reimbursement_receipt::Migration),
+            Box::new(m20261018_164410_reimbursement_category::Migration),
+            Box::new(m20261018_171530_reimbursement_approval::Migration),
+            Box::new(m20261018_174205_reimbursement_withdrawal::Migration),
+            Box::new(m20261018_180245_exchange_rate::Migration),
+            Box::new(m20261018_184520_reimbursement_flag::Migration),
+            Box::new(m20261018_192010_payroll_run::Migration),
+            Box::new(m20261018_201530_tax_profile::Migration),
         ]
     }
 }
@@ -0,0 +1,111 @@
+use sea_orm_migration::prelude::*;
+
+use crate::{m20250613_083042_init::User, setup_user_table_fk, util::{default_user_table_statement, DefaultColumn}};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(default_user_table_statement()
+                .table(WorkSchedule::Table)
+                .col(ColumnDef::new(WorkSchedule::Name)
+                    .text()
+                    .not_null())
+                .col(ColumnDef::new(WorkSchedule::WorkingDays)
+                    .small_integer()
+                    .not_null()) // Bitmask of working weekdays, bit 0 is Monday and bit 6 is Sunday
+                .col(ColumnDef::new(WorkSchedule::ShiftStart)
+                    .time()
+                    .not_null())
+                .col(ColumnDef::new(WorkSchedule::ShiftEnd)
+                    .time()
+                    .not_null())
+                .col(ColumnDef::new(WorkSchedule::BreakMinutes)
+                    .small_integer()
+                    .not_null()
+                    .default(0))
+                .take()
+            ).await.unwrap();
+        setup_user_table_fk!(manager, WorkSchedule::Table);
+
+        manager
+            .create_table(default_user_table_statement()
+                .table(EmployeeWorkSchedule::Table)
+                .col(ColumnDef::new(EmployeeWorkSchedule::UserId)
+                    .uuid()
+                    .not_null())
+                .col(ColumnDef::new(EmployeeWorkSchedule::WorkScheduleId)
+                    .uuid()
+                    .not_null())
+                .col(ColumnDef::new(EmployeeWorkSchedule::EffectiveFrom)
+                    .date()
+                    .not_null())
+                .col(ColumnDef::new(EmployeeWorkSchedule::EffectiveUntil)
+                    .date()) // Open-ended when null
+                .take()
+            ).await.unwrap();
+        setup_user_table_fk!(manager, EmployeeWorkSchedule::Table);
+
+        manager.create_foreign_key(ForeignKeyCreateStatement::new()
+            .from(EmployeeWorkSchedule::Table, EmployeeWorkSchedule::UserId)
+            .to(User::Table, DefaultColumn::Id)
+            .on_delete(ForeignKeyAction::Cascade)
+            .on_update(ForeignKeyAction::Cascade)
+            .take()
+        ).await.unwrap();
+
+        manager.create_foreign_key(ForeignKeyCreateStatement::new()
+            .from(EmployeeWorkSchedule::Table, EmployeeWorkSchedule::WorkScheduleId)
+            .to(WorkSchedule::Table, DefaultColumn::Id)
+            .take()
+        ).await.unwrap();
+
+        manager.create_index(IndexCreateStatement::new()
+            .name("idx_employee_work_schedule_user_id_effective_from")
+            .table(EmployeeWorkSchedule::Table)
+            .col(EmployeeWorkSchedule::UserId)
+            .col(EmployeeWorkSchedule::EffectiveFrom)
+            .take()
+        ).await.unwrap();
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager.drop_table(
+            TableDropStatement::new()
+                .table(EmployeeWorkSchedule::Table)
+                .take()
+        ).await.unwrap();
+
+        manager.drop_table(
+            TableDropStatement::new()
+                .table(WorkSchedule::Table)
+                .take()
+        ).await.unwrap();
+
+        Ok(())
+    }
+}
+
+#[derive(Iden)]
+pub(crate) enum WorkSchedule {
+    Table,
+    Name,
+    WorkingDays,
+    ShiftStart,
+    ShiftEnd,
+    BreakMinutes,
+}
+
+#[derive(Iden)]
+enum EmployeeWorkSchedule {
+    Table,
+    UserId,
+    WorkScheduleId,
+    EffectiveFrom,
+    EffectiveUntil,
+}
@@ -0,0 +1,95 @@
+use chrono::Local;
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        for table in [EmployeeAttendance::Table.into_iden(), EmployeeOvertime::Table.into_iden()] {
+            manager
+                .alter_table(TableAlterStatement::new()
+                    .table(table.clone())
+                    .add_column(ColumnDef::new(WorkDate)
+                        .date())
+                    .take()
+                ).await.unwrap();
+
+            // Business days are told apart in the server's local time like check-ins do, and a
+            // time belongs to the day before until halfway through the off-duty gap after its shift.
+            // Employees without a schedule assigned work Monday to Friday from 09:00 to 17:00
+            manager.get_connection().execute_unprepared(&format!(r#"
+                UPDATE {table}
+                SET work_date = shifted.work_date
+                FROM (
+                    SELECT local.id, CASE
+                        WHEN schedule.working_days & (1 << (EXTRACT(ISODOW FROM local.date - 1)::int - 1)) <> 0
+                            AND local.at < (local.date - 1) + schedule.shift_start
+                                + make_interval(mins => schedule.shift_minutes + (1440 - schedule.shift_minutes) / 2)
+                        THEN local.date - 1
+                        ELSE local.date
+                    END AS work_date
+                    FROM (
+                        SELECT id, created_by, created_at AT TIME ZONE INTERVAL '{offset}' AS at, (created_at AT TIME ZONE INTERVAL '{offset}')::date AS date
+                        FROM {table}
+                    ) AS local
+                    CROSS JOIN LATERAL (
+                        SELECT working_days, shift_start,
+                            (EXTRACT(EPOCH FROM shift_end - shift_start) / 60)::int + CASE WHEN shift_end <= shift_start THEN 1440 ELSE 0 END AS shift_minutes
+                        FROM (
+                            SELECT work_schedule.working_days, work_schedule.shift_start, work_schedule.shift_end, employee_work_schedule.effective_from
+                            FROM employee_work_schedule
+                            JOIN work_schedule ON work_schedule.id = employee_work_schedule.work_schedule_id
+                            WHERE employee_work_schedule.user_id = local.created_by
+                                AND employee_work_schedule.effective_from <= local.date - 1
+                                AND (employee_work_schedule.effective_until IS NULL OR local.date - 1 <= employee_work_schedule.effective_until)
+                            UNION ALL
+                            SELECT 31, TIME '09:00', TIME '17:00', DATE '-infinity'
+                            ORDER BY effective_from DESC
+                            LIMIT 1
+                        ) AS assigned
+                    ) AS schedule
+                ) AS shifted
+                WHERE {table}.id = shifted.id
+            "#, table = table.to_string(), offset = Local::now().offset())).await.unwrap();
+
+            manager
+                .alter_table(TableAlterStatement::new()
+                    .table(table)
+                    .modify_column(ColumnDef::new(WorkDate)
+                        .date()
+                        .not_null())
+                    .take()
+                ).await.unwrap();
+        }
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        for table in [EmployeeAttendance::Table.into_iden(), EmployeeOvertime::Table.into_iden()] {
+            manager
+                .alter_table(TableAlterStatement::new()
+                    .table(table)
+                    .drop_column(WorkDate)
+                    .take()
+                ).await.unwrap();
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Iden)]
+enum EmployeeAttendance {
+    Table,
+}
+
+#[derive(Iden)]
+enum EmployeeOvertime {
+    Table,
+}
+
+#[derive(Iden)]
+struct WorkDate;
@@ -0,0 +1,54 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(TableAlterStatement::new()
+                .table(EmployeeAttendance::Table)
+                .add_column(ColumnDef::new(EmployeeAttendance::CheckedOutAt)
+                    .timestamp_with_time_zone())
+                .add_column(ColumnDef::new(EmployeeAttendance::BreakStartedAt)
+                    .timestamp_with_time_zone())
+                .add_column(ColumnDef::new(EmployeeAttendance::BreakEndedAt)
+                    .timestamp_with_time_zone())
+                .add_column(ColumnDef::new(EmployeeAttendance::WorkedMinutes)
+                    .integer()) // Only known once checked-out
+                .add_column(ColumnDef::new(EmployeeAttendance::AutoCheckedOut)
+                    .boolean()
+                    .not_null()
+                    .default(false)) // Employee forgot to check-out, so it was closed at the end of their shift
+                .take()
+            ).await.unwrap();
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(TableAlterStatement::new()
+                .table(EmployeeAttendance::Table)
+                .drop_column(EmployeeAttendance::CheckedOutAt)
+                .drop_column(EmployeeAttendance::BreakStartedAt)
+                .drop_column(EmployeeAttendance::BreakEndedAt)
+                .drop_column(EmployeeAttendance::WorkedMinutes)
+                .drop_column(EmployeeAttendance::AutoCheckedOut)
+                .take()
+            ).await.unwrap();
+
+        Ok(())
+    }
+}
+
+#[derive(Iden)]
+enum EmployeeAttendance {
+    Table,
+    CheckedOutAt,
+    BreakStartedAt,
+    BreakEndedAt,
+    WorkedMinutes,
+    AutoCheckedOut,
+}
@@ -0,0 +1,183 @@
+use sea_orm_migration::{prelude::{extension::postgres::TypeDropStatement, *}, sea_orm::{ActiveEnum, DbBackend, DeriveActiveEnum, EnumIter, Schema}};
+
+use crate::{m20250613_083042_init::User, setup_user_table_fk, util::{default_table_statement, default_user_table_statement, DefaultColumn}};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let schema = Schema::new(DbBackend::Postgres);
+
+        manager
+            .create_type(
+                schema.create_enum_from_active_enum::<LeaveType>()
+            ).await.unwrap();
+
+        manager
+            .create_type(
+                schema.create_enum_from_active_enum::<ApprovalStatus>()
+            ).await.unwrap();
+
+        manager
+            .create_table(default_table_statement()
+                .table(LeaveEntitlement::Table)
+                .col(ColumnDef::new(LeaveEntitlement::UserId)
+                    .uuid()
+                    .not_null())
+                .col(ColumnDef::new(LeaveEntitlement::LeaveType)
+                    .custom(LeaveType::name())
+                    .not_null())
+                .col(ColumnDef::new(LeaveEntitlement::Year)
+                    .integer()
+                    .not_null())
+                .col(ColumnDef::new(LeaveEntitlement::Days)
+                    .integer()
+                    .not_null())
+                .col(ColumnDef::new(LeaveEntitlement::CarriedOverDays)
+                    .integer()
+                    .not_null()
+                    .default(0)) // Unused days brought over from the previous year
+                .take()
+            ).await.unwrap();
+
+        manager.create_foreign_key(ForeignKeyCreateStatement::new()
+            .from(LeaveEntitlement::Table, LeaveEntitlement::UserId)
+            .to(User::Table, DefaultColumn::Id)
+            .on_delete(ForeignKeyAction::Cascade)
+            .on_update(ForeignKeyAction::Cascade)
+            .take()
+        ).await.unwrap();
+
+        manager.create_index(IndexCreateStatement::new()
+            .name("idx_leave_entitlement_user_id_leave_type_year")
+            .table(LeaveEntitlement::Table)
+            .col(LeaveEntitlement::UserId)
+            .col(LeaveEntitlement::LeaveType)
+            .col(LeaveEntitlement::Year)
+            .unique()
+            .take()
+        ).await.unwrap();
+
+        manager
+            .create_table(default_user_table_statement()
+                .table(EmployeeLeave::Table)
+                .col(ColumnDef::new(EmployeeLeave::LeaveType)
+                    .custom(LeaveType::name())
+                    .not_null())
+                .col(ColumnDef::new(EmployeeLeave::StartDate)
+                    .date()
+                    .not_null())
+                .col(ColumnDef::new(EmployeeLeave::EndDate)
+                    .date()
+                    .not_null())
+                .col(ColumnDef::new(EmployeeLeave::Days)
+                    .integer()
+                    .not_null()) // Working days covered by the leave at the time it was requested
+                .col(ColumnDef::new(EmployeeLeave::Reason)
+                    .text()
+                    .not_null())
+                .col(ColumnDef::new(EmployeeLeave::Status)
+                    .custom(ApprovalStatus::name())
+                    .not_null()
+                    .default(Expr::val("pending").cast_as(ApprovalStatus::name())))
+                .col(ColumnDef::new(EmployeeLeave::ReviewedBy)
+                    .uuid())
+                .col(ColumnDef::new(EmployeeLeave::ReviewedAt)
+                    .timestamp_with_time_zone())
+                .col(ColumnDef::new(EmployeeLeave::ReviewComment)
+                    .text())
+                .take()
+            ).await.unwrap();
+        setup_user_table_fk!(manager, EmployeeLeave::Table);
+
+        manager.create_foreign_key(ForeignKeyCreateStatement::new()
+            .from(EmployeeLeave::Table, EmployeeLeave::ReviewedBy)
+            .to(User::Table, DefaultColumn::Id)
+            .on_delete(ForeignKeyAction::SetNull)
+            .on_update(ForeignKeyAction::Cascade)
+            .take()
+        ).await.unwrap();
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager.drop_table(
+            TableDropStatement::new()
+                .table(EmployeeLeave::Table)
+                .take()
+        ).await.unwrap();
+
+        manager.drop_table(
+            TableDropStatement::new()
+                .table(LeaveEntitlement::Table)
+                .take()
+        ).await.unwrap();
+
+        manager
+            .drop_type(
+                TypeDropStatement::new()
+                    .name(ApprovalStatus::name())
+                    .to_owned()
+            ).await.unwrap();
+
+        manager
+            .drop_type(
+                TypeDropStatement::new()
+                    .name(LeaveType::name())
+                    .to_owned()
+            ).await.unwrap();
+
+        Ok(())
+    }
+}
+
+#[derive(EnumIter, DeriveActiveEnum)]
+#[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "leave_type")]
+enum LeaveType {
+    #[sea_orm(string_value = "annual")]
+    Annual,
+    #[sea_orm(string_value = "sick")]
+    Sick,
+    #[sea_orm(string_value = "unpaid")]
+    Unpaid,
+    #[sea_orm(string_value = "maternity")]
+    Maternity,
+}
+
+#[derive(EnumIter, DeriveActiveEnum)]
+#[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "approval_status")]
+pub(crate) enum ApprovalStatus {
+    #[sea_orm(string_value = "pending")]
+    Pending,
+    #[sea_orm(string_value = "approved")]
+    Approved,
+    #[sea_orm(string_value = "rejected")]
+    Rejected,
+}
+
+#[derive(Iden)]
+enum LeaveEntitlement {
+    Table,
+    UserId,
+    LeaveType,
+    Year,
+    Days,
+    CarriedOverDays,
+}
+
+#[derive(Iden)]
+enum EmployeeLeave {
+    Table,
+    LeaveType,
+    StartDate,
+    EndDate,
+    Days,
+    Reason,
+    Status,
+    ReviewedBy,
+    ReviewedAt,
+    ReviewComment,
+}
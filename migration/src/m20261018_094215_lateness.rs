@@ -0,0 +1,44 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(TableAlterStatement::new()
+                .table(EmployeeAttendance::Table)
+                .add_column(ColumnDef::new(EmployeeAttendance::LateMinutes)
+                    .integer()
+                    .not_null()
+                    .default(0)) // Minutes checked-in after the shift start, before any grace period
+                .add_column(ColumnDef::new(EmployeeAttendance::EarlyLeaveMinutes)
+                    .integer()
+                    .not_null()
+                    .default(0)) // Minutes checked-out before the shift end, before any grace period
+                .take()
+            ).await.unwrap();
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(TableAlterStatement::new()
+                .table(EmployeeAttendance::Table)
+                .drop_column(EmployeeAttendance::LateMinutes)
+                .drop_column(EmployeeAttendance::EarlyLeaveMinutes)
+                .take()
+            ).await.unwrap();
+
+        Ok(())
+    }
+}
+
+#[derive(Iden)]
+enum EmployeeAttendance {
+    Table,
+    LateMinutes,
+    EarlyLeaveMinutes,
+}
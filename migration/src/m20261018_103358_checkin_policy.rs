@@ -0,0 +1,148 @@
+use sea_orm_migration::prelude::*;
+
+use crate::{setup_user_table_fk, util::{default_user_table_statement, DefaultColumn}};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(default_user_table_statement()
+                .table(CheckinPolicy::Table)
+                .col(ColumnDef::new(CheckinPolicy::Name)
+                    .text()
+                    .not_null())
+                .col(ColumnDef::new(CheckinPolicy::AttendancePeriodId)
+                    .uuid()) // Applies to every period when null
+                .col(ColumnDef::new(CheckinPolicy::AllowedNetworks)
+                    .array(ColumnType::Text)
+                    .not_null()
+                    .default(Expr::cust("'{}'"))) // CIDRs, any network is allowed when empty
+                .col(ColumnDef::new(CheckinPolicy::Latitude)
+                    .double())
+                .col(ColumnDef::new(CheckinPolicy::Longitude)
+                    .double())
+                .col(ColumnDef::new(CheckinPolicy::RadiusMeters)
+                    .double()) // No geofence when null
+                .take()
+            ).await.unwrap();
+        setup_user_table_fk!(manager, CheckinPolicy::Table);
+
+        manager.create_foreign_key(ForeignKeyCreateStatement::new()
+            .from(CheckinPolicy::Table, CheckinPolicy::AttendancePeriodId)
+            .to(AttendancePeriod::Table, DefaultColumn::Id)
+            .on_delete(ForeignKeyAction::Cascade)
+            .on_update(ForeignKeyAction::Cascade)
+            .take()
+        ).await.unwrap();
+
+        manager
+            .create_table(default_user_table_statement()
+                .table(CheckinRejection::Table)
+                .col(ColumnDef::new(CheckinRejection::AttendancePeriodId)
+                    .uuid()
+                    .not_null())
+                .col(ColumnDef::new(CheckinRejection::ClientIp)
+                    .text())
+                .col(ColumnDef::new(CheckinRejection::Latitude)
+                    .double())
+                .col(ColumnDef::new(CheckinRejection::Longitude)
+                    .double())
+                .col(ColumnDef::new(CheckinRejection::Reason)
+                    .text()
+                    .not_null())
+                .take()
+            ).await.unwrap();
+        setup_user_table_fk!(manager, CheckinRejection::Table);
+
+        manager.create_foreign_key(ForeignKeyCreateStatement::new()
+            .from(CheckinRejection::Table, CheckinRejection::AttendancePeriodId)
+            .to(AttendancePeriod::Table, DefaultColumn::Id)
+            .on_delete(ForeignKeyAction::Cascade)
+            .on_update(ForeignKeyAction::Cascade)
+            .take()
+        ).await.unwrap();
+
+        manager.create_index(IndexCreateStatement::new()
+            .name("idx_checkin_rejection_attendance_period_id")
+            .table(CheckinRejection::Table)
+            .col(CheckinRejection::AttendancePeriodId)
+            .take()
+        ).await.unwrap();
+
+        manager
+            .alter_table(TableAlterStatement::new()
+                .table(EmployeeAttendance::Table)
+                .add_column(ColumnDef::new(EmployeeAttendance::ClientIp)
+                    .text())
+                .add_column(ColumnDef::new(EmployeeAttendance::Latitude)
+                    .double())
+                .add_column(ColumnDef::new(EmployeeAttendance::Longitude)
+                    .double())
+                .take()
+            ).await.unwrap();
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(TableAlterStatement::new()
+                .table(EmployeeAttendance::Table)
+                .drop_column(EmployeeAttendance::ClientIp)
+                .drop_column(EmployeeAttendance::Latitude)
+                .drop_column(EmployeeAttendance::Longitude)
+                .take()
+            ).await.unwrap();
+
+        manager.drop_table(
+            TableDropStatement::new()
+                .table(CheckinRejection::Table)
+                .take()
+        ).await.unwrap();
+
+        manager.drop_table(
+            TableDropStatement::new()
+                .table(CheckinPolicy::Table)
+                .take()
+        ).await.unwrap();
+
+        Ok(())
+    }
+}
+
+#[derive(Iden)]
+enum CheckinPolicy {
+    Table,
+    Name,
+    AttendancePeriodId,
+    AllowedNetworks,
+    Latitude,
+    Longitude,
+    RadiusMeters,
+}
+
+#[derive(Iden)]
+enum CheckinRejection {
+    Table,
+    AttendancePeriodId,
+    ClientIp,
+    Latitude,
+    Longitude,
+    Reason,
+}
+
+#[derive(Iden)]
+enum EmployeeAttendance {
+    Table,
+    ClientIp,
+    Latitude,
+    Longitude,
+}
+
+#[derive(Iden)]
+enum AttendancePeriod {
+    Table,
+}
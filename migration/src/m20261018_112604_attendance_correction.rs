@@ -0,0 +1,131 @@
+use sea_orm_migration::{prelude::*, sea_orm::ActiveEnum};
+
+use crate::{m20250613_083042_init::User, m20261018_081947_leave::ApprovalStatus, setup_user_table_fk, util::{default_user_table_statement, DefaultColumn}};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(default_user_table_statement()
+                .table(AttendanceCorrection::Table)
+                .col(ColumnDef::new(AttendanceCorrection::AttendancePeriodId)
+                    .uuid()
+                    .not_null())
+                .col(ColumnDef::new(AttendanceCorrection::WorkDate)
+                    .date()
+                    .not_null())
+                .col(ColumnDef::new(AttendanceCorrection::CheckedInAt)
+                    .timestamp_with_time_zone()
+                    .not_null())
+                .col(ColumnDef::new(AttendanceCorrection::CheckedOutAt)
+                    .timestamp_with_time_zone()
+                    .not_null())
+                .col(ColumnDef::new(AttendanceCorrection::Reason)
+                    .text()
+                    .not_null())
+                .col(ColumnDef::new(AttendanceCorrection::Status)
+                    .custom(ApprovalStatus::name())
+                    .not_null()
+                    .default(Expr::val("pending").cast_as(ApprovalStatus::name())))
+                .col(ColumnDef::new(AttendanceCorrection::ReviewedBy)
+                    .uuid())
+                .col(ColumnDef::new(AttendanceCorrection::ReviewedAt)
+                    .timestamp_with_time_zone())
+                .col(ColumnDef::new(AttendanceCorrection::ReviewComment)
+                    .text())
+                .take()
+            ).await.unwrap();
+        setup_user_table_fk!(manager, AttendanceCorrection::Table);
+
+        manager.create_foreign_key(ForeignKeyCreateStatement::new()
+            .from(AttendanceCorrection::Table, AttendanceCorrection::AttendancePeriodId)
+            .to(AttendancePeriod::Table, DefaultColumn::Id)
+            .on_delete(ForeignKeyAction::Cascade)
+            .on_update(ForeignKeyAction::Cascade)
+            .take()
+        ).await.unwrap();
+
+        manager.create_foreign_key(ForeignKeyCreateStatement::new()
+            .from(AttendanceCorrection::Table, AttendanceCorrection::ReviewedBy)
+            .to(User::Table, DefaultColumn::Id)
+            .on_delete(ForeignKeyAction::SetNull)
+            .on_update(ForeignKeyAction::Cascade)
+            .take()
+        ).await.unwrap();
+
+        manager
+            .alter_table(TableAlterStatement::new()
+                .table(EmployeeAttendance::Table)
+                .add_column(ColumnDef::new(EmployeeAttendance::AttendanceCorrectionId)
+                    .uuid()) // Backdated through an approved correction when set
+                .add_column(ColumnDef::new(EmployeeAttendance::ApprovedBy)
+                    .uuid())
+                .take()
+            ).await.unwrap();
+
+        manager.create_foreign_key(ForeignKeyCreateStatement::new()
+            .from(EmployeeAttendance::Table, EmployeeAttendance::AttendanceCorrectionId)
+            .to(AttendanceCorrection::Table, DefaultColumn::Id)
+            .on_delete(ForeignKeyAction::SetNull)
+            .on_update(ForeignKeyAction::Cascade)
+            .take()
+        ).await.unwrap();
+
+        manager.create_foreign_key(ForeignKeyCreateStatement::new()
+            .from(EmployeeAttendance::Table, EmployeeAttendance::ApprovedBy)
+            .to(User::Table, DefaultColumn::Id)
+            .on_delete(ForeignKeyAction::SetNull)
+            .on_update(ForeignKeyAction::Cascade)
+            .take()
+        ).await.unwrap();
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(TableAlterStatement::new()
+                .table(EmployeeAttendance::Table)
+                .drop_column(EmployeeAttendance::AttendanceCorrectionId)
+                .drop_column(EmployeeAttendance::ApprovedBy)
+                .take()
+            ).await.unwrap();
+
+        manager.drop_table(
+            TableDropStatement::new()
+                .table(AttendanceCorrection::Table)
+                .take()
+        ).await.unwrap();
+
+        Ok(())
+    }
+}
+
+#[derive(Iden)]
+enum AttendanceCorrection {
+    Table,
+    AttendancePeriodId,
+    WorkDate,
+    CheckedInAt,
+    CheckedOutAt,
+    Reason,
+    Status,
+    ReviewedBy,
+    ReviewedAt,
+    ReviewComment,
+}
+
+#[derive(Iden)]
+enum EmployeeAttendance {
+    Table,
+    AttendanceCorrectionId,
+    ApprovedBy,
+}
+
+#[derive(Iden)]
+enum AttendancePeriod {
+    Table,
+}
@@ -0,0 +1,44 @@
+use sea_orm_migration::prelude::*;
+
+use crate::{setup_user_table_fk, util::default_user_table_statement};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(default_user_table_statement()
+                .table(PublicHoliday::Table)
+                .col(ColumnDef::new(PublicHoliday::Date)
+                    .date()
+                    .unique_key()
+                    .not_null())
+                .col(ColumnDef::new(PublicHoliday::Name)
+                    .text()
+                    .not_null())
+                .take()
+            ).await.unwrap();
+        setup_user_table_fk!(manager, PublicHoliday::Table);
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager.drop_table(
+            TableDropStatement::new()
+                .table(PublicHoliday::Table)
+                .take()
+        ).await.unwrap();
+
+        Ok(())
+    }
+}
+
+#[derive(Iden)]
+enum PublicHoliday {
+    Table,
+    Date,
+    Name,
+}
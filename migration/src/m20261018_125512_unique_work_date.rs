@@ -0,0 +1,115 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+
+        // Keeps the check-in that was checked-out, otherwise the first one
+        db.execute_unprepared(r#"
+            DELETE FROM employee_attendance
+            USING (
+                SELECT id, ROW_NUMBER() OVER (
+                    PARTITION BY created_by, attendance_period_id, work_date
+                    ORDER BY checked_out_at IS NULL, created_at, id
+                ) AS rank
+                FROM employee_attendance
+                WHERE created_by IS NOT NULL
+            ) AS ranked
+            WHERE employee_attendance.id = ranked.id AND ranked.rank > 1
+        "#).await.unwrap();
+
+        // Overtime requests add up, so duplicates are merged into the first one without going past the daily limit
+        db.execute_unprepared(r#"
+            UPDATE employee_overtime
+            SET extra_hours = LEAST(merged.extra_hours, 3)
+            FROM (
+                SELECT first_id, SUM(extra_hours) AS extra_hours
+                FROM (
+                    SELECT extra_hours, FIRST_VALUE(id) OVER (
+                        PARTITION BY created_by, attendance_period_id, work_date
+                        ORDER BY created_at, id
+                    ) AS first_id
+                    FROM employee_overtime
+                    WHERE created_by IS NOT NULL
+                ) AS ranked
+                GROUP BY first_id
+                HAVING COUNT(*) > 1
+            ) AS merged
+            WHERE employee_overtime.id = merged.first_id
+        "#).await.unwrap();
+
+        db.execute_unprepared(r#"
+            DELETE FROM employee_overtime
+            USING (
+                SELECT id, ROW_NUMBER() OVER (
+                    PARTITION BY created_by, attendance_period_id, work_date
+                    ORDER BY created_at, id
+                ) AS rank
+                FROM employee_overtime
+                WHERE created_by IS NOT NULL
+            ) AS ranked
+            WHERE employee_overtime.id = ranked.id AND ranked.rank > 1
+        "#).await.unwrap();
+
+        manager.create_index(IndexCreateStatement::new()
+            .name("idx_employee_attendance_created_by_period_work_date")
+            .table(EmployeeAttendance::Table)
+            .col(CreatedBy)
+            .col(AttendancePeriodId)
+            .col(WorkDate)
+            .unique()
+            .take()
+        ).await.unwrap();
+
+        manager.create_index(IndexCreateStatement::new()
+            .name("idx_employee_overtime_created_by_period_work_date")
+            .table(EmployeeOvertime::Table)
+            .col(CreatedBy)
+            .col(AttendancePeriodId)
+            .col(WorkDate)
+            .unique()
+            .take()
+        ).await.unwrap();
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager.drop_index(IndexDropStatement::new()
+            .name("idx_employee_overtime_created_by_period_work_date")
+            .table(EmployeeOvertime::Table)
+            .to_owned()
+        ).await.unwrap();
+
+        manager.drop_index(IndexDropStatement::new()
+            .name("idx_employee_attendance_created_by_period_work_date")
+            .table(EmployeeAttendance::Table)
+            .to_owned()
+        ).await.unwrap();
+
+        Ok(())
+    }
+}
+
+#[derive(Iden)]
+enum EmployeeAttendance {
+    Table,
+}
+
+#[derive(Iden)]
+enum EmployeeOvertime {
+    Table,
+}
+
+#[derive(Iden)]
+struct CreatedBy;
+
+#[derive(Iden)]
+struct AttendancePeriodId;
+
+#[derive(Iden)]
+struct WorkDate;
@@ -0,0 +1,66 @@
+use sea_orm_migration::{prelude::{extension::postgres::TypeDropStatement, *}, sea_orm::{ActiveEnum, DbBackend, DeriveActiveEnum, EnumIter, Schema}};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let schema = Schema::new(DbBackend::Postgres);
+
+        manager
+            .create_type(
+                schema.create_enum_from_active_enum::<AttendanceType>()
+            ).await.unwrap();
+
+        // Everything recorded so far was a full day at the office
+        manager
+            .alter_table(TableAlterStatement::new()
+                .table(EmployeeAttendance::Table)
+                .add_column(ColumnDef::new(EmployeeAttendance::AttendanceType)
+                    .custom(AttendanceType::name())
+                    .not_null()
+                    .default(Expr::val("office").cast_as(AttendanceType::name())))
+                .take()
+            ).await.unwrap();
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(TableAlterStatement::new()
+                .table(EmployeeAttendance::Table)
+                .drop_column(EmployeeAttendance::AttendanceType)
+                .take()
+            ).await.unwrap();
+
+        manager
+            .drop_type(
+                TypeDropStatement::new()
+                    .name(AttendanceType::name())
+                    .to_owned()
+            ).await.unwrap();
+
+        Ok(())
+    }
+}
+
+#[derive(EnumIter, DeriveActiveEnum)]
+#[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "attendance_type")]
+enum AttendanceType {
+    #[sea_orm(string_value = "office")]
+    Office,
+    #[sea_orm(string_value = "remote")]
+    Remote,
+    #[sea_orm(string_value = "half_day")]
+    HalfDay,
+    #[sea_orm(string_value = "business_trip")]
+    BusinessTrip,
+}
+
+#[derive(Iden)]
+enum EmployeeAttendance {
+    Table,
+    AttendanceType,
+}
@@ -0,0 +1,71 @@
+use sea_orm_migration::{prelude::*, sea_orm::ActiveEnum};
+
+use crate::{m20250613_083042_init::User, m20261018_081947_leave::ApprovalStatus, util::DefaultColumn};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Overtime recorded so far was already paid without sign-off, so it counts as approved
+        manager
+            .alter_table(TableAlterStatement::new()
+                .table(EmployeeOvertime::Table)
+                .add_column(ColumnDef::new(EmployeeOvertime::Status)
+                    .custom(ApprovalStatus::name())
+                    .not_null()
+                    .default(Expr::val("approved").cast_as(ApprovalStatus::name())))
+                .add_column(ColumnDef::new(EmployeeOvertime::ReviewedBy)
+                    .uuid())
+                .add_column(ColumnDef::new(EmployeeOvertime::ReviewedAt)
+                    .timestamp_with_time_zone())
+                .add_column(ColumnDef::new(EmployeeOvertime::ReviewComment)
+                    .text())
+                .take()
+            ).await.unwrap();
+
+        manager
+            .alter_table(TableAlterStatement::new()
+                .table(EmployeeOvertime::Table)
+                .modify_column(ColumnDef::new(EmployeeOvertime::Status)
+                    .custom(ApprovalStatus::name())
+                    .not_null()
+                    .default(Expr::val("pending").cast_as(ApprovalStatus::name())))
+                .take()
+            ).await.unwrap();
+
+        manager.create_foreign_key(ForeignKeyCreateStatement::new()
+            .from(EmployeeOvertime::Table, EmployeeOvertime::ReviewedBy)
+            .to(User::Table, DefaultColumn::Id)
+            .on_delete(ForeignKeyAction::SetNull)
+            .on_update(ForeignKeyAction::Cascade)
+            .take()
+        ).await.unwrap();
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(TableAlterStatement::new()
+                .table(EmployeeOvertime::Table)
+                .drop_column(EmployeeOvertime::Status)
+                .drop_column(EmployeeOvertime::ReviewedBy)
+                .drop_column(EmployeeOvertime::ReviewedAt)
+                .drop_column(EmployeeOvertime::ReviewComment)
+                .take()
+            ).await.unwrap();
+
+        Ok(())
+    }
+}
+
+#[derive(Iden)]
+enum EmployeeOvertime {
+    Table,
+    Status,
+    ReviewedBy,
+    ReviewedAt,
+    ReviewComment,
+}
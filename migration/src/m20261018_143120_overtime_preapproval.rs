@@ -0,0 +1,82 @@
+use sea_orm_migration::{prelude::*, sea_orm::ActiveEnum};
+
+use crate::{m20250613_083042_init::User, m20261018_081947_leave::ApprovalStatus, setup_user_table_fk, util::{default_user_table_statement, DefaultColumn}};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(default_user_table_statement()
+                .table(OvertimePreapproval::Table)
+                .col(ColumnDef::new(OvertimePreapproval::AttendancePeriodId)
+                    .uuid()
+                    .not_null())
+                .col(ColumnDef::new(OvertimePreapproval::WorkDate)
+                    .date()
+                    .not_null())
+                .col(ColumnDef::new(OvertimePreapproval::Reason)
+                    .text()
+                    .not_null())
+                .col(ColumnDef::new(OvertimePreapproval::Status)
+                    .custom(ApprovalStatus::name())
+                    .not_null()
+                    .default(Expr::val("pending").cast_as(ApprovalStatus::name())))
+                .col(ColumnDef::new(OvertimePreapproval::ReviewedBy)
+                    .uuid())
+                .col(ColumnDef::new(OvertimePreapproval::ReviewedAt)
+                    .timestamp_with_time_zone())
+                .col(ColumnDef::new(OvertimePreapproval::ReviewComment)
+                    .text())
+                .take()
+            ).await.unwrap();
+        setup_user_table_fk!(manager, OvertimePreapproval::Table);
+
+        manager.create_foreign_key(ForeignKeyCreateStatement::new()
+            .from(OvertimePreapproval::Table, OvertimePreapproval::AttendancePeriodId)
+            .to(AttendancePeriod::Table, DefaultColumn::Id)
+            .on_delete(ForeignKeyAction::Cascade)
+            .on_update(ForeignKeyAction::Cascade)
+            .take()
+        ).await.unwrap();
+
+        manager.create_foreign_key(ForeignKeyCreateStatement::new()
+            .from(OvertimePreapproval::Table, OvertimePreapproval::ReviewedBy)
+            .to(User::Table, DefaultColumn::Id)
+            .on_delete(ForeignKeyAction::SetNull)
+            .on_update(ForeignKeyAction::Cascade)
+            .take()
+        ).await.unwrap();
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager.drop_table(
+            TableDropStatement::new()
+                .table(OvertimePreapproval::Table)
+                .take()
+        ).await.unwrap();
+
+        Ok(())
+    }
+}
+
+#[derive(Iden)]
+enum OvertimePreapproval {
+    Table,
+    AttendancePeriodId,
+    WorkDate,
+    Reason,
+    Status,
+    ReviewedBy,
+    ReviewedAt,
+    ReviewComment,
+}
+
+#[derive(Iden)]
+enum AttendancePeriod {
+    Table,
+}
@@ -0,0 +1,133 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Overtime recorded in hours so far doesn't know when it happened, so it has no time range
+        manager
+            .alter_table(TableAlterStatement::new()
+                .table(EmployeeOvertime::Table)
+                .add_column(ColumnDef::new(EmployeeOvertime::StartedAt)
+                    .timestamp_with_time_zone())
+                .add_column(ColumnDef::new(EmployeeOvertime::EndedAt)
+                    .timestamp_with_time_zone())
+                .add_column(ColumnDef::new(EmployeeOvertime::Minutes)
+                    .integer())
+                .take()
+            ).await.unwrap();
+
+        manager.get_connection().execute_unprepared(r#"
+            UPDATE employee_overtime SET minutes = extra_hours * 60
+        "#).await.unwrap();
+
+        manager
+            .alter_table(TableAlterStatement::new()
+                .table(EmployeeOvertime::Table)
+                .modify_column(ColumnDef::new(EmployeeOvertime::Minutes)
+                    .integer()
+                    .not_null()) // Rounded, which is what's paid
+                .drop_column(EmployeeOvertime::ExtraHours)
+                .take()
+            ).await.unwrap();
+
+        // A day may have many entries now
+        manager.drop_index(IndexDropStatement::new()
+            .name("idx_employee_overtime_created_by_period_work_date")
+            .table(EmployeeOvertime::Table)
+            .to_owned()
+        ).await.unwrap();
+
+        manager.create_index(IndexCreateStatement::new()
+            .name("idx_employee_overtime_created_by_work_date")
+            .table(EmployeeOvertime::Table)
+            .col(CreatedBy)
+            .col(EmployeeOvertime::WorkDate)
+            .take()
+        ).await.unwrap();
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+
+        manager.drop_index(IndexDropStatement::new()
+            .name("idx_employee_overtime_created_by_work_date")
+            .table(EmployeeOvertime::Table)
+            .to_owned()
+        ).await.unwrap();
+
+        manager
+            .alter_table(TableAlterStatement::new()
+                .table(EmployeeOvertime::Table)
+                .add_column(ColumnDef::new(EmployeeOvertime::ExtraHours)
+                    .tiny_integer())
+                .take()
+            ).await.unwrap();
+
+        // Entries of a day are merged back into the first one, partial hours are rounded up
+        db.execute_unprepared(r#"
+            UPDATE employee_overtime
+            SET extra_hours = merged.extra_hours
+            FROM (
+                SELECT first_id, CEIL(SUM(minutes) / 60.0) AS extra_hours
+                FROM (
+                    SELECT minutes, FIRST_VALUE(id) OVER (
+                        PARTITION BY created_by, attendance_period_id, work_date
+                        ORDER BY created_at, id
+                    ) AS first_id
+                    FROM employee_overtime
+                ) AS ranked
+                GROUP BY first_id
+            ) AS merged
+            WHERE employee_overtime.id = merged.first_id
+        "#).await.unwrap();
+
+        db.execute_unprepared(r#"
+            DELETE FROM employee_overtime WHERE extra_hours IS NULL
+        "#).await.unwrap();
+
+        manager
+            .alter_table(TableAlterStatement::new()
+                .table(EmployeeOvertime::Table)
+                .modify_column(ColumnDef::new(EmployeeOvertime::ExtraHours)
+                    .tiny_integer()
+                    .not_null())
+                .drop_column(EmployeeOvertime::StartedAt)
+                .drop_column(EmployeeOvertime::EndedAt)
+                .drop_column(EmployeeOvertime::Minutes)
+                .take()
+            ).await.unwrap();
+
+        manager.create_index(IndexCreateStatement::new()
+            .name("idx_employee_overtime_created_by_period_work_date")
+            .table(EmployeeOvertime::Table)
+            .col(CreatedBy)
+            .col(AttendancePeriodId)
+            .col(EmployeeOvertime::WorkDate)
+            .unique()
+            .take()
+        ).await.unwrap();
+
+        Ok(())
+    }
+}
+
+#[derive(Iden)]
+enum EmployeeOvertime {
+    Table,
+    ExtraHours,
+    WorkDate,
+    StartedAt,
+    EndedAt,
+    Minutes,
+}
+
+#[derive(Iden)]
+struct CreatedBy;
+
+#[derive(Iden)]
+struct AttendancePeriodId;
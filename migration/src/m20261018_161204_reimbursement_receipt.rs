@@ -0,0 +1,82 @@
+use sea_orm_migration::prelude::*;
+
+use crate::{setup_user_table_fk, util::{default_user_table_statement, DefaultColumn}};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(default_user_table_statement()
+                .table(ReimbursementReceipt::Table)
+                .col(ColumnDef::new(ReimbursementReceipt::EmployeeReimbursementId)
+                    .uuid()
+                    .not_null())
+                .col(ColumnDef::new(ReimbursementReceipt::FileName)
+                    .text()
+                    .not_null())
+                .col(ColumnDef::new(ReimbursementReceipt::ContentType)
+                    .text()
+                    .not_null())
+                .col(ColumnDef::new(ReimbursementReceipt::SizeBytes)
+                    .big_integer()
+                    .not_null())
+                .col(ColumnDef::new(ReimbursementReceipt::Sha256)
+                    .text()
+                    .not_null())
+                .col(ColumnDef::new(ReimbursementReceipt::StorageKey)
+                    .text()
+                    .not_null())
+                .take()
+            ).await.unwrap();
+        setup_user_table_fk!(manager, ReimbursementReceipt::Table);
+
+        manager.create_foreign_key(ForeignKeyCreateStatement::new()
+            .from(ReimbursementReceipt::Table, ReimbursementReceipt::EmployeeReimbursementId)
+            .to(EmployeeReimbursement::Table, DefaultColumn::Id)
+            .on_delete(ForeignKeyAction::Cascade)
+            .on_update(ForeignKeyAction::Cascade)
+            .take()
+        ).await.unwrap();
+
+        // The same file is attached to a claim once
+        manager.create_index(IndexCreateStatement::new()
+            .name("idx_reimbursement_receipt_reimbursement_sha256")
+            .table(ReimbursementReceipt::Table)
+            .col(ReimbursementReceipt::EmployeeReimbursementId)
+            .col(ReimbursementReceipt::Sha256)
+            .unique()
+            .take()
+        ).await.unwrap();
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager.drop_table(
+            TableDropStatement::new()
+                .table(ReimbursementReceipt::Table)
+                .take()
+        ).await.unwrap();
+
+        Ok(())
+    }
+}
+
+#[derive(Iden)]
+enum ReimbursementReceipt {
+    Table,
+    EmployeeReimbursementId,
+    FileName,
+    ContentType,
+    SizeBytes,
+    Sha256,
+    StorageKey,
+}
+
+#[derive(Iden)]
+enum EmployeeReimbursement {
+    Table,
+}
@@ -0,0 +1,109 @@
+use sea_orm_migration::prelude::*;
+
+use crate::{setup_user_table_fk, util::{default_user_table_statement, DefaultColumn}};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(default_user_table_statement()
+                .table(ReimbursementCategory::Table)
+                .col(ColumnDef::new(ReimbursementCategory::Name)
+                    .text()
+                    .not_null()
+                    .unique_key())
+                .col(ColumnDef::new(ReimbursementCategory::PerClaimMaxAmount)
+                    .big_integer())
+                .col(ColumnDef::new(ReimbursementCategory::PeriodMaxAmount)
+                    .big_integer())
+                .col(ColumnDef::new(ReimbursementCategory::YearlyMaxAmount)
+                    .big_integer())
+                .col(ColumnDef::new(ReimbursementCategory::ReceiptRequired)
+                    .boolean()
+                    .not_null()
+                    .default(false))
+                .take()
+            ).await.unwrap();
+        setup_user_table_fk!(manager, ReimbursementCategory::Table);
+
+        // Limits are left for admins to set, except for `Other` that anything can be claimed under
+        manager.get_connection().execute_unprepared(r#"
+            INSERT INTO reimbursement_category (created_at, updated_at, name, per_claim_max_amount, period_max_amount, receipt_required)
+            VALUES (NOW(), NOW(), 'Transport', NULL, NULL, FALSE),
+                (NOW(), NOW(), 'Medical', NULL, NULL, FALSE),
+                (NOW(), NOW(), 'Meals', NULL, NULL, FALSE),
+                (NOW(), NOW(), 'Equipment', NULL, NULL, FALSE),
+                (NOW(), NOW(), 'Other', 500000, 1000000, TRUE)
+        "#).await.unwrap();
+
+        manager
+            .alter_table(TableAlterStatement::new()
+                .table(EmployeeReimbursement::Table)
+                .add_column(ColumnDef::new(EmployeeReimbursement::CategoryId)
+                    .uuid())
+                .take()
+            ).await.unwrap();
+
+        // Claims so far were free-text
+        manager.get_connection().execute_unprepared(r#"
+            UPDATE employee_reimbursement
+            SET category_id = (SELECT id FROM reimbursement_category WHERE name = 'Other')
+        "#).await.unwrap();
+
+        manager
+            .alter_table(TableAlterStatement::new()
+                .table(EmployeeReimbursement::Table)
+                .modify_column(ColumnDef::new(EmployeeReimbursement::CategoryId)
+                    .uuid()
+                    .not_null())
+                .take()
+            ).await.unwrap();
+
+        // Categories with claims can't be deleted
+        manager.create_foreign_key(ForeignKeyCreateStatement::new()
+            .from(EmployeeReimbursement::Table, EmployeeReimbursement::CategoryId)
+            .to(ReimbursementCategory::Table, DefaultColumn::Id)
+            .on_delete(ForeignKeyAction::Restrict)
+            .on_update(ForeignKeyAction::Cascade)
+            .take()
+        ).await.unwrap();
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(TableAlterStatement::new()
+                .table(EmployeeReimbursement::Table)
+                .drop_column(EmployeeReimbursement::CategoryId)
+                .take()
+            ).await.unwrap();
+
+        manager.drop_table(
+            TableDropStatement::new()
+                .table(ReimbursementCategory::Table)
+                .take()
+        ).await.unwrap();
+
+        Ok(())
+    }
+}
+
+#[derive(Iden)]
+enum ReimbursementCategory {
+    Table,
+    Name,
+    PerClaimMaxAmount,
+    PeriodMaxAmount,
+    YearlyMaxAmount,
+    ReceiptRequired,
+}
+
+#[derive(Iden)]
+enum EmployeeReimbursement {
+    Table,
+    CategoryId,
+}
@@ -0,0 +1,104 @@
+use sea_orm_migration::{prelude::{extension::postgres::TypeDropStatement, *}, sea_orm::{ActiveEnum, DbBackend, DeriveActiveEnum, EnumIter, Schema}};
+
+use crate::{m20250613_083042_init::User, util::DefaultColumn};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let schema = Schema::new(DbBackend::Postgres);
+
+        manager
+            .create_type(
+                schema.create_enum_from_active_enum::<ReimbursementStatus>()
+            ).await.unwrap();
+
+        manager
+            .alter_table(TableAlterStatement::new()
+                .table(EmployeeReimbursement::Table)
+                .add_column(ColumnDef::new(EmployeeReimbursement::Status)
+                    .custom(ReimbursementStatus::name())
+                    .not_null()
+                    .default(Expr::val("submitted").cast_as(ReimbursementStatus::name())))
+                .add_column(ColumnDef::new(EmployeeReimbursement::ApprovedAmount)
+                    .big_integer())
+                .add_column(ColumnDef::new(EmployeeReimbursement::ReviewedBy)
+                    .uuid())
+                .add_column(ColumnDef::new(EmployeeReimbursement::ReviewedAt)
+                    .timestamp_with_time_zone())
+                .add_column(ColumnDef::new(EmployeeReimbursement::ReviewComment)
+                    .text())
+                .take()
+            ).await.unwrap();
+
+        // Claims of processed periods were paid in full without sign-off, the rest are left
+        // submitted for an admin to review
+        manager.get_connection().execute_unprepared(r#"
+            UPDATE employee_reimbursement
+            SET status = 'paid',
+                approved_amount = employee_reimbursement.amount
+            FROM attendance_period
+            WHERE attendance_period.id = employee_reimbursement.attendance_period_id
+                AND attendance_period.processed
+        "#).await.unwrap();
+
+        manager.create_foreign_key(ForeignKeyCreateStatement::new()
+            .from(EmployeeReimbursement::Table, EmployeeReimbursement::ReviewedBy)
+            .to(User::Table, DefaultColumn::Id)
+            .on_delete(ForeignKeyAction::SetNull)
+            .on_update(ForeignKeyAction::Cascade)
+            .take()
+        ).await.unwrap();
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(TableAlterStatement::new()
+                .table(EmployeeReimbursement::Table)
+                .drop_column(EmployeeReimbursement::Status)
+                .drop_column(EmployeeReimbursement::ApprovedAmount)
+                .drop_column(EmployeeReimbursement::ReviewedBy)
+                .drop_column(EmployeeReimbursement::ReviewedAt)
+                .drop_column(EmployeeReimbursement::ReviewComment)
+                .take()
+            ).await.unwrap();
+
+        manager
+            .drop_type(
+                TypeDropStatement::new()
+                    .name(ReimbursementStatus::name())
+                    .to_owned()
+            ).await.unwrap();
+
+        Ok(())
+    }
+}
+
+#[derive(EnumIter, DeriveActiveEnum)]
+#[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "reimbursement_status")]
+enum ReimbursementStatus {
+    #[sea_orm(string_value = "submitted")]
+    Submitted,
+    #[sea_orm(string_value = "approved")]
+    Approved,
+    #[sea_orm(string_value = "partially_approved")]
+    PartiallyApproved,
+    #[sea_orm(string_value = "rejected")]
+    Rejected,
+    #[sea_orm(string_value = "paid")]
+    Paid,
+}
+
+#[derive(Iden)]
+enum EmployeeReimbursement {
+    Table,
+    Status,
+    ApprovedAmount,
+    ReviewedBy,
+    ReviewedAt,
+    ReviewComment,
+}
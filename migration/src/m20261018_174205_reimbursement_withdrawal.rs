@@ -0,0 +1,37 @@
+use sea_orm_migration::prelude::{extension::postgres::Type, *};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_type(
+                Type::alter()
+                    .name(ReimbursementStatus::Enum)
+                    .add_value(ReimbursementStatus::Withdrawn)
+                    .if_not_exists() // Kept when rolled back
+                    .to_owned()
+            ).await.unwrap();
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Postgres can't drop a value of an enum, so withdrawn claims are deleted as they were before
+        manager.get_connection().execute_unprepared(r#"
+            DELETE FROM employee_reimbursement
+            WHERE status = 'withdrawn'
+        "#).await.unwrap();
+
+        Ok(())
+    }
+}
+
+#[derive(Iden)]
+enum ReimbursementStatus {
+    #[iden = "reimbursement_status"]
+    Enum,
+    Withdrawn,
+}
@@ -0,0 +1,112 @@
+use sea_orm_migration::prelude::*;
+
+use crate::{setup_user_table_fk, util::default_user_table_statement};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(default_user_table_statement()
+                .table(ExchangeRate::Table)
+                .col(ColumnDef::new(ExchangeRate::Currency)
+                    .text()
+                    .not_null())
+                .col(ColumnDef::new(ExchangeRate::EffectiveFrom)
+                    .date()
+                    .not_null())
+                .col(ColumnDef::new(ExchangeRate::Rate)
+                    .big_integer()
+                    .not_null()) // Millionths of a rupiah per unit of the currency
+                .take()
+            ).await.unwrap();
+        setup_user_table_fk!(manager, ExchangeRate::Table);
+
+        manager.create_index(IndexCreateStatement::new()
+            .name("idx_exchange_rate_currency_effective_from")
+            .table(ExchangeRate::Table)
+            .col(ExchangeRate::Currency)
+            .col(ExchangeRate::EffectiveFrom)
+            .unique()
+            .take()
+        ).await.unwrap();
+
+        manager
+            .alter_table(TableAlterStatement::new()
+                .table(EmployeeReimbursement::Table)
+                .add_column(ColumnDef::new(EmployeeReimbursement::Currency)
+                    .text()
+                    .not_null()
+                    .default("IDR"))
+                .add_column(ColumnDef::new(EmployeeReimbursement::OriginalAmount)
+                    .big_integer()) // In the minor unit of the currency
+                .add_column(ColumnDef::new(EmployeeReimbursement::TransactionDate)
+                    .date())
+                .add_column(ColumnDef::new(EmployeeReimbursement::ExchangeRate)
+                    .big_integer()
+                    .not_null()
+                    .default(1_000_000))
+                .take()
+            ).await.unwrap();
+
+        // Claims so far were in rupiah, made on the day they were submitted
+        manager.get_connection().execute_unprepared(r#"
+            UPDATE employee_reimbursement
+            SET original_amount = amount,
+                transaction_date = created_at::date
+        "#).await.unwrap();
+
+        manager
+            .alter_table(TableAlterStatement::new()
+                .table(EmployeeReimbursement::Table)
+                .modify_column(ColumnDef::new(EmployeeReimbursement::OriginalAmount)
+                    .big_integer()
+                    .not_null())
+                .modify_column(ColumnDef::new(EmployeeReimbursement::TransactionDate)
+                    .date()
+                    .not_null())
+                .take()
+            ).await.unwrap();
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(TableAlterStatement::new()
+                .table(EmployeeReimbursement::Table)
+                .drop_column(EmployeeReimbursement::Currency)
+                .drop_column(EmployeeReimbursement::OriginalAmount)
+                .drop_column(EmployeeReimbursement::TransactionDate)
+                .drop_column(EmployeeReimbursement::ExchangeRate)
+                .take()
+            ).await.unwrap();
+
+        manager.drop_table(
+            TableDropStatement::new()
+                .table(ExchangeRate::Table)
+                .take()
+        ).await.unwrap();
+
+        Ok(())
+    }
+}
+
+#[derive(Iden)]
+enum ExchangeRate {
+    Table,
+    Currency,
+    EffectiveFrom,
+    Rate,
+}
+
+#[derive(Iden)]
+enum EmployeeReimbursement {
+    Table,
+    Currency,
+    OriginalAmount,
+    TransactionDate,
+    ExchangeRate,
+}
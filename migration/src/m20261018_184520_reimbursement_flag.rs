@@ -0,0 +1,118 @@
+use sea_orm_migration::{prelude::{extension::postgres::TypeDropStatement, *}, sea_orm::{ActiveEnum, DbBackend, DeriveActiveEnum, EnumIter, Schema}};
+
+use crate::{util::{default_table_statement, DefaultColumn}};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let schema = Schema::new(DbBackend::Postgres);
+
+        manager
+            .create_type(
+                schema.create_enum_from_active_enum::<ReimbursementFlagKind>()
+            ).await.unwrap();
+
+        manager
+            .create_table(default_table_statement()
+                .table(ReimbursementFlag::Table)
+                .col(ColumnDef::new(ReimbursementFlag::EmployeeReimbursementId)
+                    .uuid()
+                    .not_null())
+                .col(ColumnDef::new(ReimbursementFlag::Kind)
+                    .custom(ReimbursementFlagKind::name())
+                    .not_null())
+                .col(ColumnDef::new(ReimbursementFlag::Reason)
+                    .text()
+                    .not_null())
+                .col(ColumnDef::new(ReimbursementFlag::RelatedReimbursementId)
+                    .uuid()) // The claim it looks duplicated from
+                .take()
+            ).await.unwrap();
+
+        manager.create_foreign_key(ForeignKeyCreateStatement::new()
+            .from(ReimbursementFlag::Table, ReimbursementFlag::EmployeeReimbursementId)
+            .to(EmployeeReimbursement::Table, DefaultColumn::Id)
+            .on_delete(ForeignKeyAction::Cascade)
+            .on_update(ForeignKeyAction::Cascade)
+            .take()
+        ).await.unwrap();
+
+        manager.create_foreign_key(ForeignKeyCreateStatement::new()
+            .from(ReimbursementFlag::Table, ReimbursementFlag::RelatedReimbursementId)
+            .to(EmployeeReimbursement::Table, DefaultColumn::Id)
+            .on_delete(ForeignKeyAction::SetNull)
+            .on_update(ForeignKeyAction::Cascade)
+            .take()
+        ).await.unwrap();
+
+        // Receipts are looked up by their content to find them reused on other claims
+        manager.create_index(IndexCreateStatement::new()
+            .name("idx_reimbursement_receipt_sha256")
+            .table(ReimbursementReceipt::Table)
+            .col(ReimbursementReceipt::Sha256)
+            .take()
+        ).await.unwrap();
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager.drop_index(
+            IndexDropStatement::new()
+                .name("idx_reimbursement_receipt_sha256")
+                .table(ReimbursementReceipt::Table)
+                .to_owned()
+        ).await.unwrap();
+
+        manager.drop_table(
+            TableDropStatement::new()
+                .table(ReimbursementFlag::Table)
+                .take()
+        ).await.unwrap();
+
+        manager
+            .drop_type(
+                TypeDropStatement::new()
+                    .name(ReimbursementFlagKind::name())
+                    .to_owned()
+            ).await.unwrap();
+
+        Ok(())
+    }
+}
+
+#[derive(EnumIter, DeriveActiveEnum)]
+#[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "reimbursement_flag_kind")]
+enum ReimbursementFlagKind {
+    #[sea_orm(string_value = "duplicate")]
+    Duplicate,
+    #[sea_orm(string_value = "receipt_reused")]
+    ReceiptReused,
+    #[sea_orm(string_value = "employee_outlier")]
+    EmployeeOutlier,
+    #[sea_orm(string_value = "category_outlier")]
+    CategoryOutlier,
+}
+
+#[derive(Iden)]
+enum ReimbursementFlag {
+    Table,
+    EmployeeReimbursementId,
+    Kind,
+    Reason,
+    RelatedReimbursementId,
+}
+
+#[derive(Iden)]
+enum ReimbursementReceipt {
+    Table,
+    Sha256,
+}
+
+#[derive(Iden)]
+enum EmployeeReimbursement {
+    Table,
+}
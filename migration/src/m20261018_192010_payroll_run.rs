@@ -0,0 +1,225 @@
+use sea_orm_migration::{prelude::{extension::postgres::TypeDropStatement, *}, sea_orm::{ActiveEnum, DbBackend, DeriveActiveEnum, EnumIter, Schema}};
+
+use crate::{m20250613_083042_init::User, setup_user_table_fk, util::{default_table_statement, default_user_table_statement, DefaultColumn}};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let schema = Schema::new(DbBackend::Postgres);
+
+        manager
+            .create_type(
+                schema.create_enum_from_active_enum::<PayslipLineKind>()
+            ).await.unwrap();
+
+        manager
+            .create_table(default_user_table_statement()
+                .table(PayrollRun::Table)
+                .col(ColumnDef::new(PayrollRun::AttendancePeriodId)
+                    .uuid()
+                    .not_null()
+                    .unique_key())
+                .col(ColumnDef::new(PayrollRun::EngineVersion)
+                    .integer()
+                    .not_null())
+                .col(ColumnDef::new(PayrollRun::TotalTakeHome)
+                    .big_integer()
+                    .not_null())
+                .col(ColumnDef::new(PayrollRun::Backfilled)
+                    .boolean()
+                    .not_null()
+                    .default(false)) // Computed after the fact for periods processed before runs were kept
+                .take()
+            ).await.unwrap();
+        setup_user_table_fk!(manager, PayrollRun::Table);
+
+        manager.create_foreign_key(ForeignKeyCreateStatement::new()
+            .from(PayrollRun::Table, PayrollRun::AttendancePeriodId)
+            .to(AttendancePeriod::Table, DefaultColumn::Id)
+            .on_delete(ForeignKeyAction::Restrict)
+            .on_update(ForeignKeyAction::Cascade)
+            .take()
+        ).await.unwrap();
+
+        manager
+            .create_table(default_table_statement()
+                .table(Payslip::Table)
+                .col(ColumnDef::new(Payslip::PayrollRunId)
+                    .uuid()
+                    .not_null())
+                .col(ColumnDef::new(Payslip::EmployeeId)
+                    .uuid()
+                    .not_null())
+                .col(ColumnDef::new(Payslip::BaseSalary)
+                    .big_integer()
+                    .not_null())
+                .col(ColumnDef::new(Payslip::ProratedAmount)
+                    .big_integer()
+                    .not_null())
+                .col(ColumnDef::new(Payslip::OvertimeTotal)
+                    .big_integer()
+                    .not_null())
+                .col(ColumnDef::new(Payslip::ReimbursementTotal)
+                    .big_integer()
+                    .not_null())
+                .col(ColumnDef::new(Payslip::DeductionTotal)
+                    .big_integer()
+                    .not_null())
+                .col(ColumnDef::new(Payslip::TakeHomePay)
+                    .big_integer()
+                    .not_null())
+                .col(ColumnDef::new(Payslip::Snapshot)
+                    .json_binary()
+                    .not_null()) // The whole payslip as it was computed
+                .take()
+            ).await.unwrap();
+
+        manager.create_foreign_key(ForeignKeyCreateStatement::new()
+            .from(Payslip::Table, Payslip::PayrollRunId)
+            .to(PayrollRun::Table, DefaultColumn::Id)
+            .on_delete(ForeignKeyAction::Cascade)
+            .on_update(ForeignKeyAction::Cascade)
+            .take()
+        ).await.unwrap();
+
+        manager.create_foreign_key(ForeignKeyCreateStatement::new()
+            .from(Payslip::Table, Payslip::EmployeeId)
+            .to(User::Table, DefaultColumn::Id)
+            .on_delete(ForeignKeyAction::Restrict)
+            .on_update(ForeignKeyAction::Cascade)
+            .take()
+        ).await.unwrap();
+
+        manager.create_index(IndexCreateStatement::new()
+            .name("idx_payslip_payroll_run_id_employee_id")
+            .table(Payslip::Table)
+            .col(Payslip::PayrollRunId)
+            .col(Payslip::EmployeeId)
+            .unique()
+            .take()
+        ).await.unwrap();
+
+        manager
+            .create_table(default_table_statement()
+                .table(PayslipLine::Table)
+                .col(ColumnDef::new(PayslipLine::PayslipId)
+                    .uuid()
+                    .not_null())
+                .col(ColumnDef::new(PayslipLine::Position)
+                    .integer()
+                    .not_null())
+                .col(ColumnDef::new(PayslipLine::Kind)
+                    .custom(PayslipLineKind::name())
+                    .not_null())
+                .col(ColumnDef::new(PayslipLine::Description)
+                    .text()
+                    .not_null())
+                .col(ColumnDef::new(PayslipLine::Amount)
+                    .big_integer()
+                    .not_null()) // Deductions are positive as well, `kind` tells which way it goes
+                .take()
+            ).await.unwrap();
+
+        manager.create_foreign_key(ForeignKeyCreateStatement::new()
+            .from(PayslipLine::Table, PayslipLine::PayslipId)
+            .to(Payslip::Table, DefaultColumn::Id)
+            .on_delete(ForeignKeyAction::Cascade)
+            .on_update(ForeignKeyAction::Cascade)
+            .take()
+        ).await.unwrap();
+
+        manager.create_index(IndexCreateStatement::new()
+            .name("idx_payslip_line_payslip_id_position")
+            .table(PayslipLine::Table)
+            .col(PayslipLine::PayslipId)
+            .col(PayslipLine::Position)
+            .unique()
+            .take()
+        ).await.unwrap();
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager.drop_table(
+            TableDropStatement::new()
+                .table(PayslipLine::Table)
+                .take()
+        ).await.unwrap();
+
+        manager.drop_table(
+            TableDropStatement::new()
+                .table(Payslip::Table)
+                .take()
+        ).await.unwrap();
+
+        manager.drop_table(
+            TableDropStatement::new()
+                .table(PayrollRun::Table)
+                .take()
+        ).await.unwrap();
+
+        manager
+            .drop_type(
+                TypeDropStatement::new()
+                    .name(PayslipLineKind::name())
+                    .to_owned()
+            ).await.unwrap();
+
+        Ok(())
+    }
+}
+
+#[derive(EnumIter, DeriveActiveEnum)]
+#[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "payslip_line_kind")]
+enum PayslipLineKind {
+    #[sea_orm(string_value = "salary")]
+    Salary,
+    #[sea_orm(string_value = "overtime")]
+    Overtime,
+    #[sea_orm(string_value = "reimbursement")]
+    Reimbursement,
+    #[sea_orm(string_value = "deduction")]
+    Deduction,
+}
+
+#[derive(Iden)]
+enum PayrollRun {
+    Table,
+    AttendancePeriodId,
+    EngineVersion,
+    TotalTakeHome,
+    Backfilled,
+}
+
+#[derive(Iden)]
+enum Payslip {
+    Table,
+    PayrollRunId,
+    EmployeeId,
+    BaseSalary,
+    ProratedAmount,
+    OvertimeTotal,
+    ReimbursementTotal,
+    DeductionTotal,
+    TakeHomePay,
+    Snapshot,
+}
+
+#[derive(Iden)]
+enum PayslipLine {
+    Table,
+    PayslipId,
+    Position,
+    Kind,
+    Description,
+    Amount,
+}
+
+#[derive(Iden)]
+enum AttendancePeriod {
+    Table,
+}
@@ -0,0 +1,151 @@
+use sea_orm_migration::{prelude::{extension::postgres::{Type, TypeDropStatement}, *}, sea_orm::{ActiveEnum, DbBackend, DeriveActiveEnum, EnumIter, Schema}};
+
+use crate::{m20250613_083042_init::User, setup_user_table_fk, util::{default_user_table_statement, DefaultColumn}};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let schema = Schema::new(DbBackend::Postgres);
+
+        manager
+            .create_type(
+                schema.create_enum_from_active_enum::<PtkpStatus>()
+            ).await.unwrap();
+
+        manager
+            .create_table(default_user_table_statement()
+                .table(TaxProfile::Table)
+                .col(ColumnDef::new(TaxProfile::UserId)
+                    .uuid()
+                    .not_null()
+                    .unique_key())
+                .col(ColumnDef::new(TaxProfile::PtkpStatus)
+                    .custom(PtkpStatus::name())
+                    .not_null())
+                .col(ColumnDef::new(TaxProfile::Npwp)
+                    .text()) // Digits only, absent for employees without a taxpayer number
+                .take()
+            ).await.unwrap();
+        setup_user_table_fk!(manager, TaxProfile::Table);
+
+        manager.create_foreign_key(ForeignKeyCreateStatement::new()
+            .from(TaxProfile::Table, TaxProfile::UserId)
+            .to(User::Table, DefaultColumn::Id)
+            .on_delete(ForeignKeyAction::Cascade)
+            .on_update(ForeignKeyAction::Cascade)
+            .take()
+        ).await.unwrap();
+
+        manager
+            .alter_table(TableAlterStatement::new()
+                .table(Payslip::Table)
+                .add_column(ColumnDef::new(Payslip::TaxableIncome)
+                    .big_integer()
+                    .not_null()
+                    .default(0))
+                .add_column(ColumnDef::new(Payslip::IncomeTax)
+                    .big_integer()
+                    .not_null()
+                    .default(0))
+                .take()
+            ).await.unwrap();
+
+        // What was withheld over is paid back on top of the take-home pay, not deducted
+        manager
+            .alter_type(
+                Type::alter()
+                    .name(PayslipLineKind::Enum)
+                    .add_value(PayslipLineKind::TaxRefund)
+                    .if_not_exists() // Kept when rolled back
+                    .to_owned()
+            ).await.unwrap();
+
+        // Nothing was withheld so far, and the only deduction was lateness which isn't earned
+        manager
+            .exec_stmt(Query::update()
+                .table(Payslip::Table)
+                .value(Payslip::TaxableIncome, Expr::cust("prorated_amount + overtime_total - deduction_total"))
+                .to_owned()
+            ).await.unwrap();
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Postgres can't drop a value of an enum, so refunds go back to being negative deductions
+        manager.get_connection().execute_unprepared(r#"
+            UPDATE payslip_line
+            SET kind = 'deduction', amount = -amount
+            WHERE kind = 'tax_refund'
+        "#).await.unwrap();
+
+        manager
+            .alter_table(TableAlterStatement::new()
+                .table(Payslip::Table)
+                .drop_column(Payslip::TaxableIncome)
+                .drop_column(Payslip::IncomeTax)
+                .take()
+            ).await.unwrap();
+
+        manager.drop_table(
+            TableDropStatement::new()
+                .table(TaxProfile::Table)
+                .take()
+        ).await.unwrap();
+
+        manager
+            .drop_type(
+                TypeDropStatement::new()
+                    .name(PtkpStatus::name())
+                    .to_owned()
+            ).await.unwrap();
+
+        Ok(())
+    }
+}
+
+#[derive(EnumIter, DeriveActiveEnum)]
+#[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "ptkp_status")]
+enum PtkpStatus {
+    #[sea_orm(string_value = "tk0")]
+    Tk0,
+    #[sea_orm(string_value = "tk1")]
+    Tk1,
+    #[sea_orm(string_value = "tk2")]
+    Tk2,
+    #[sea_orm(string_value = "tk3")]
+    Tk3,
+    #[sea_orm(string_value = "k0")]
+    K0,
+    #[sea_orm(string_value = "k1")]
+    K1,
+    #[sea_orm(string_value = "k2")]
+    K2,
+    #[sea_orm(string_value = "k3")]
+    K3,
+}
+
+#[derive(Iden)]
+enum TaxProfile {
+    Table,
+    UserId,
+    PtkpStatus,
+    Npwp,
+}
+
+#[derive(Iden)]
+enum PayslipLineKind {
+    #[iden = "payslip_line_kind"]
+    Enum,
+    TaxRefund,
+}
+
+#[derive(Iden)]
+enum Payslip {
+    Table,
+    TaxableIncome,
+    IncomeTax,
+}
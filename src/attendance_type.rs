@@ -0,0 +1,63 @@
+use chrono::{NaiveDate, NaiveDateTime, TimeDelta};
+
+use crate::{consts::{ATTENDANCE_TYPE_MAX_DAYS, ATTENDANCE_TYPE_WEIGHTS}, entity::sea_orm_active_enums::AttendanceType, schedule::Schedule};
+
+impl AttendanceType {
+    /// Share of a day's salary earned in percent, see [`ATTENDANCE_TYPE_WEIGHTS`]
+    pub fn weight_percent(&self) -> i64 {
+        ATTENDANCE_TYPE_WEIGHTS.iter()
+            .find(|(attendance_type, _)| attendance_type == self)
+            .map(|(_, weight)| *weight)
+            .unwrap_or(100)
+    }
+
+    /// Most days allowed within a period, `None` when the attendance type is unlimited
+    pub fn max_days(&self) -> Option<u64> {
+        ATTENDANCE_TYPE_MAX_DAYS.iter()
+            .find(|(attendance_type, _)| attendance_type == self)
+            .map(|(_, days)| *days)
+    }
+
+    /// Check-in policies only apply when the employee is expected to be at the office, the other
+    /// types are capped by [`Self::max_days`] instead
+    pub fn requires_presence(&self) -> bool {
+        matches!(self, AttendanceType::Office | AttendanceType::HalfDay)
+    }
+
+    /// Half-days are done halfway through the shift, which is when leaving early starts to count
+    pub fn shift_end_at(&self, schedule: &Schedule, work_date: NaiveDate) -> NaiveDateTime {
+        match self {
+            AttendanceType::HalfDay => schedule.shift_start_at(work_date) + TimeDelta::minutes(schedule.shift_minutes() / 2),
+            _ => schedule.shift_end_at(work_date),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveTime;
+
+    use super::*;
+
+    #[test]
+    fn test_attendance_type() {
+        assert_eq!(AttendanceType::Office.weight_percent(), 100);
+        assert_eq!(AttendanceType::HalfDay.weight_percent(), 50);
+
+        assert_eq!(AttendanceType::Remote.max_days(), Some(8));
+        assert_eq!(AttendanceType::BusinessTrip.max_days(), Some(5));
+        assert_eq!(AttendanceType::Office.max_days(), None);
+
+        assert!(AttendanceType::HalfDay.requires_presence());
+        assert!(!AttendanceType::Remote.requires_presence());
+    }
+
+    #[test]
+    fn test_shift_end_at() {
+        let work_date = NaiveDate::from_ymd_opt(2024, 6, 3).unwrap();
+        let schedule = Schedule::default();
+
+        assert_eq!(AttendanceType::Office.shift_end_at(&schedule, work_date), work_date.and_time(NaiveTime::from_hms_opt(17, 0, 0).unwrap()));
+        assert_eq!(AttendanceType::HalfDay.shift_end_at(&schedule, work_date), work_date.and_time(NaiveTime::from_hms_opt(13, 0, 0).unwrap()));
+    }
+}
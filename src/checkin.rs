@@ -0,0 +1,190 @@
+use std::net::IpAddr;
+
+use ipnet::IpNet;
+use serde::{Deserialize, Serialize};
+
+use crate::entity::checkin_policy;
+
+const EARTH_RADIUS_METERS: f64 = 6_371_000.0;
+
+/// Proxies whose `X-Forwarded-For` header is believed when telling who the client is
+#[derive(Debug, Clone, Default)]
+pub struct TrustedProxies(pub Vec<IpNet>);
+
+impl TrustedProxies {
+    pub fn contains(&self, ip: &IpAddr) -> bool {
+        self.0.iter().any(|network| network.contains(ip))
+    }
+
+    /// Address of the client behind any trusted proxies
+    ///
+    /// Every proxy appends the address it received the request from, so the header is walked
+    /// from the right and stops at the first hop that isn't a trusted proxy.
+    /// A client connecting directly can't spoof its address with the header this way
+    pub fn client_ip(&self, peer: Option<IpAddr>, forwarded_for: Option<&str>) -> Option<IpAddr> {
+        let mut client = peer?;
+
+        for hop in forwarded_for.into_iter().flat_map(|header| header.rsplit(',')) {
+            if !self.contains(&client) {
+                break
+            }
+
+            let Ok(hop) = hop.trim().parse() else {
+                break
+            };
+
+            client = hop;
+        }
+
+        Some(client)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Coordinate {
+    pub latitude: f64,
+    pub longitude: f64,
+}
+
+impl Coordinate {
+    pub fn is_valid(&self) -> bool {
+        (-90.0..=90.0).contains(&self.latitude) && (-180.0..=180.0).contains(&self.longitude)
+    }
+
+    /// Great-circle distance using the haversine formula
+    pub fn distance_meters(&self, other: &Coordinate) -> f64 {
+        let (lat1, lat2) = (self.latitude.to_radians(), other.latitude.to_radians());
+        let d_lat = lat2 - lat1;
+        let d_lon = (other.longitude - self.longitude).to_radians();
+
+        let a = (d_lat / 2.0).sin().powi(2) + lat1.cos() * lat2.cos() * (d_lon / 2.0).sin().powi(2);
+
+        2.0 * EARTH_RADIUS_METERS * a.sqrt().asin()
+    }
+}
+
+/// Accepts either a CIDR or a single address, which is treated as a network of its own
+pub fn parse_network(network: &str) -> Option<IpNet> {
+    network.parse::<IpNet>().ok()
+        .or_else(|| network.parse::<IpAddr>().ok().map(IpNet::from))
+}
+
+/// Checks a check-in against a single policy, returning why it isn't allowed
+pub fn check(policy: &checkin_policy::Model, client_ip: Option<IpAddr>, location: Option<Coordinate>) -> Result<(), &'static str> {
+    if !policy.allowed_networks.is_empty() {
+        let allowed = client_ip.is_some_and(|ip|
+            policy.allowed_networks.iter()
+                .filter_map(|network| parse_network(network))
+                .any(|network| network.contains(&ip))
+        );
+
+        if !allowed {
+            return Err("check-in is not allowed from this network")
+        }
+    }
+
+    if let (Some(latitude), Some(longitude), Some(radius_meters)) = (policy.latitude, policy.longitude, policy.radius_meters) {
+        let Some(location) = location else {
+            return Err("location is required to check-in")
+        };
+
+        if location.distance_meters(&Coordinate { latitude, longitude }) > radius_meters {
+            return Err("check-in location is outside the allowed area")
+        }
+    }
+
+    Ok(())
+}
+
+/// A check-in is allowed when there is no policy or any of them allows it,
+/// otherwise the reason of the first policy is given
+pub fn check_all(policies: &[checkin_policy::Model], client_ip: Option<IpAddr>, location: Option<Coordinate>) -> Result<(), &'static str> {
+    let mut rejection = Ok(());
+
+    for policy in policies {
+        match check(policy, client_ip, location) {
+            Ok(()) => return Ok(()),
+            Err(reason) => rejection = rejection.and(Err(reason)),
+        }
+    }
+
+    rejection
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Local;
+    use uuid::Uuid;
+
+    use super::*;
+
+    fn policy(allowed_networks: &[&str], geofence: Option<(f64, f64, f64)>) -> checkin_policy::Model {
+        checkin_policy::Model {
+            id: Uuid::nil(),
+            created_at: Local::now().fixed_offset(),
+            updated_at: Local::now().fixed_offset(),
+            created_by: None,
+            updated_by: None,
+            name: "Office".to_string(),
+            attendance_period_id: None,
+            allowed_networks: allowed_networks.iter().map(|network| network.to_string()).collect(),
+            latitude: geofence.map(|g| g.0),
+            longitude: geofence.map(|g| g.1),
+            radius_meters: geofence.map(|g| g.2),
+        }
+    }
+
+    #[test]
+    fn test_client_ip() {
+        let proxies = TrustedProxies(vec!["10.0.0.0/8".parse().unwrap()]);
+        let ip = |ip: &str| Some(ip.parse::<IpAddr>().unwrap());
+
+        // Directly connected clients can't claim another address
+        assert_eq!(proxies.client_ip(ip("203.0.113.7"), Some("198.51.100.1")), ip("203.0.113.7"));
+        assert_eq!(proxies.client_ip(ip("10.0.0.2"), None), ip("10.0.0.2"));
+        assert_eq!(proxies.client_ip(ip("10.0.0.2"), Some("203.0.113.7")), ip("203.0.113.7"));
+        // Only the hops appended by trusted proxies are believed
+        assert_eq!(proxies.client_ip(ip("10.0.0.2"), Some("198.51.100.1, 203.0.113.7, 10.0.0.3")), ip("203.0.113.7"));
+        assert_eq!(proxies.client_ip(ip("10.0.0.2"), Some("garbage")), ip("10.0.0.2"));
+        assert_eq!(proxies.client_ip(None, Some("203.0.113.7")), None);
+    }
+
+    #[test]
+    fn test_distance_meters() {
+        // Monas and Bundaran HI in Jakarta are roughly 2.2 km apart
+        let monas = Coordinate { latitude: -6.175392, longitude: 106.827153 };
+        let bundaran_hi = Coordinate { latitude: -6.194927, longitude: 106.823023 };
+
+        let distance = monas.distance_meters(&bundaran_hi);
+        assert!((2_100.0..2_400.0).contains(&distance), "{distance}");
+        assert_eq!(monas.distance_meters(&monas), 0.0);
+
+        assert!(monas.is_valid());
+        assert!(!Coordinate { latitude: 91.0, longitude: 0.0 }.is_valid());
+    }
+
+    #[test]
+    fn test_check() {
+        let office = Coordinate { latitude: -6.175392, longitude: 106.827153 };
+        let ip = "192.168.1.20".parse().ok();
+
+        assert_eq!(check(&policy(&["192.168.1.0/24"], None), ip, None), Ok(()));
+        assert_eq!(check(&policy(&["192.168.2.0/24", "192.168.1.20"], None), ip, None), Ok(()));
+        assert!(check(&policy(&["192.168.2.0/24"], None), ip, None).is_err());
+        assert!(check(&policy(&["192.168.1.0/24"], None), None, None).is_err());
+
+        let geofenced = policy(&[], Some((office.latitude, office.longitude, 200.0)));
+        assert_eq!(check(&geofenced, ip, Some(office)), Ok(()));
+        assert_eq!(check(&geofenced, ip, None), Err("location is required to check-in"));
+        assert_eq!(check(&geofenced, ip, Some(Coordinate { latitude: -6.194927, longitude: 106.823023 })), Err("check-in location is outside the allowed area"));
+    }
+
+    #[test]
+    fn test_check_all() {
+        let ip = "192.168.1.20".parse().ok();
+
+        assert_eq!(check_all(&[], ip, None), Ok(()));
+        assert_eq!(check_all(&[policy(&["10.0.0.0/8"], None), policy(&["192.168.1.0/24"], None)], ip, None), Ok(()));
+        assert_eq!(check_all(&[policy(&["10.0.0.0/8"], None), policy(&[], Some((0.0, 0.0, 100.0)))], ip, None), Err("check-in is not allowed from this network"));
+    }
+}
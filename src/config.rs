@@ -1,7 +1,9 @@
-use std::{env, net::{SocketAddr, ToSocketAddrs as _}};
+use std::{env, net::{SocketAddr, ToSocketAddrs as _}, path::PathBuf, sync::Arc};
 
 use sea_orm::ConnectOptions;
-use tracing::info;
+use tracing::{error, info};
+
+use crate::{checkin::{self, TrustedProxies}, lateness::LatenessPolicy, overtime::{FlatOvertimeRate, NonWorkingDayOvertime, OvertimeLimits, OvertimeRatePolicy, OvertimeRounding, RoundingMode, StatutoryOvertimeRate}, receipt::{LocalReceiptStorage, ReceiptLimits, ReceiptStorage, S3Credentials, S3ReceiptStorage}, reimbursement::ReimbursementScreening};
 
 pub struct Config {
     pub host_address: SocketAddr,
@@ -9,6 +11,24 @@ pub struct Config {
     pub database_opt: ConnectOptions,
     
     pub jwt_key: String,
+
+    pub lateness_policy: LatenessPolicy,
+
+    pub trusted_proxies: TrustedProxies,
+
+    pub overtime_rate_policy: Arc<dyn OvertimeRatePolicy>,
+
+    pub non_working_day_overtime: NonWorkingDayOvertime,
+
+    pub overtime_rounding: OvertimeRounding,
+
+    pub overtime_limits: OvertimeLimits,
+
+    pub receipt_storage: Arc<dyn ReceiptStorage>,
+
+    pub receipt_limits: ReceiptLimits,
+
+    pub reimbursement_screening: ReimbursementScreening,
 }
 
 pub fn load() -> Config {
@@ -16,6 +36,15 @@ pub fn load() -> Config {
         host_address: load_host_address(),
         database_opt: load_database_opt().into(),
         jwt_key: load_jwt_key(),
+        lateness_policy: load_lateness_policy(),
+        trusted_proxies: load_trusted_proxies(),
+        overtime_rate_policy: load_overtime_rate_policy(),
+        non_working_day_overtime: load_non_working_day_overtime(),
+        overtime_rounding: load_overtime_rounding(),
+        overtime_limits: load_overtime_limits(),
+        receipt_storage: load_receipt_storage(),
+        receipt_limits: load_receipt_limits(),
+        reimbursement_screening: load_reimbursement_screening(),
     }
 }
 
@@ -44,3 +73,192 @@ fn load_jwt_key() -> String {
     
     var
 }
+
+fn load_lateness_policy() -> LatenessPolicy {
+    info!("Loading environment `LATENESS_GRACE_MINUTES`, `LATENESS_WARNING_MINUTES` and `LATENESS_DEDUCTION_MINUTES`");
+
+    let default = LatenessPolicy::default();
+
+    LatenessPolicy {
+        grace_minutes: load_minutes("LATENESS_GRACE_MINUTES").unwrap_or(default.grace_minutes),
+        warning_minutes: load_optional_minutes("LATENESS_WARNING_MINUTES", default.warning_minutes),
+        deduction_minutes: load_optional_minutes("LATENESS_DEDUCTION_MINUTES", default.deduction_minutes),
+    }
+}
+
+fn load_minutes(key: &str) -> Option<i64> {
+    let var = env::var(key).ok()?;
+
+    Some(var.parse().unwrap_or_else(|_| panic!("`{key}` is not a valid number of minutes")))
+}
+
+/// Minutes of a threshold that `off` or an empty value turns off, `default` is kept when unset
+fn load_optional_minutes(key: &str, default: Option<i64>) -> Option<i64> {
+    let Ok(var) = env::var(key) else {
+        return default
+    };
+
+    match var.trim() {
+        "" | "off" => None,
+        var => var.parse().map(Some).unwrap_or_else(|_| {
+            error!("`{key}` is neither a number of minutes nor `off`, got `{var}`, the default is used instead");
+            default
+        }),
+    }
+}
+
+fn load_trusted_proxies() -> TrustedProxies {
+    info!("Loading environment `TRUSTED_PROXIES`");
+
+    let var = env::var("TRUSTED_PROXIES").unwrap_or_default();
+
+    TrustedProxies(
+        var.split(',')
+            .map(str::trim)
+            .filter(|network| !network.is_empty())
+            .map(|network| checkin::parse_network(network).unwrap_or_else(|| panic!("`TRUSTED_PROXIES` contains an invalid network `{network}`")))
+            .collect()
+    )
+}
+
+fn load_overtime_rate_policy() -> Arc<dyn OvertimeRatePolicy> {
+    info!("Loading environment `OVERTIME_RATE_POLICY`");
+
+    match env::var("OVERTIME_RATE_POLICY").as_deref() {
+        Ok("flat") | Err(_) => Arc::new(FlatOvertimeRate),
+        Ok("statutory") => Arc::new(StatutoryOvertimeRate),
+        Ok(policy) => panic!("`OVERTIME_RATE_POLICY` must be either `flat` or `statutory`, got `{policy}`"),
+    }
+}
+
+fn load_non_working_day_overtime() -> NonWorkingDayOvertime {
+    info!("Loading environment `NON_WORKING_DAY_OVERTIME_PREAPPROVAL`");
+
+    let require_preapproval = match env::var("NON_WORKING_DAY_OVERTIME_PREAPPROVAL").as_deref() {
+        Ok("true") => true,
+        Ok("false") | Err(_) => false,
+        Ok(var) => panic!("`NON_WORKING_DAY_OVERTIME_PREAPPROVAL` must be either `true` or `false`, got `{var}`"),
+    };
+
+    NonWorkingDayOvertime { require_preapproval }
+}
+
+fn load_overtime_rounding() -> OvertimeRounding {
+    info!("Loading environment `OVERTIME_ROUNDING_MINUTES` and `OVERTIME_ROUNDING_MODE`");
+
+    let default = OvertimeRounding::default();
+
+    let minutes = load_minutes("OVERTIME_ROUNDING_MINUTES").unwrap_or(default.minutes);
+    if minutes <= 0 {
+        panic!("`OVERTIME_ROUNDING_MINUTES` must be more than 0");
+    }
+
+    let mode = match env::var("OVERTIME_ROUNDING_MODE").as_deref() {
+        Ok("up") => RoundingMode::Up,
+        Ok("down") => RoundingMode::Down,
+        Ok("nearest") => RoundingMode::Nearest,
+        Err(_) => default.mode,
+        Ok(mode) => panic!("`OVERTIME_ROUNDING_MODE` must be either `up`, `down` or `nearest`, got `{mode}`"),
+    };
+
+    OvertimeRounding { minutes, mode }
+}
+
+fn load_overtime_limits() -> OvertimeLimits {
+    info!("Loading environment `OVERTIME_WORKDAY_MAX_MINUTES`, `OVERTIME_NON_WORKING_DAY_MAX_MINUTES`, `OVERTIME_WEEKLY_MAX_MINUTES` and `OVERTIME_PERIOD_MAX_MINUTES`");
+
+    let default = OvertimeLimits::default();
+
+    OvertimeLimits {
+        workday_minutes: load_minutes("OVERTIME_WORKDAY_MAX_MINUTES").unwrap_or(default.workday_minutes),
+        non_working_day_minutes: load_minutes("OVERTIME_NON_WORKING_DAY_MAX_MINUTES").unwrap_or(default.non_working_day_minutes),
+        weekly_minutes: load_optional_minutes("OVERTIME_WEEKLY_MAX_MINUTES", default.weekly_minutes),
+        period_minutes: load_optional_minutes("OVERTIME_PERIOD_MAX_MINUTES", default.period_minutes),
+    }
+}
+
+fn load_receipt_storage() -> Arc<dyn ReceiptStorage> {
+    info!("Loading environment `RECEIPT_STORAGE`");
+
+    match env::var("RECEIPT_STORAGE").as_deref() {
+        Ok("local") | Err(_) => {
+            info!("Loading environment `RECEIPT_STORAGE_PATH`");
+
+            Arc::new(LocalReceiptStorage {
+                root: PathBuf::from(env::var("RECEIPT_STORAGE_PATH").unwrap_or_else(|_| "receipts".to_string())),
+            })
+        },
+        Ok("s3") => {
+            info!("Loading environment `RECEIPT_S3_ENDPOINT`, `RECEIPT_S3_BUCKET`, `RECEIPT_S3_REGION`, `RECEIPT_S3_ACCESS_KEY_ID` and `RECEIPT_S3_SECRET_ACCESS_KEY`");
+
+            let endpoint = env::var("RECEIPT_S3_ENDPOINT").expect("Environment `RECEIPT_S3_ENDPOINT` is required to be set")
+                .parse().expect("`RECEIPT_S3_ENDPOINT` is not a valid URL");
+
+            Arc::new(S3ReceiptStorage::new(
+                endpoint,
+                env::var("RECEIPT_S3_BUCKET").expect("Environment `RECEIPT_S3_BUCKET` is required to be set"),
+                env::var("RECEIPT_S3_REGION").unwrap_or_else(|_| "us-east-1".to_string()),
+                S3Credentials {
+                    access_key_id: env::var("RECEIPT_S3_ACCESS_KEY_ID").expect("Environment `RECEIPT_S3_ACCESS_KEY_ID` is required to be set"),
+                    secret_access_key: env::var("RECEIPT_S3_SECRET_ACCESS_KEY").expect("Environment `RECEIPT_S3_SECRET_ACCESS_KEY` is required to be set"),
+                },
+            ))
+        },
+        Ok(storage) => panic!("`RECEIPT_STORAGE` must be either `local` or `s3`, got `{storage}`"),
+    }
+}
+
+fn load_receipt_limits() -> ReceiptLimits {
+    info!("Loading environment `RECEIPT_MAX_BYTES`, `RECEIPT_MAX_FILES` and `RECEIPT_MAX_TOTAL_BYTES`");
+
+    let default = ReceiptLimits::default();
+
+    let max_bytes = match env::var("RECEIPT_MAX_BYTES") {
+        Ok(var) => var.parse().expect("`RECEIPT_MAX_BYTES` is not a valid number of bytes"),
+        Err(_) => default.max_bytes,
+    };
+
+    let max_files = match env::var("RECEIPT_MAX_FILES") {
+        Ok(var) => var.parse().expect("`RECEIPT_MAX_FILES` is not a valid number of files"),
+        Err(_) => default.max_files,
+    };
+    if max_files == 0 {
+        panic!("`RECEIPT_MAX_FILES` must be more than 0");
+    }
+
+    let max_total_bytes = match env::var("RECEIPT_MAX_TOTAL_BYTES") {
+        Ok(var) => var.parse().expect("`RECEIPT_MAX_TOTAL_BYTES` is not a valid number of bytes"),
+        Err(_) => default.max_total_bytes,
+    };
+
+    ReceiptLimits { max_bytes, max_files, max_total_bytes }
+}
+
+fn load_reimbursement_screening() -> ReimbursementScreening {
+    info!("Loading environment `REIMBURSEMENT_DUPLICATE_WINDOW_DAYS`, `REIMBURSEMENT_OUTLIER_PERCENT` and `REIMBURSEMENT_OUTLIER_MIN_CLAIMS`");
+
+    let default = ReimbursementScreening::default();
+
+    let duplicate_window_days = match env::var("REIMBURSEMENT_DUPLICATE_WINDOW_DAYS") {
+        Ok(var) => var.parse().expect("`REIMBURSEMENT_DUPLICATE_WINDOW_DAYS` is not a valid number of days"),
+        Err(_) => default.duplicate_window_days,
+    };
+    if duplicate_window_days < 0 {
+        panic!("`REIMBURSEMENT_DUPLICATE_WINDOW_DAYS` must not be less than 0");
+    }
+
+    let outlier_percent = match env::var("REIMBURSEMENT_OUTLIER_PERCENT") {
+        Ok(var) => var.parse().expect("`REIMBURSEMENT_OUTLIER_PERCENT` is not a valid percent"),
+        Err(_) => default.outlier_percent,
+    };
+    if outlier_percent < 100 {
+        panic!("`REIMBURSEMENT_OUTLIER_PERCENT` must not be less than 100");
+    }
+
+    let outlier_min_claims = match env::var("REIMBURSEMENT_OUTLIER_MIN_CLAIMS") {
+        Ok(var) => var.parse().expect("`REIMBURSEMENT_OUTLIER_MIN_CLAIMS` is not a valid number of claims"),
+        Err(_) => default.outlier_min_claims,
+    };
+
+    ReimbursementScreening { duplicate_window_days, outlier_percent, outlier_min_claims }
+}
@@ -1 +1,57 @@
+use crate::entity::sea_orm_active_enums::{AttendanceType, LeaveType};
+
 pub const WORKING_HOUR: (u32, u32) = (9, 17);
+
+/// Yearly leave entitlements in working days, leave types not listed here are unlimited
+pub const LEAVE_ENTITLEMENT_DAYS: [(LeaveType, i32); 3] = [
+    (LeaveType::Annual, 12),
+    (LeaveType::Sick, 12),
+    (LeaveType::Maternity, 65), // Roughly 3 months of Monday to Friday
+];
+
+/// At most this many unused annual leave days are carried over to the next year
+pub const LEAVE_MAX_CARRY_OVER_DAYS: i32 = 6;
+
+/// Employees projected to be paid less than this ratio of their base salary are flagged on the attendance dashboard
+pub const ATTENDANCE_RATIO_THRESHOLD: f64 = 0.8;
+
+/// Share of a day's salary earned by each attendance type in percent
+pub const ATTENDANCE_TYPE_WEIGHTS: [(AttendanceType, i64); 4] = [
+    (AttendanceType::Office, 100),
+    (AttendanceType::Remote, 100),
+    (AttendanceType::HalfDay, 50),
+    (AttendanceType::BusinessTrip, 100),
+];
+
+/// Most days of an attendance type an employee may take within a period, attendance types not listed here are unlimited.
+/// Check-in policies don't apply away from the office, so those are capped instead
+pub const ATTENDANCE_TYPE_MAX_DAYS: [(AttendanceType, u64); 2] = [
+    (AttendanceType::Remote, 8),
+    (AttendanceType::BusinessTrip, 5),
+];
+
+/// Currency payroll is paid in, amounts without a currency are in it
+pub const BASE_CURRENCY: &str = "IDR";
+
+/// Digits after the decimal point of the currencies claims can be made in, amounts are kept in
+/// their minor unit, e.g. cents for USD. Rupiah is kept whole as it's never paid in sen
+pub const CURRENCY_MINOR_DIGITS: [(&str, u32); 12] = [
+    ("IDR", 0),
+    ("USD", 2),
+    ("SGD", 2),
+    ("MYR", 2),
+    ("THB", 2),
+    ("PHP", 2),
+    ("AUD", 2),
+    ("EUR", 2),
+    ("GBP", 2),
+    ("CNY", 2),
+    ("HKD", 2),
+    ("JPY", 0),
+];
+
+/// Most recent claims a claim is compared against when screening for unusually large amounts
+pub const REIMBURSEMENT_SCREENING_HISTORY_CLAIMS: u64 = 200;
+
+/// Bumped whenever how payslips are computed changes, payslips keep the version that computed them
+pub const PAYROLL_ENGINE_VERSION: i32 = 2;
@@ -0,0 +1,78 @@
+use chrono::NaiveDate;
+use sea_orm::{ColumnTrait, ConnectionTrait, EntityTrait, QueryFilter, QueryOrder};
+
+use crate::{consts::{BASE_CURRENCY, CURRENCY_MINOR_DIGITS}, entity::{exchange_rate, prelude::*}};
+
+/// Exchange rates are kept in millionths of a rupiah per unit of the currency
+pub const RATE_SCALE: i64 = 1_000_000;
+
+/// Digits after the decimal point of `currency`, `None` when claims can't be made in it
+pub fn minor_digits(currency: &str) -> Option<u32> {
+    CURRENCY_MINOR_DIGITS.iter()
+        .find(|(code, _)| *code == currency)
+        .map(|(_, digits)| *digits)
+}
+
+/// Converts `amount` in the minor unit of `currency` to rupiah at `rate`, rounding half away from
+/// zero. `None` when the currency isn't supported or the result doesn't fit
+pub fn convert(amount: i64, currency: &str, rate: i64) -> Option<i64> {
+    let divisor = 10i128.pow(minor_digits(currency)?) * RATE_SCALE as i128;
+    let scaled = amount as i128 * rate as i128;
+
+    let quotient = scaled / divisor;
+    let remainder = scaled % divisor;
+    let rounded = if remainder.abs() * 2 >= divisor { quotient + scaled.signum() } else { quotient };
+
+    i64::try_from(rounded).ok()
+}
+
+/// Rate of `currency` effective on `date`, which is the latest one set from on or before it.
+/// Rupiah is always at par
+pub async fn effective_rate<C: ConnectionTrait>(conn: &C, currency: &str, date: NaiveDate) -> Option<i64> {
+    if currency == BASE_CURRENCY {
+        return Some(RATE_SCALE)
+    }
+
+    ExchangeRate::find()
+        .filter(exchange_rate::Column::Currency.eq(currency))
+        .filter(exchange_rate::Column::EffectiveFrom.lte(date))
+        .order_by_desc(exchange_rate::Column::EffectiveFrom)
+        .one(conn).await.unwrap()
+        .map(|rate| rate.rate)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_minor_digits() {
+        assert_eq!(minor_digits("IDR"), Some(0));
+        assert_eq!(minor_digits("USD"), Some(2));
+        assert_eq!(minor_digits("JPY"), Some(0));
+        assert_eq!(minor_digits("usd"), None);
+        assert_eq!(minor_digits("XYZ"), None);
+    }
+
+    #[test]
+    fn test_convert() {
+        // Rupiah is at par
+        assert_eq!(convert(150_000, "IDR", RATE_SCALE), Some(150_000));
+
+        // USD 12.34 at 16,250.5 is 200,531.17, which rounds down
+        assert_eq!(convert(1_234, "USD", 16_250_500_000), Some(200_531));
+
+        // USD 0.01 at 15,850 is 158.5, which rounds half away from zero
+        assert_eq!(convert(1, "USD", 15_850_000_000), Some(159));
+        assert_eq!(convert(-1, "USD", 15_850_000_000), Some(-159));
+
+        // SGD 1,000.00 at 12,045.123456 is exactly 12,045,123.456
+        assert_eq!(convert(100_000, "SGD", 12_045_123_456), Some(12_045_123));
+
+        // JPY 1,000 at 105.75 is 105,750
+        assert_eq!(convert(1_000, "JPY", 105_750_000), Some(105_750));
+
+        assert_eq!(convert(1_000, "XYZ", RATE_SCALE), None);
+        assert_eq!(convert(i64::MAX, "IDR", 2 * RATE_SCALE), None);
+    }
+}
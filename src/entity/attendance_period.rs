@@ -19,12 +19,22 @@ pub struct Model {
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
 pub enum Relation {
+    #[sea_orm(has_many = "super::attendance_correction::Entity")]
+    AttendanceCorrection,
+    #[sea_orm(has_many = "super::checkin_policy::Entity")]
+    CheckinPolicy,
+    #[sea_orm(has_many = "super::checkin_rejection::Entity")]
+    CheckinRejection,
     #[sea_orm(has_many = "super::employee_attendance::Entity")]
     EmployeeAttendance,
     #[sea_orm(has_many = "super::employee_overtime::Entity")]
     EmployeeOvertime,
     #[sea_orm(has_many = "super::employee_reimbursement::Entity")]
     EmployeeReimbursement,
+    #[sea_orm(has_many = "super::overtime_preapproval::Entity")]
+    OvertimePreapproval,
+    #[sea_orm(has_one = "super::payroll_run::Entity")]
+    PayrollRun,
     #[sea_orm(
         belongs_to = "super::user::Entity",
         from = "Column::CreatedBy",
@@ -43,6 +53,24 @@ pub enum Relation {
     User1,
 }
 
+impl Related<super::attendance_correction::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::AttendanceCorrection.def()
+    }
+}
+
+impl Related<super::checkin_policy::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::CheckinPolicy.def()
+    }
+}
+
+impl Related<super::checkin_rejection::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::CheckinRejection.def()
+    }
+}
+
 impl Related<super::employee_attendance::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::EmployeeAttendance.def()
@@ -61,4 +89,16 @@ impl Related<super::employee_reimbursement::Entity> for Entity {
     }
 }
 
+impl Related<super::overtime_preapproval::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::OvertimePreapproval.def()
+    }
+}
+
+impl Related<super::payroll_run::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::PayrollRun.def()
+    }
+}
+
 impl ActiveModelBehavior for ActiveModel {}
@@ -1,9 +1,10 @@
 //! `SeaORM` Entity, @generated by sea-orm-codegen 1.1.0
 
+use super::sea_orm_active_enums::AttendanceType;
 use sea_orm::entity::prelude::*;
 use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
 #[sea_orm(table_name = "employee_attendance")]
 pub struct Model {
     #[sea_orm(primary_key, auto_increment = false)]
@@ -13,10 +14,35 @@ pub struct Model {
     pub created_by: Option<Uuid>,
     pub updated_by: Option<Uuid>,
     pub attendance_period_id: Uuid,
+    pub work_date: Date,
+    pub checked_out_at: Option<DateTimeWithTimeZone>,
+    pub break_started_at: Option<DateTimeWithTimeZone>,
+    pub break_ended_at: Option<DateTimeWithTimeZone>,
+    pub worked_minutes: Option<i32>,
+    pub auto_checked_out: bool,
+    pub late_minutes: i32,
+    pub early_leave_minutes: i32,
+    #[sea_orm(column_type = "Text", nullable)]
+    pub client_ip: Option<String>,
+    #[sea_orm(column_type = "Double", nullable)]
+    pub latitude: Option<f64>,
+    #[sea_orm(column_type = "Double", nullable)]
+    pub longitude: Option<f64>,
+    pub attendance_correction_id: Option<Uuid>,
+    pub approved_by: Option<Uuid>,
+    pub attendance_type: AttendanceType,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
 pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::attendance_correction::Entity",
+        from = "Column::AttendanceCorrectionId",
+        to = "super::attendance_correction::Column::Id",
+        on_update = "Cascade",
+        on_delete = "SetNull"
+    )]
+    AttendanceCorrection,
     #[sea_orm(
         belongs_to = "super::attendance_period::Entity",
         from = "Column::AttendancePeriodId",
@@ -25,6 +51,14 @@ pub enum Relation {
         on_delete = "NoAction"
     )]
     AttendancePeriod,
+    #[sea_orm(
+        belongs_to = "super::user::Entity",
+        from = "Column::ApprovedBy",
+        to = "super::user::Column::Id",
+        on_update = "Cascade",
+        on_delete = "SetNull"
+    )]
+    User3,
     #[sea_orm(
         belongs_to = "super::user::Entity",
         from = "Column::CreatedBy",
@@ -43,6 +77,12 @@ pub enum Relation {
     User1,
 }
 
+impl Related<super::attendance_correction::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::AttendanceCorrection.def()
+    }
+}
+
 impl Related<super::attendance_period::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::AttendancePeriod.def()
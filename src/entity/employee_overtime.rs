@@ -1,5 +1,6 @@
 //! `SeaORM` Entity, @generated by sea-orm-codegen 1.1.0
 
+use super::sea_orm_active_enums::ApprovalStatus;
 use sea_orm::entity::prelude::*;
 use serde::{Deserialize, Serialize};
 
@@ -12,8 +13,16 @@ pub struct Model {
     pub updated_at: DateTimeWithTimeZone,
     pub created_by: Option<Uuid>,
     pub updated_by: Option<Uuid>,
-    pub extra_hours: i16,
     pub attendance_period_id: Uuid,
+    pub work_date: Date,
+    pub status: ApprovalStatus,
+    pub reviewed_by: Option<Uuid>,
+    pub reviewed_at: Option<DateTimeWithTimeZone>,
+    #[sea_orm(column_type = "Text", nullable)]
+    pub review_comment: Option<String>,
+    pub started_at: Option<DateTimeWithTimeZone>,
+    pub ended_at: Option<DateTimeWithTimeZone>,
+    pub minutes: i32,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -26,6 +35,14 @@ pub enum Relation {
         on_delete = "NoAction"
     )]
     AttendancePeriod,
+    #[sea_orm(
+        belongs_to = "super::user::Entity",
+        from = "Column::ReviewedBy",
+        to = "super::user::Column::Id",
+        on_update = "Cascade",
+        on_delete = "SetNull"
+    )]
+    User3,
     #[sea_orm(
         belongs_to = "super::user::Entity",
         from = "Column::CreatedBy",
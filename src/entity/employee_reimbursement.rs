@@ -1,5 +1,6 @@
 //! `SeaORM` Entity, @generated by sea-orm-codegen 1.1.0
 
+use super::sea_orm_active_enums::ReimbursementStatus;
 use sea_orm::entity::prelude::*;
 use serde::{Deserialize, Serialize};
 
@@ -16,6 +17,18 @@ pub struct Model {
     #[sea_orm(column_type = "Text")]
     pub description: String,
     pub attendance_period_id: Uuid,
+    pub category_id: Uuid,
+    pub status: ReimbursementStatus,
+    pub approved_amount: Option<i64>,
+    pub reviewed_by: Option<Uuid>,
+    pub reviewed_at: Option<DateTimeWithTimeZone>,
+    #[sea_orm(column_type = "Text", nullable)]
+    pub review_comment: Option<String>,
+    #[sea_orm(column_type = "Text")]
+    pub currency: String,
+    pub original_amount: i64,
+    pub transaction_date: Date,
+    pub exchange_rate: i64,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -28,6 +41,26 @@ pub enum Relation {
         on_delete = "NoAction"
     )]
     AttendancePeriod,
+    #[sea_orm(
+        belongs_to = "super::reimbursement_category::Entity",
+        from = "Column::CategoryId",
+        to = "super::reimbursement_category::Column::Id",
+        on_update = "Cascade",
+        on_delete = "Restrict"
+    )]
+    ReimbursementCategory,
+    #[sea_orm(has_many = "super::reimbursement_receipt::Entity")]
+    ReimbursementReceipt,
+    #[sea_orm(has_many = "super::reimbursement_flag::Entity")]
+    ReimbursementFlag,
+    #[sea_orm(
+        belongs_to = "super::user::Entity",
+        from = "Column::ReviewedBy",
+        to = "super::user::Column::Id",
+        on_update = "Cascade",
+        on_delete = "SetNull"
+    )]
+    User3,
     #[sea_orm(
         belongs_to = "super::user::Entity",
         from = "Column::CreatedBy",
@@ -52,4 +85,22 @@ impl Related<super::attendance_period::Entity> for Entity {
     }
 }
 
+impl Related<super::reimbursement_category::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::ReimbursementCategory.def()
+    }
+}
+
+impl Related<super::reimbursement_receipt::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::ReimbursementReceipt.def()
+    }
+}
+
+impl Related<super::reimbursement_flag::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::ReimbursementFlag.def()
+    }
+}
+
 impl ActiveModelBehavior for ActiveModel {}
@@ -2,9 +2,26 @@
 
 pub mod prelude;
 
+pub mod attendance_correction;
 pub mod attendance_period;
+pub mod checkin_policy;
+pub mod checkin_rejection;
 pub mod employee_attendance;
+pub mod employee_leave;
 pub mod employee_overtime;
 pub mod employee_reimbursement;
+pub mod employee_work_schedule;
+pub mod exchange_rate;
+pub mod leave_entitlement;
+pub mod overtime_preapproval;
+pub mod payroll_run;
+pub mod payslip;
+pub mod payslip_line;
+pub mod public_holiday;
+pub mod reimbursement_category;
+pub mod reimbursement_flag;
+pub mod reimbursement_receipt;
 pub mod sea_orm_active_enums;
+pub mod tax_profile;
 pub mod user;
+pub mod work_schedule;
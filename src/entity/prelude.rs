@@ -1,7 +1,24 @@
 //! `SeaORM` Entity, @generated by sea-orm-codegen 1.1.0
 
+pub use super::attendance_correction::Entity as AttendanceCorrection;
 pub use super::attendance_period::Entity as AttendancePeriod;
+pub use super::checkin_policy::Entity as CheckinPolicy;
+pub use super::checkin_rejection::Entity as CheckinRejection;
 pub use super::employee_attendance::Entity as EmployeeAttendance;
+pub use super::employee_leave::Entity as EmployeeLeave;
 pub use super::employee_overtime::Entity as EmployeeOvertime;
 pub use super::employee_reimbursement::Entity as EmployeeReimbursement;
+pub use super::employee_work_schedule::Entity as EmployeeWorkSchedule;
+pub use super::exchange_rate::Entity as ExchangeRate;
+pub use super::leave_entitlement::Entity as LeaveEntitlement;
+pub use super::overtime_preapproval::Entity as OvertimePreapproval;
+pub use super::payroll_run::Entity as PayrollRun;
+pub use super::payslip::Entity as Payslip;
+pub use super::payslip_line::Entity as PayslipLine;
+pub use super::public_holiday::Entity as PublicHoliday;
+pub use super::reimbursement_category::Entity as ReimbursementCategory;
+pub use super::reimbursement_flag::Entity as ReimbursementFlag;
+pub use super::reimbursement_receipt::Entity as ReimbursementReceipt;
+pub use super::tax_profile::Entity as TaxProfile;
 pub use super::user::Entity as User;
+pub use super::work_schedule::Entity as WorkSchedule;
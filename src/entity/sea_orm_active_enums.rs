@@ -11,3 +11,115 @@ pub enum RoleType {
     #[sea_orm(string_value = "admin")]
     Admin,
 }
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EnumIter, DeriveActiveEnum, Serialize, Deserialize)]
+#[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "leave_type")]
+pub enum LeaveType {
+    #[sea_orm(string_value = "annual")]
+    Annual,
+    #[sea_orm(string_value = "sick")]
+    Sick,
+    #[sea_orm(string_value = "unpaid")]
+    Unpaid,
+    #[sea_orm(string_value = "maternity")]
+    Maternity,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EnumIter, DeriveActiveEnum, Serialize, Deserialize)]
+#[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "attendance_type")]
+pub enum AttendanceType {
+    #[sea_orm(string_value = "office")]
+    Office,
+    #[sea_orm(string_value = "remote")]
+    Remote,
+    #[sea_orm(string_value = "half_day")]
+    HalfDay,
+    #[sea_orm(string_value = "business_trip")]
+    BusinessTrip,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize)]
+#[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "approval_status")]
+pub enum ApprovalStatus {
+    #[sea_orm(string_value = "pending")]
+    Pending,
+    #[sea_orm(string_value = "approved")]
+    Approved,
+    #[sea_orm(string_value = "rejected")]
+    Rejected,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize)]
+#[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "reimbursement_status")]
+pub enum ReimbursementStatus {
+    #[sea_orm(string_value = "submitted")]
+    Submitted,
+    #[sea_orm(string_value = "approved")]
+    Approved,
+    #[sea_orm(string_value = "partially_approved")]
+    PartiallyApproved,
+    #[sea_orm(string_value = "rejected")]
+    Rejected,
+    #[sea_orm(string_value = "paid")]
+    Paid,
+    #[sea_orm(string_value = "withdrawn")]
+    Withdrawn,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize)]
+#[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "reimbursement_flag_kind")]
+pub enum ReimbursementFlagKind {
+    #[sea_orm(string_value = "duplicate")]
+    Duplicate,
+    #[sea_orm(string_value = "receipt_reused")]
+    ReceiptReused,
+    #[sea_orm(string_value = "employee_outlier")]
+    EmployeeOutlier,
+    #[sea_orm(string_value = "category_outlier")]
+    CategoryOutlier,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize)]
+#[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "payslip_line_kind")]
+pub enum PayslipLineKind {
+    #[sea_orm(string_value = "salary")]
+    Salary,
+    #[sea_orm(string_value = "overtime")]
+    Overtime,
+    #[sea_orm(string_value = "reimbursement")]
+    Reimbursement,
+    #[sea_orm(string_value = "deduction")]
+    Deduction,
+    #[sea_orm(string_value = "tax_refund")]
+    TaxRefund,
+}
+
+/// Family status the tax-free income (PTKP) follows, single (TK) or married (K) with up to 3 dependants
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize)]
+#[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "ptkp_status")]
+pub enum PtkpStatus {
+    #[sea_orm(string_value = "tk0")]
+    #[serde(rename = "TK/0")]
+    Tk0,
+    #[sea_orm(string_value = "tk1")]
+    #[serde(rename = "TK/1")]
+    Tk1,
+    #[sea_orm(string_value = "tk2")]
+    #[serde(rename = "TK/2")]
+    Tk2,
+    #[sea_orm(string_value = "tk3")]
+    #[serde(rename = "TK/3")]
+    Tk3,
+    #[sea_orm(string_value = "k0")]
+    #[serde(rename = "K/0")]
+    K0,
+    #[sea_orm(string_value = "k1")]
+    #[serde(rename = "K/1")]
+    K1,
+    #[sea_orm(string_value = "k2")]
+    #[serde(rename = "K/2")]
+    K2,
+    #[sea_orm(string_value = "k3")]
+    #[serde(rename = "K/3")]
+    K3,
+}
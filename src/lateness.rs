@@ -0,0 +1,98 @@
+use chrono::NaiveDateTime;
+
+/// How lateness and early departures over a payroll period turn into warnings and deductions
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LatenessPolicy {
+    /// Arriving late or leaving early by at most this many minutes isn't counted at all
+    pub grace_minutes: i64,
+    /// Cumulative counted minutes in a period from which the payslip carries a warning
+    pub warning_minutes: Option<i64>,
+    /// Cumulative counted minutes in a period that are tolerated, every minute beyond is deducted from pay
+    pub deduction_minutes: Option<i64>,
+}
+
+impl Default for LatenessPolicy {
+    /// Warns after an hour in a period but never deducts
+    fn default() -> Self {
+        Self {
+            grace_minutes: 15,
+            warning_minutes: Some(60),
+            deduction_minutes: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct LatenessOutcome {
+    pub counted_minutes: i64,
+    pub warning: bool,
+    pub deducted_minutes: i64,
+}
+
+impl LatenessPolicy {
+    /// Minutes of a single check-in or check-out that count against the employee,
+    /// anything within the grace period is forgiven entirely
+    pub fn counted_minutes(&self, minutes: i64) -> i64 {
+        if minutes > self.grace_minutes {
+            minutes
+        } else {
+            0
+        }
+    }
+
+    /// Applies the policy to the cumulative counted minutes of a period
+    pub fn evaluate(&self, counted_minutes: i64) -> LatenessOutcome {
+        LatenessOutcome {
+            counted_minutes,
+            warning: self.warning_minutes.is_some_and(|minutes| counted_minutes >= minutes),
+            deducted_minutes: self.deduction_minutes
+                .map(|minutes| (counted_minutes - minutes).max(0))
+                .unwrap_or_default(),
+        }
+    }
+}
+
+/// Whole minutes that `actual` is past `expected`, zero when it isn't
+pub fn minutes_past(expected: NaiveDateTime, actual: NaiveDateTime) -> i64 {
+    (actual - expected).num_minutes().max(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use super::*;
+
+    #[test]
+    fn test_counted_minutes() {
+        let policy = LatenessPolicy::default();
+
+        assert_eq!(policy.counted_minutes(0), 0);
+        assert_eq!(policy.counted_minutes(15), 0);
+        assert_eq!(policy.counted_minutes(16), 16);
+    }
+
+    #[test]
+    fn test_evaluate() {
+        let policy = LatenessPolicy::default();
+
+        assert_eq!(policy.evaluate(30), LatenessOutcome { counted_minutes: 30, warning: false, deducted_minutes: 0 });
+        assert_eq!(policy.evaluate(90), LatenessOutcome { counted_minutes: 90, warning: true, deducted_minutes: 0 });
+
+        let policy = LatenessPolicy {
+            deduction_minutes: Some(60),
+            ..Default::default()
+        };
+
+        assert_eq!(policy.evaluate(45).deducted_minutes, 0);
+        assert_eq!(policy.evaluate(100).deducted_minutes, 40);
+    }
+
+    #[test]
+    fn test_minutes_past() {
+        let shift_start = NaiveDate::from_ymd_opt(2024, 6, 3).unwrap().and_hms_opt(9, 0, 0).unwrap();
+
+        assert_eq!(minutes_past(shift_start, NaiveDate::from_ymd_opt(2024, 6, 3).unwrap().and_hms_opt(8, 45, 0).unwrap()), 0);
+        assert_eq!(minutes_past(shift_start, NaiveDate::from_ymd_opt(2024, 6, 3).unwrap().and_hms_opt(9, 20, 30).unwrap()), 20);
+    }
+}
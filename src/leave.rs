@@ -0,0 +1,192 @@
+use chrono::{Datelike as _, Local, NaiveDate};
+use sea_orm::{sea_query::OnConflict, ActiveValue::Set, ColumnTrait, ConnectionTrait, DatabaseConnection, EntityTrait, QueryFilter};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{consts::{LEAVE_ENTITLEMENT_DAYS, LEAVE_MAX_CARRY_OVER_DAYS}, entity::{employee_leave, leave_entitlement, prelude::*, sea_orm_active_enums::{ApprovalStatus, LeaveType}}};
+
+impl LeaveType {
+    /// Paid leave counts as attended in payroll proration
+    pub fn is_paid(&self) -> bool {
+        *self != LeaveType::Unpaid
+    }
+
+    /// Yearly entitlement in working days, `None` when the leave type is unlimited
+    pub fn default_days(&self) -> Option<i32> {
+        LEAVE_ENTITLEMENT_DAYS.iter()
+            .find(|(leave_type, _)| leave_type == self)
+            .map(|(_, days)| *days)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Entitlement {
+    pub days: i32,
+    pub carried_over_days: i32,
+}
+
+impl Entitlement {
+    pub fn total_days(&self) -> i32 {
+        self.days + self.carried_over_days
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LeaveBalance {
+    pub leave_type: LeaveType,
+    pub year: i32,
+    /// `None` when the leave type is unlimited
+    pub entitled_days: Option<i32>,
+    pub carried_over_days: i32,
+    pub used_days: i32,
+    pub pending_days: i32,
+    /// Pending requests are reserved, so they are already subtracted from this
+    pub remaining_days: Option<i32>,
+}
+
+/// Entitlement of an employee for a year, either the recorded one or the default of the leave type
+pub async fn entitlement(db: &DatabaseConnection, user_id: Uuid, leave_type: LeaveType, year: i32) -> Option<Entitlement> {
+    let default_days = leave_type.default_days()?;
+
+    if let Some(recorded) = find_entitlement(db, user_id, leave_type, year).await {
+        return Some(recorded)
+    }
+
+    let carried_over_days = match leave_type {
+        LeaveType::Annual => carry_over_days(db, user_id, year - 1).await,
+        _ => 0,
+    };
+
+    Some(Entitlement { days: default_days, carried_over_days })
+}
+
+/// Records the entitlement for the year if it wasn't yet, so that it can be carried over next year.
+/// Years after the current one aren't, as what's carried over into them may still change
+pub async fn ensure_entitlement(db: &DatabaseConnection, user_id: Uuid, leave_type: LeaveType, year: i32) {
+    if year > Local::now().year() {
+        return
+    }
+
+    let Some(entitlement) = entitlement(db, user_id, leave_type, year).await else {
+        return
+    };
+
+    LeaveEntitlement::insert(leave_entitlement::ActiveModel {
+        created_at: Set(Local::now().fixed_offset()),
+        updated_at: Set(Local::now().fixed_offset()),
+        user_id: Set(user_id),
+        leave_type: Set(leave_type),
+        year: Set(year),
+        days: Set(entitlement.days),
+        carried_over_days: Set(entitlement.carried_over_days),
+        ..Default::default()
+    })
+        .on_conflict(OnConflict::columns([leave_entitlement::Column::UserId, leave_entitlement::Column::LeaveType, leave_entitlement::Column::Year])
+            .do_nothing()
+            .to_owned())
+        .do_nothing()
+        .exec(db).await.unwrap();
+}
+
+async fn find_entitlement(db: &DatabaseConnection, user_id: Uuid, leave_type: LeaveType, year: i32) -> Option<Entitlement> {
+    LeaveEntitlement::find()
+        .filter(leave_entitlement::Column::UserId.eq(user_id))
+        .filter(leave_entitlement::Column::LeaveType.eq(leave_type))
+        .filter(leave_entitlement::Column::Year.eq(year))
+        .one(db).await.unwrap()
+        .map(|recorded| Entitlement { days: recorded.days, carried_over_days: recorded.carried_over_days })
+}
+
+/// Unused annual leave of `previous_year`, out of the default entitlement when none was recorded for
+/// it. Nothing is carried over from before the employee joined
+async fn carry_over_days(db: &DatabaseConnection, user_id: Uuid, previous_year: i32) -> i32 {
+    let previous = match find_entitlement(db, user_id, LeaveType::Annual, previous_year).await {
+        Some(recorded) => recorded,
+        None => {
+            let joined_at = User::find_by_id(user_id)
+                .one(db).await.unwrap()
+                .map(|user| user.created_at.year());
+
+            if joined_at.is_none_or(|joined_at| joined_at > previous_year) {
+                return 0
+            }
+
+            Entitlement { days: LeaveType::Annual.default_days().unwrap_or_default(), carried_over_days: 0 }
+        },
+    };
+
+    let used_days = leave_days(db, user_id, LeaveType::Annual, previous_year, ApprovalStatus::Approved).await;
+
+    (previous.total_days() - used_days).clamp(0, LEAVE_MAX_CARRY_OVER_DAYS)
+}
+
+async fn leave_days(db: &DatabaseConnection, user_id: Uuid, leave_type: LeaveType, year: i32, status: ApprovalStatus) -> i32 {
+    let leaves = EmployeeLeave::find()
+        .filter(employee_leave::Column::CreatedBy.eq(user_id))
+        .filter(employee_leave::Column::LeaveType.eq(leave_type))
+        .filter(employee_leave::Column::Status.eq(status))
+        .filter(employee_leave::Column::StartDate.between(
+            NaiveDate::from_ymd_opt(year, 1, 1).unwrap(),
+            NaiveDate::from_ymd_opt(year, 12, 31).unwrap(),
+        ))
+        .all(db).await.unwrap();
+
+    leaves.iter().map(|leave| leave.days).sum()
+}
+
+pub async fn balance(db: &DatabaseConnection, user_id: Uuid, leave_type: LeaveType, year: i32) -> LeaveBalance {
+    let entitlement = entitlement(db, user_id, leave_type, year).await;
+    let used_days = leave_days(db, user_id, leave_type, year, ApprovalStatus::Approved).await;
+    let pending_days = leave_days(db, user_id, leave_type, year, ApprovalStatus::Pending).await;
+
+    LeaveBalance {
+        leave_type,
+        year,
+        entitled_days: entitlement.map(|e| e.days),
+        carried_over_days: entitlement.map(|e| e.carried_over_days).unwrap_or_default(),
+        used_days,
+        pending_days,
+        remaining_days: entitlement.map(|e| e.total_days() - used_days - pending_days),
+    }
+}
+
+/// Approved leaves of an employee that overlap with `start` to `end` (inclusive)
+pub async fn approved_leaves<C: ConnectionTrait>(db: &C, user_id: Uuid, start: NaiveDate, end: NaiveDate) -> Vec<employee_leave::Model> {
+    EmployeeLeave::find()
+        .filter(employee_leave::Column::CreatedBy.eq(user_id))
+        .filter(employee_leave::Column::Status.eq(ApprovalStatus::Approved))
+        .filter(employee_leave::Column::StartDate.lte(end))
+        .filter(employee_leave::Column::EndDate.gte(start))
+        .all(db).await.unwrap()
+}
+
+pub fn leave_on(leaves: &[employee_leave::Model], date: NaiveDate) -> Option<&employee_leave::Model> {
+    leaves.iter().find(|leave| leave.start_date <= date && date <= leave.end_date)
+}
+
+/// Leave can't span across years, otherwise it's unclear whose entitlement it uses
+pub fn leave_year(start: NaiveDate, end: NaiveDate) -> Option<i32> {
+    (start.year() == end.year()).then_some(start.year())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_leave_type() {
+        assert!(LeaveType::Annual.is_paid());
+        assert!(LeaveType::Sick.is_paid());
+        assert!(LeaveType::Maternity.is_paid());
+        assert!(!LeaveType::Unpaid.is_paid());
+
+        assert_eq!(LeaveType::Annual.default_days(), Some(12));
+        assert_eq!(LeaveType::Unpaid.default_days(), None);
+    }
+
+    #[test]
+    fn test_leave_year() {
+        assert_eq!(leave_year(NaiveDate::from_ymd_opt(2024, 6, 3).unwrap(), NaiveDate::from_ymd_opt(2024, 6, 7).unwrap()), Some(2024));
+        assert_eq!(leave_year(NaiveDate::from_ymd_opt(2024, 12, 30).unwrap(), NaiveDate::from_ymd_opt(2025, 1, 2).unwrap()), None);
+    }
+}
@@ -0,0 +1,57 @@
+/// How much of a limit is left, `S` tells what the limit is over, e.g. a day or a period
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Headroom<S> {
+    pub scope: S,
+    pub limit: i64,
+    pub taken: i64,
+    pub remaining: i64,
+}
+
+/// Headroom of every limit that's set, in the order they're given. Each limit comes with what's
+/// already taken of it
+pub fn headroom<S>(limits: impl IntoIterator<Item = (S, Option<i64>, i64)>) -> Vec<Headroom<S>> {
+    limits.into_iter()
+        .filter_map(|(scope, limit, taken)| limit.map(|limit| Headroom {
+            scope,
+            limit,
+            taken,
+            remaining: (limit - taken).max(0),
+        }))
+        .collect()
+}
+
+/// The tightest limit that taking `amount` more would go past
+pub fn exceeded<S>(headroom: impl IntoIterator<Item = Headroom<S>>, amount: i64) -> Option<Headroom<S>> {
+    headroom.into_iter()
+        .filter(|headroom| amount > headroom.remaining)
+        .min_by_key(|headroom| headroom.remaining)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_headroom() {
+        let limits = headroom([("day", Some(180), 60), ("week", None, 600), ("period", Some(600), 700)]);
+
+        assert_eq!(limits, vec![
+            Headroom { scope: "day", limit: 180, taken: 60, remaining: 120 },
+            Headroom { scope: "period", limit: 600, taken: 700, remaining: 0 },
+        ]);
+    }
+
+    #[test]
+    fn test_exceeded() {
+        let limits = headroom([("claim", Some(500), 0), ("period", Some(1000), 700), ("year", Some(5000), 4900)]);
+
+        assert_eq!(exceeded(limits.clone(), 100), None);
+        assert_eq!(exceeded(limits.clone(), 200).map(|headroom| headroom.scope), Some("year"));
+        assert_eq!(exceeded(limits, 600).map(|headroom| headroom.scope), Some("year"));
+
+        let limits = headroom([("claim", Some(500), 0), ("period", Some(1000), 0)]);
+        assert_eq!(exceeded(limits, 600).map(|headroom| headroom.scope), Some("claim"));
+
+        assert_eq!(exceeded(Vec::<Headroom<&str>>::new(), i64::MAX), None);
+    }
+}
@@ -11,6 +11,17 @@ use crate::auth::Authority;
 mod config;
 mod consts;
 mod utils;
+mod schedule;
+mod leave;
+mod lateness;
+mod checkin;
+mod attendance_type;
+mod limit;
+mod overtime;
+mod receipt;
+mod reimbursement;
+mod currency;
+mod tax;
 
 mod entity;
 mod auth;
@@ -45,16 +56,43 @@ async fn main() {
     let config::Config {
         host_address,
         database_opt,
-        jwt_key
+        jwt_key,
+        lateness_policy,
+        trusted_proxies,
+        overtime_rate_policy,
+        non_working_day_overtime,
+        overtime_rounding,
+        overtime_limits,
+        receipt_storage,
+        receipt_limits,
+        reimbursement_screening,
     } = config::load();
     
     let database = web::Data::new(Database::connect(database_opt).await.expect("Unable to connect to database"));
     let authority = web::Data::new(Authority::new(jwt_key.as_bytes()));
+    let lateness_policy = web::Data::new(lateness_policy);
+    let trusted_proxies = web::Data::new(trusted_proxies);
+    let overtime_rate_policy = web::Data::from(overtime_rate_policy);
+    let non_working_day_overtime = web::Data::new(non_working_day_overtime);
+    let overtime_rounding = web::Data::new(overtime_rounding);
+    let overtime_limits = web::Data::new(overtime_limits);
+    let receipt_storage = web::Data::from(receipt_storage);
+    let receipt_limits = web::Data::new(receipt_limits);
+    let reimbursement_screening = web::Data::new(reimbursement_screening);
 
     let server = HttpServer::new(move || {
         App::new()
             .app_data(database.clone())
             .app_data(authority.clone())
+            .app_data(lateness_policy.clone())
+            .app_data(trusted_proxies.clone())
+            .app_data(overtime_rate_policy.clone())
+            .app_data(non_working_day_overtime.clone())
+            .app_data(overtime_rounding.clone())
+            .app_data(overtime_limits.clone())
+            .app_data(receipt_storage.clone())
+            .app_data(receipt_limits.clone())
+            .app_data(reimbursement_screening.clone())
             .wrap(TracingLogger::default())
             .configure(pages::config)
     });
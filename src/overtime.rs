@@ -0,0 +1,353 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use crate::{limit::{self, Headroom}, schedule::EmployeeSchedule};
+
+/// Kind of day overtime was worked on, which decides the tiers it's paid at
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum OvertimeDay {
+    Workday,
+    RestDay,
+    PublicHoliday,
+}
+
+impl OvertimeDay {
+    pub fn of(schedule: &EmployeeSchedule, date: NaiveDate) -> Self {
+        if schedule.is_holiday(date) {
+            OvertimeDay::PublicHoliday
+        } else if !schedule.is_working_day(date) {
+            OvertimeDay::RestDay
+        } else {
+            OvertimeDay::Workday
+        }
+    }
+}
+
+/// Most overtime an employee may take, a day's limit depends on whether it's a working day
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OvertimeLimits {
+    pub workday_minutes: i64,
+    /// On rest days and public holidays there's no shift, so it can take a whole day
+    pub non_working_day_minutes: i64,
+    /// Within a Monday to Sunday week, only overtime on working days counts towards it
+    pub weekly_minutes: Option<i64>,
+    pub period_minutes: Option<i64>,
+}
+
+impl Default for OvertimeLimits {
+    /// 4 hours a day and 18 hours a week as regulated by PP 35/2021, which leaves out overtime on
+    /// rest days and public holidays
+    fn default() -> Self {
+        Self {
+            workday_minutes: 4 * 60,
+            non_working_day_minutes: 8 * 60,
+            weekly_minutes: Some(18 * 60),
+            period_minutes: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum OvertimeLimitScope {
+    Day,
+    Week,
+    Period,
+}
+
+impl OvertimeLimitScope {
+    fn describe(&self) -> &'static str {
+        match self {
+            OvertimeLimitScope::Day => "a day",
+            OvertimeLimitScope::Week => "a week",
+            OvertimeLimitScope::Period => "in this period",
+        }
+    }
+}
+
+/// Minutes of overtime already taken within each scope, the week's only counts working days
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct OvertimeTaken {
+    pub day_minutes: i64,
+    pub week_minutes: i64,
+    pub period_minutes: i64,
+}
+
+pub type OvertimeHeadroom = Headroom<OvertimeLimitScope>;
+
+impl OvertimeHeadroom {
+    /// Tells which limit was hit and how much of it is left
+    pub fn exceeded_message(&self) -> String {
+        format!(
+            "you cannot take overtime for more than {} {}, {} remaining",
+            describe_minutes(self.limit),
+            self.scope.describe(),
+            describe_minutes(self.remaining),
+        )
+    }
+}
+
+impl OvertimeLimits {
+    /// Headroom of every limit that applies on `day`, from the narrowest scope
+    pub fn headroom(&self, day: OvertimeDay, taken: OvertimeTaken) -> Vec<OvertimeHeadroom> {
+        let (daily_minutes, weekly_minutes) = match day {
+            OvertimeDay::Workday => (self.workday_minutes, self.weekly_minutes),
+            OvertimeDay::RestDay | OvertimeDay::PublicHoliday => (self.non_working_day_minutes, None),
+        };
+
+        limit::headroom([
+            (OvertimeLimitScope::Day, Some(daily_minutes), taken.day_minutes),
+            (OvertimeLimitScope::Week, weekly_minutes, taken.week_minutes),
+            (OvertimeLimitScope::Period, self.period_minutes, taken.period_minutes),
+        ])
+    }
+
+    /// The tightest limit that taking `minutes` more would go past
+    pub fn exceeded(&self, day: OvertimeDay, taken: OvertimeTaken, minutes: i64) -> Option<OvertimeHeadroom> {
+        limit::exceeded(self.headroom(day, taken), minutes)
+    }
+}
+
+fn describe_minutes(minutes: i64) -> String {
+    let plural = |n: i64, unit: &str| if n == 1 { format!("{n} {unit}") } else { format!("{n} {unit}s") };
+
+    match (minutes / 60, minutes % 60) {
+        (0, minutes) => plural(minutes, "minute"),
+        (hours, 0) => plural(hours, "hour"),
+        (hours, minutes) => format!("{} {}", plural(hours, "hour"), plural(minutes, "minute")),
+    }
+}
+
+/// Rules for overtime on rest days and public holidays, which is taken without checking-in
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NonWorkingDayOvertime {
+    /// Overtime can only be taken on days an admin has pre-approved
+    pub require_preapproval: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RoundingMode {
+    Up,
+    Down,
+    Nearest,
+}
+
+/// How the minutes of an overtime entry are rounded before they're paid
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OvertimeRounding {
+    /// Granularity, e.g. 15 rounds to quarter hours and 1 keeps the minutes as they are
+    pub minutes: i64,
+    pub mode: RoundingMode,
+}
+
+impl Default for OvertimeRounding {
+    fn default() -> Self {
+        Self {
+            minutes: 1,
+            mode: RoundingMode::Nearest,
+        }
+    }
+}
+
+impl OvertimeRounding {
+    pub fn round(&self, minutes: i64) -> i64 {
+        let down = minutes - minutes.rem_euclid(self.minutes);
+        let up = if down == minutes { minutes } else { down + self.minutes };
+
+        match self.mode {
+            RoundingMode::Up => up,
+            RoundingMode::Down => down,
+            RoundingMode::Nearest if (minutes - down) * 2 >= self.minutes => up,
+            RoundingMode::Nearest => down,
+        }
+    }
+}
+
+/// Part of a day's overtime paid at the same multiplier
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OvertimeTier {
+    pub minutes: i64,
+    /// In percent of the hourly rate, e.g. 150 is 1.5x
+    pub multiplier_percent: i64,
+}
+
+impl OvertimeTier {
+    pub fn amount(&self, hourly_rate: i64) -> i64 {
+        (hourly_rate * self.minutes * self.multiplier_percent) / (60 * 100)
+    }
+}
+
+/// How overtime is paid
+pub trait OvertimeRatePolicy: Send + Sync {
+    /// Hourly wage the multipliers apply to
+    fn hourly_rate(&self, monthly_salary: i64, period_working_minutes: i64) -> i64;
+
+    /// Splits the overtime worked on a single day into tiers, `working_days_per_week` is of
+    /// the employee's schedule
+    fn tiers(&self, day: OvertimeDay, minutes: i64, working_days_per_week: u32) -> Vec<OvertimeTier>;
+}
+
+/// Every hour paid at twice the hourly wage earned within the period, or thrice on rest days and
+/// public holidays. Periods without working minutes have no hourly wage
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FlatOvertimeRate;
+
+impl OvertimeRatePolicy for FlatOvertimeRate {
+    fn hourly_rate(&self, monthly_salary: i64, period_working_minutes: i64) -> i64 {
+        (monthly_salary * 60).checked_div(period_working_minutes).unwrap_or_default()
+    }
+
+    fn tiers(&self, day: OvertimeDay, minutes: i64, _working_days_per_week: u32) -> Vec<OvertimeTier> {
+        let multiplier_percent = match day {
+            OvertimeDay::Workday => 200,
+            OvertimeDay::RestDay | OvertimeDay::PublicHoliday => 300,
+        };
+
+        vec![OvertimeTier { minutes, multiplier_percent }]
+    }
+}
+
+/// Indonesian overtime as regulated by PP 35/2021
+///
+/// The hourly wage is 1/173 of the monthly wage. On workdays the first hour is paid 1.5x and
+/// every hour after 2x. On rest days and public holidays a whole shift is paid 2x, the next hour
+/// 3x and the hours after 4x, where a shift is 8 hours on a 5-day week and 7 hours on a 6-day week
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StatutoryOvertimeRate;
+
+impl OvertimeRatePolicy for StatutoryOvertimeRate {
+    fn hourly_rate(&self, monthly_salary: i64, _period_working_minutes: i64) -> i64 {
+        monthly_salary / 173
+    }
+
+    fn tiers(&self, day: OvertimeDay, minutes: i64, working_days_per_week: u32) -> Vec<OvertimeTier> {
+        let tiers: &[(Option<i64>, i64)] = match day {
+            OvertimeDay::Workday => &[(Some(60), 150), (None, 200)],
+            OvertimeDay::RestDay | OvertimeDay::PublicHoliday => match working_days_per_week {
+                6.. => &[(Some(7 * 60), 200), (Some(60), 300), (None, 400)],
+                _ => &[(Some(8 * 60), 200), (Some(60), 300), (None, 400)],
+            },
+        };
+
+        split_tiers(minutes, tiers)
+    }
+}
+
+/// Fills `(length, multiplier)` tiers in order, the last one without a length takes the rest
+fn split_tiers(mut minutes: i64, tiers: &[(Option<i64>, i64)]) -> Vec<OvertimeTier> {
+    let mut res = Vec::new();
+
+    for (length, multiplier_percent) in tiers {
+        if minutes <= 0 {
+            break
+        }
+
+        let tier_minutes = length.map_or(minutes, |length| minutes.min(length));
+        res.push(OvertimeTier { minutes: tier_minutes, multiplier_percent: *multiplier_percent });
+        minutes -= tier_minutes;
+    }
+
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_flat_overtime_rate() {
+        let policy = FlatOvertimeRate;
+
+        // 5mil over 3 days of 8 hours
+        let hourly_rate = policy.hourly_rate(5_000_000, 3 * 8 * 60);
+        assert_eq!(hourly_rate, 208_333);
+
+        // Without working minutes in the period, nothing was earned by the hour
+        assert_eq!(policy.hourly_rate(5_000_000, 0), 0);
+
+        let tiers = policy.tiers(OvertimeDay::Workday, 3 * 60, 5);
+        assert_eq!(tiers, vec![OvertimeTier { minutes: 180, multiplier_percent: 200 }]);
+        assert_eq!(tiers[0].amount(hourly_rate), 1_249_998);
+
+        assert_eq!(policy.tiers(OvertimeDay::RestDay, 8 * 60, 5), vec![
+            OvertimeTier { minutes: 480, multiplier_percent: 300 },
+        ]);
+    }
+
+    #[test]
+    fn test_statutory_overtime_rate() {
+        let policy = StatutoryOvertimeRate;
+
+        assert_eq!(policy.hourly_rate(5_190_000, 0), 30_000);
+
+        assert_eq!(policy.tiers(OvertimeDay::Workday, 30, 5), vec![
+            OvertimeTier { minutes: 30, multiplier_percent: 150 },
+        ]);
+        assert_eq!(policy.tiers(OvertimeDay::Workday, 3 * 60, 5), vec![
+            OvertimeTier { minutes: 60, multiplier_percent: 150 },
+            OvertimeTier { minutes: 120, multiplier_percent: 200 },
+        ]);
+
+        assert_eq!(policy.tiers(OvertimeDay::RestDay, 10 * 60, 5), vec![
+            OvertimeTier { minutes: 480, multiplier_percent: 200 },
+            OvertimeTier { minutes: 60, multiplier_percent: 300 },
+            OvertimeTier { minutes: 60, multiplier_percent: 400 },
+        ]);
+        assert_eq!(policy.tiers(OvertimeDay::PublicHoliday, 8 * 60, 6), vec![
+            OvertimeTier { minutes: 420, multiplier_percent: 200 },
+            OvertimeTier { minutes: 60, multiplier_percent: 300 },
+        ]);
+
+        assert_eq!(policy.tiers(OvertimeDay::Workday, 0, 5), vec![]);
+    }
+
+    #[test]
+    fn test_overtime_limits() {
+        let limits = OvertimeLimits { period_minutes: Some(20 * 60), ..Default::default() };
+        let taken = OvertimeTaken { day_minutes: 60, week_minutes: 17 * 60, period_minutes: 17 * 60 };
+
+        assert_eq!(limits.headroom(OvertimeDay::Workday, taken), vec![
+            OvertimeHeadroom { scope: OvertimeLimitScope::Day, limit: 240, taken: 60, remaining: 180 },
+            OvertimeHeadroom { scope: OvertimeLimitScope::Week, limit: 1080, taken: 1020, remaining: 60 },
+            OvertimeHeadroom { scope: OvertimeLimitScope::Period, limit: 1200, taken: 1020, remaining: 180 },
+        ]);
+
+        // The week's limit doesn't apply on rest days and public holidays
+        assert_eq!(limits.headroom(OvertimeDay::RestDay, taken), vec![
+            OvertimeHeadroom { scope: OvertimeLimitScope::Day, limit: 480, taken: 60, remaining: 420 },
+            OvertimeHeadroom { scope: OvertimeLimitScope::Period, limit: 1200, taken: 1020, remaining: 180 },
+        ]);
+    }
+
+    #[test]
+    fn test_overtime_limit_message() {
+        let headroom = OvertimeHeadroom { scope: OvertimeLimitScope::Week, limit: 18 * 60, taken: 1005, remaining: 75 };
+        assert_eq!(headroom.exceeded_message(), "you cannot take overtime for more than 18 hours a week, 1 hour 15 minutes remaining");
+
+        let headroom = OvertimeHeadroom { scope: OvertimeLimitScope::Day, limit: 4 * 60, taken: 240, remaining: 0 };
+        assert_eq!(headroom.exceeded_message(), "you cannot take overtime for more than 4 hours a day, 0 minutes remaining");
+    }
+
+    #[test]
+    fn test_overtime_rounding() {
+        let rounding = |mode| OvertimeRounding { minutes: 15, mode };
+
+        assert_eq!(rounding(RoundingMode::Up).round(31), 45);
+        assert_eq!(rounding(RoundingMode::Up).round(30), 30);
+        assert_eq!(rounding(RoundingMode::Down).round(44), 30);
+        assert_eq!(rounding(RoundingMode::Nearest).round(37), 30);
+        assert_eq!(rounding(RoundingMode::Nearest).round(38), 45);
+        assert_eq!(rounding(RoundingMode::Nearest).round(7), 0);
+
+        assert_eq!(OvertimeRounding::default().round(31), 31);
+    }
+
+    #[test]
+    fn test_overtime_tier_amount() {
+        // 1 hour at 1.5x and 2 hours at 2x of 30k per hour
+        let amount = StatutoryOvertimeRate.tiers(OvertimeDay::Workday, 3 * 60, 5).iter()
+            .map(|tier| tier.amount(30_000))
+            .sum::<i64>();
+
+        assert_eq!(amount, 45_000 + 120_000);
+    }
+}
@@ -2,11 +2,35 @@ use actix_web::web;
 
 mod auth;
 mod attendance;
+mod checkin_policy;
+mod exchange_rate;
+mod holiday;
+mod leave;
+mod reimbursement;
+mod reimbursement_category;
+mod schedule;
+mod tax_profile;
 
 pub fn config(cfg: &mut web::ServiceConfig) {
     cfg
         .service(web::scope("/auth")
             .configure(auth::config))
         .service(web::scope("/attendance")
-            .configure(attendance::config));
+            .configure(attendance::config))
+        .service(web::scope("/checkin_policy")
+            .configure(checkin_policy::config))
+        .service(web::scope("/exchange_rate")
+            .configure(exchange_rate::config))
+        .service(web::scope("/holiday")
+            .configure(holiday::config))
+        .service(web::scope("/leave")
+            .configure(leave::config))
+        .service(web::scope("/reimbursement")
+            .configure(reimbursement::config))
+        .service(web::scope("/reimbursement_category")
+            .configure(reimbursement_category::config))
+        .service(web::scope("/schedule")
+            .configure(schedule::config))
+        .service(web::scope("/tax_profile")
+            .configure(tax_profile::config));
 }
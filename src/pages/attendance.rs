@@ -1,16 +1,21 @@
-use std::str::FromStr;
+use std::{collections::{hash_map::Entry, BTreeMap, HashMap, HashSet}, net::IpAddr, str::FromStr};
 
-use actix_web::{dev, get, post, web, FromRequest, HttpRequest, HttpResponse, Responder};
-use chrono::{Datelike, Local, Timelike, Utc, Weekday};
+use actix_web::{delete, dev, get, post, put, web, FromRequest, HttpRequest, HttpResponse, Responder};
+use chrono::{DateTime, Datelike as _, FixedOffset, Local, TimeZone as _, Utc};
 use futures_util::future::LocalBoxFuture;
-use sea_orm::{prelude::DateTimeWithTimeZone, ActiveValue::{Set, Unchanged}, ColumnTrait, DatabaseConnection, EntityTrait, PaginatorTrait, QueryFilter};
+use sea_orm::{prelude::{DateTimeWithTimeZone, Expr}, sea_query::OnConflict, ActiveEnum as _, ActiveValue::{Set, Unchanged}, ColumnTrait, Condition, ConnectionTrait, DatabaseConnection, DbErr, EntityTrait, Iterable as _, ModelTrait as _, PaginatorTrait as _, QueryFilter, QueryOrder, QuerySelect as _, TransactionTrait as _, TryInsertResult};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-use crate::{auth::Admin, consts::{self, WORKING_HOUR}, entity::{attendance_period, employee_attendance, employee_overtime, employee_reimbursement, prelude::*, sea_orm_active_enums::RoleType, user}, pages::attendance::extractor::{ProcessedAttendance, UnprocessedAttendance}, utils};
+use crate::{auth::Admin, checkin::{self, Coordinate, TrustedProxies}, entity::{attendance_period, checkin_policy, checkin_rejection, employee_attendance, employee_overtime, employee_reimbursement, prelude::*, public_holiday, sea_orm_active_enums::{ApprovalStatus, AttendanceType, ReimbursementStatus, RoleType}, user}, lateness::{self, LatenessPolicy}, leave, overtime::{NonWorkingDayOvertime, OvertimeDay, OvertimeRatePolicy}, pages::attendance::extractor::{ProcessedAttendance, UnprocessedAttendance}, schedule::EmployeeSchedule, utils};
 
 use model::*;
 
+mod correction;
+mod dashboard;
+mod overtime;
+mod payroll;
+mod reimbursement;
 mod extractor;
 mod model;
 
@@ -18,12 +23,15 @@ pub(super) fn config(cfg: &mut web::ServiceConfig) {
     cfg
         .service(create_attendance)
         .service(get_attendance)
+        .service(get_my_calendar)
         .service(create_employee_attendance)
-        .service(create_employee_overtime)
-        .service(create_employee_reimbursement)
-        .service(process_payroll)
-        .service(get_payslip)
-        .service(get_employee_payslips);
+        .service(get_checkin_rejections)
+        .service(checkout_employee_attendance)
+        .configure(correction::config)
+        .configure(dashboard::config)
+        .configure(overtime::config)
+        .configure(payroll::config)
+        .configure(reimbursement::config);
 }
 
 #[post("")]
@@ -56,29 +64,187 @@ async fn get_attendance(attendance: attendance_period::Model) -> impl Responder
     web::Json(attendance)
 }
 
+/// Day by day view of the employee's attendance, counting the same way as their payslip
+#[get("/{attendance_id}/me")]
+async fn get_my_calendar(db: web::Data<DatabaseConnection>, employee: user::Model, attendance: attendance_period::Model) -> impl Responder {
+    let now = Utc::now().with_timezone(&attendance.created_at.timezone());
+    let schedule = load_schedule(db.as_ref(), employee.id, &attendance).await;
+    let today = schedule.work_date(now.naive_local());
+    let (period_start, period_end) = (attendance.start_at.date_naive(), attendance.end_at.date_naive());
+
+    let attendances = EmployeeAttendance::find()
+        .filter(employee_attendance::Column::AttendancePeriodId.eq(attendance.id))
+        .filter(employee_attendance::Column::CreatedBy.eq(employee.id))
+        .all(db.as_ref()).await.unwrap();
+    let attended_days = attendances.len() as u64;
+    let attendances = attendances.into_iter()
+        .map(|a| (a.work_date, a))
+        .collect::<HashMap<_, _>>();
+
+    let overtimes = EmployeeOvertime::find()
+        .filter(employee_overtime::Column::AttendancePeriodId.eq(attendance.id))
+        .filter(employee_overtime::Column::CreatedBy.eq(employee.id))
+        .all(db.as_ref()).await.unwrap();
+    let overtime_minutes = overtimes.iter()
+        .fold(HashMap::<_, i64>::new(), |mut minutes, overtime| {
+            *minutes.entry(overtime.work_date).or_default() += overtime.minutes as i64;
+            minutes
+        });
+
+    let holidays = PublicHoliday::find()
+        .filter(public_holiday::Column::Date.between(period_start, period_end))
+        .all(db.as_ref()).await.unwrap();
+    let holidays = holidays.into_iter()
+        .map(|holiday| (holiday.date, holiday.name))
+        .collect::<HashMap<_, _>>();
+
+    let leaves = leave::approved_leaves(db.as_ref(), employee.id, period_start, period_end).await;
+
+    let days = period_start.iter_days()
+        .take_while(|date| *date <= period_end)
+        .map(|date| {
+            let e_attendance = attendances.get(&date);
+
+            EmployeeCalendarDay {
+                date,
+                weekday: date.weekday(),
+                working_day: schedule.is_working_day(date),
+                holiday: holidays.get(&date).cloned(),
+                leave: leave::leave_on(&leaves, date).map(|leave| leave.leave_type),
+                checked_in: e_attendance.is_some(),
+                attendance_type: e_attendance.map(|a| a.attendance_type),
+                checked_in_at: e_attendance.map(|a| a.created_at),
+                checked_out_at: e_attendance.and_then(|a| a.checked_out_at),
+                worked_minutes: e_attendance.and_then(|a| a.worked_minutes),
+                correction: e_attendance.is_some_and(|a| a.attendance_correction_id.is_some()),
+                overtime_minutes: overtime_minutes.get(&date).copied().unwrap_or_default(),
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let absent_days = days.iter()
+        .filter(|day| day.working_day && !day.checked_in)
+        .collect::<Vec<_>>();
+
+    web::Json(
+        EmployeeCalendar {
+            period: EmployeePayslipPeriod {
+                start_at: attendance.start_at,
+                end_at: attendance.end_at,
+            },
+            summary: EmployeeCalendarSummary {
+                working_days: utils::count_working_days(attendance.start_at, attendance.end_at, &schedule),
+                attended_days,
+                paid_leave_days: absent_days.iter().filter(|day| day.leave.is_some_and(|leave_type| leave_type.is_paid())).count() as u64,
+                unpaid_leave_days: absent_days.iter().filter(|day| day.leave.is_some_and(|leave_type| !leave_type.is_paid())).count() as u64,
+                missing_days: absent_days.iter().filter(|day| day.leave.is_none() && day.date < today).count() as u64,
+                overtime_minutes: overtime_minutes.values().sum(),
+            },
+            days,
+        }
+    )
+}
+
 #[post("/{attendance_id}")]
-async fn create_employee_attendance(db: web::Data<DatabaseConnection>, employee: user::Model, attendance: UnprocessedAttendance) -> impl Responder {
+async fn create_employee_attendance(
+    req: HttpRequest,
+    db: web::Data<DatabaseConnection>,
+    trusted_proxies: web::Data<TrustedProxies>,
+    employee: user::Model,
+    attendance: UnprocessedAttendance,
+    payload: Option<web::Json<CheckIn>>,
+) -> impl Responder {
     let now = Utc::now().with_timezone(&attendance.created_at.timezone());
+    let schedule = load_schedule(db.as_ref(), employee.id, &attendance).await;
+    let work_date = schedule.work_date(now.naive_local());
+
+    if schedule.is_holiday(work_date) {
+        return Err(actix_web::error::ErrorBadRequest("cannot attend on public holiday"));
+    }
 
-    // So glad I use Rust
-    if let Weekday::Sat | Weekday::Sun = now.weekday() {
-        return Err(actix_web::error::ErrorBadRequest("cannot attend on weekend"));
+    if !schedule.for_date(work_date).is_working_day(work_date) {
+        return Err(actix_web::error::ErrorBadRequest("cannot attend on a rest day"));
     }
 
-    let (start_of_day, end_of_day) = utils::get_today_range(&now);
+    let (attendance_type, location) = payload
+        .map(|payload| (payload.attendance_type, (payload.latitude, payload.longitude)))
+        .unwrap_or_default();
+    let attendance_type = attendance_type.unwrap_or(AttendanceType::Office);
 
-    let e_attendance = EmployeeAttendance::find()
-        .filter(employee_attendance::Column::CreatedAt.between(start_of_day, end_of_day))
-        .filter(employee_attendance::Column::CreatedBy.eq(employee.id))
-        .filter(employee_attendance::Column::AttendancePeriodId.eq(attendance.id))
-        .one(db.as_ref()).await.unwrap();
-    
-    if let Some(e_attendance) = e_attendance {
-        return Ok(HttpResponse::Ok().json(web::Json(e_attendance)))
+    let location = match location {
+        (Some(latitude), Some(longitude)) => Some(Coordinate { latitude, longitude }),
+        (None, None) => None,
+        _ => return Err(actix_web::error::ErrorBadRequest("latitude and longitude must be set together")),
+    };
+
+    if location.is_some_and(|location| !location.is_valid()) {
+        return Err(actix_web::error::ErrorBadRequest("latitude or longitude is out of range"))
     }
 
+    let forwarded_for = req.headers().get_all("X-Forwarded-For")
+        .filter_map(|value| value.to_str().ok())
+        .collect::<Vec<_>>()
+        .join(",");
+    let client_ip = trusted_proxies.client_ip(req.peer_addr().map(|addr| addr.ip()), Some(&forwarded_for));
+
+    let policies = CheckinPolicy::find()
+        .filter(Condition::any()
+            .add(checkin_policy::Column::AttendancePeriodId.eq(attendance.id))
+            .add(checkin_policy::Column::AttendancePeriodId.is_null()))
+        .order_by_asc(checkin_policy::Column::CreatedAt)
+        .all(db.as_ref()).await.unwrap();
+
+    let checked = if attendance_type.requires_presence() {
+        checkin::check_all(&policies, client_ip, location)
+    } else {
+        Ok(())
+    };
+
+    if let Err(reason) = checked {
+        reject_checkin(db.as_ref(), employee.id, &attendance, client_ip, location, reason.to_string()).await;
+
+        return Err(actix_web::error::ErrorForbidden(reason))
+    }
+
+    close_forgotten_checkouts(db.as_ref(), &attendance, Some(employee.id)).await;
+
+    let txn = db.begin().await.unwrap();
+
+    // Check-ins of the employee are written one at a time, so concurrent ones can't go past the
+    // attendance type's limit together
+    User::find_by_id(employee.id)
+        .lock_exclusive()
+        .one(&txn).await.unwrap();
+
+    if let Some(max_days) = attendance_type.max_days() {
+        let taken_days = EmployeeAttendance::find()
+            .filter(employee_attendance::Column::AttendancePeriodId.eq(attendance.id))
+            .filter(employee_attendance::Column::CreatedBy.eq(employee.id))
+            .filter(employee_attendance::Column::AttendanceType.eq(attendance_type))
+            .filter(employee_attendance::Column::WorkDate.ne(work_date))
+            .count(&txn).await.unwrap();
+
+        if taken_days >= max_days {
+            // The lock is let go first, the rejection refers to the employee
+            txn.rollback().await.unwrap();
+
+            let reason = format!("you cannot take more than {max_days} {} days in this period", attendance_type.to_value());
+            reject_checkin(db.as_ref(), employee.id, &attendance, client_ip, location, reason.clone()).await;
+
+            return Err(actix_web::error::ErrorBadRequest(reason))
+        }
+    }
+
+    let shift_start = schedule.for_date(work_date).shift_start_at(work_date);
+
     let model = employee_attendance::ActiveModel {
         attendance_period_id: Set(attendance.id),
+        work_date: Set(work_date),
+        attendance_type: Set(attendance_type),
+        late_minutes: Set(lateness::minutes_past(shift_start, now.naive_local()) as i32),
+        client_ip: Set(client_ip.map(|ip| ip.to_string())),
+        latitude: Set(location.map(|l| l.latitude)),
+        longitude: Set(location.map(|l| l.longitude)),
         created_by: Set(Some(employee.id)),
         updated_by: Set(Some(employee.id)),
         created_at: Set(Local::now().fixed_offset()),
@@ -86,196 +252,176 @@ async fn create_employee_attendance(db: web::Data<DatabaseConnection>, employee:
         ..Default::default()
     };
     
-    let e_attendance = EmployeeAttendance::insert(model)
-        .exec_with_returning(db.as_ref()).await.unwrap();
-    
-    Ok(HttpResponse::Created()
-        .json(web::Json(e_attendance)))
+    // Checking-in again, even concurrently, gives back the first check-in of the day
+    let inserted = EmployeeAttendance::insert(model)
+        .on_conflict(OnConflict::columns([employee_attendance::Column::CreatedBy, employee_attendance::Column::AttendancePeriodId, employee_attendance::Column::WorkDate])
+            .do_nothing()
+            .to_owned())
+        .do_nothing()
+        .exec(&txn).await.unwrap();
+
+    let e_attendance = EmployeeAttendance::find()
+        .filter(employee_attendance::Column::WorkDate.eq(work_date))
+        .filter(employee_attendance::Column::CreatedBy.eq(employee.id))
+        .filter(employee_attendance::Column::AttendancePeriodId.eq(attendance.id))
+        .one(&txn).await.unwrap()
+        .expect("attendance must exist after checking-in");
+
+    txn.commit().await.unwrap();
+
+    match inserted {
+        TryInsertResult::Inserted(_) => Ok(HttpResponse::Created().json(web::Json(e_attendance))),
+        _ => Ok(HttpResponse::Ok().json(web::Json(e_attendance))),
+    }
 }
 
-#[post("/{attendance_id}/overtime")]
-async fn create_employee_overtime(db: web::Data<DatabaseConnection>, employee: user::Model, attendance: UnprocessedAttendance, payload: web::Json<CreateOvertime>) -> impl Responder {
+/// Keeps the attempt along with where it was made from, for admins to look into
+async fn reject_checkin<C: ConnectionTrait>(
+    db: &C,
+    employee_id: Uuid,
+    attendance: &attendance_period::Model,
+    client_ip: Option<IpAddr>,
+    location: Option<Coordinate>,
+    reason: String,
+) {
+    CheckinRejection::insert(checkin_rejection::ActiveModel {
+        created_by: Set(Some(employee_id)),
+        updated_by: Set(Some(employee_id)),
+        created_at: Set(Local::now().fixed_offset()),
+        updated_at: Set(Local::now().fixed_offset()),
+        attendance_period_id: Set(attendance.id),
+        client_ip: Set(client_ip.map(|ip| ip.to_string())),
+        latitude: Set(location.map(|l| l.latitude)),
+        longitude: Set(location.map(|l| l.longitude)),
+        reason: Set(reason),
+        ..Default::default()
+    }).exec(db).await.unwrap();
+}
+
+#[get("/{attendance_id}/checkin_rejections")]
+async fn get_checkin_rejections(db: web::Data<DatabaseConnection>, _admin: Admin, attendance: attendance_period::Model) -> impl Responder {
+    let rejections = CheckinRejection::find()
+        .filter(checkin_rejection::Column::AttendancePeriodId.eq(attendance.id))
+        .order_by_asc(checkin_rejection::Column::CreatedAt)
+        .all(db.as_ref()).await.unwrap();
+
+    web::Json(rejections)
+}
+
+#[post("/{attendance_id}/checkout")]
+async fn checkout_employee_attendance(db: web::Data<DatabaseConnection>, employee: user::Model, attendance: UnprocessedAttendance, payload: Option<web::Json<CheckOut>>) -> impl Responder {
     let now = Utc::now().with_timezone(&attendance.created_at.timezone());
+    let schedule = load_schedule(db.as_ref(), employee.id, &attendance).await;
+    let work_date = schedule.work_date(now.naive_local());
 
-    let (start_of_day, end_of_day) = utils::get_today_range(&now);
-    
-    let Some(_) = EmployeeAttendance::find()
-        .filter(employee_attendance::Column::CreatedAt.between(start_of_day, end_of_day))
+    let Some(e_attendance) = EmployeeAttendance::find()
+        .filter(employee_attendance::Column::WorkDate.eq(work_date))
         .filter(employee_attendance::Column::CreatedBy.eq(employee.id))
         .filter(employee_attendance::Column::AttendancePeriodId.eq(attendance.id))
         .one(db.as_ref()).await.unwrap()
     else {
         return Err(actix_web::error::ErrorBadRequest("you have not checked-in today"))
     };
-    
-    if now.hour() < consts::WORKING_HOUR.1 {
-        return Err(actix_web::error::ErrorBadRequest("your work hours are not done yet"))
-    };
-    
-    let existing_e_overtime = EmployeeOvertime::find()
-        .filter(employee_overtime::Column::CreatedAt.between(start_of_day, end_of_day))
-        .filter(employee_overtime::Column::CreatedBy.eq(employee.id))
-        .filter(employee_overtime::Column::AttendancePeriodId.eq(attendance.id))
-        .one(db.as_ref()).await.unwrap();
-    
-    match existing_e_overtime {
-        Some(overtime) => {
-            let new_hours = overtime.extra_hours + payload.extra_hours;
-            if new_hours > 3 {
-                return Err(actix_web::error::ErrorBadRequest("you cannot take overtime for more than 3 hours a day"))
-            }
-            
-            let model = EmployeeOvertime::update(employee_overtime::ActiveModel {
-                id: Unchanged(overtime.id),
-                updated_at: Set(Local::now().fixed_offset()),
-                updated_by: Set(Some(employee.id)),
-                extra_hours: Set(new_hours),
-                ..Default::default()
-            }).exec(db.as_ref()).await.unwrap();
-            
-            Ok(HttpResponse::Ok().json(web::Json(model)))
-        },
-        None => {
-            if payload.extra_hours > 3 {
-                return Err(actix_web::error::ErrorBadRequest("you cannot take overtime for more than 3 hours a day"))
+
+    if e_attendance.checked_out_at.is_some() {
+        return Ok(HttpResponse::Ok().json(web::Json(e_attendance)))
+    }
+
+    let (break_started_at, break_ended_at) = payload
+        .map(|payload| (payload.break_started_at, payload.break_ended_at))
+        .unwrap_or_default();
+
+    let break_minutes = match (break_started_at, break_ended_at) {
+        (Some(started_at), Some(ended_at)) => {
+            if ended_at < started_at || started_at < e_attendance.created_at || now < ended_at {
+                return Err(actix_web::error::ErrorBadRequest("break must be taken between check-in and check-out"))
             }
 
-            let model = EmployeeOvertime::insert(employee_overtime::ActiveModel {
-                created_by: Set(Some(employee.id)),
-                updated_by: Set(Some(employee.id)),
-                created_at: Set(Local::now().fixed_offset()),
-                updated_at: Set(Local::now().fixed_offset()),
-                extra_hours: Set(payload.extra_hours),
-                attendance_period_id: Set(attendance.id),
-                ..Default::default()
-            }).exec_with_returning(db.as_ref()).await.unwrap();
-            
-            Ok(HttpResponse::Created().json(web::Json(model)))
+            (ended_at - started_at).num_minutes()
         },
-    }
-}
+        // Assume the scheduled break was taken when the employee doesn't tell us otherwise
+        (None, None) => schedule.for_date(work_date).break_minutes,
+        _ => return Err(actix_web::error::ErrorBadRequest("break_started_at and break_ended_at must be set together")),
+    };
 
-#[post("/{attendance_id}/reimburse")]
-async fn create_employee_reimbursement(db: web::Data<DatabaseConnection>, employee: user::Model, attendance: UnprocessedAttendance, payload: web::Json<CreateReimbursement>) -> impl Responder {
-    let model = EmployeeReimbursement::insert(employee_reimbursement::ActiveModel {
-        created_by: Set(Some(employee.id)),
+    let model = EmployeeAttendance::update(employee_attendance::ActiveModel {
+        id: Unchanged(e_attendance.id),
         updated_by: Set(Some(employee.id)),
-        created_at: Set(Local::now().fixed_offset()),
         updated_at: Set(Local::now().fixed_offset()),
-        description: Set(payload.description.clone()),
-        amount: Set(payload.amount),
-        attendance_period_id: Set(attendance.id),
+        checked_out_at: Set(Some(now)),
+        break_started_at: Set(break_started_at),
+        break_ended_at: Set(break_ended_at),
+        worked_minutes: Set(Some(utils::count_worked_minutes(e_attendance.created_at, now, break_minutes) as i32)),
+        early_leave_minutes: Set(lateness::minutes_past(now.naive_local(), e_attendance.attendance_type.shift_end_at(schedule.for_date(work_date), work_date)) as i32),
         ..Default::default()
-    }).exec_with_returning(db.as_ref()).await.unwrap();
+    }).exec(db.as_ref()).await.unwrap();
 
-    HttpResponse::Created().json(web::Json(model))
+    Ok(HttpResponse::Ok().json(web::Json(model)))
 }
 
-#[post("/{attendance_id}/process_payroll")]
-async fn process_payroll(db: web::Data<DatabaseConnection>, admin: Admin, attendance: UnprocessedAttendance) -> impl Responder {
-    let model = AttendancePeriod::update(attendance_period::ActiveModel {
-        id: Unchanged(attendance.id),
-        processed: Set(true),
-        updated_by: Set(Some(admin.id)),
-        updated_at: Set(Local::now().fixed_offset()),
-        ..Default::default()
-    }).exec(db.as_ref()).await.unwrap();
+/// Employee's schedule knowing the public holidays of the period and of today
+async fn load_schedule<C: ConnectionTrait>(db: &C, employee_id: Uuid, attendance: &attendance_period::Model) -> EmployeeSchedule {
+    let today = Utc::now().with_timezone(&attendance.created_at.timezone()).date_naive();
 
-    HttpResponse::Ok().json(web::Json(model))
+    EmployeeSchedule::load(db, employee_id, attendance.start_at.date_naive().min(today), attendance.end_at.date_naive().max(today)).await
 }
 
-async fn generate_employee_payslip(
-    db: &DatabaseConnection,
-    employee: user::Model,
-    attendance: &ProcessedAttendance,
-) -> EmployeePayslip {
-    let attendance_days = EmployeeAttendance::find()
-        .filter(employee_attendance::Column::AttendancePeriodId.eq(attendance.id))
-        .filter(employee_attendance::Column::CreatedBy.eq(employee.id))
-        .count(db).await.unwrap();
-
-    let overtimes = EmployeeOvertime::find()
-        .filter(employee_overtime::Column::AttendancePeriodId.eq(attendance.id))
-        .filter(employee_overtime::Column::CreatedBy.eq(employee.id))
-        .all(db).await.unwrap();
-    
-    let reimbursements = EmployeeReimbursement::find()
-        .filter(employee_reimbursement::Column::AttendancePeriodId.eq(attendance.id))
-        .filter(employee_reimbursement::Column::CreatedBy.eq(employee.id))
-        .all(db).await.unwrap();
-    
-    let total_working_days = utils::count_working_days(attendance.start_at, attendance.end_at);
+/// The attendance closed as if the employee checked-out right at the end of their shift, or
+/// `None` while the shift is still on
+fn close_at_shift_end(e_attendance: &employee_attendance::Model, schedule: &EmployeeSchedule, now: DateTime<FixedOffset>) -> Option<employee_attendance::Model> {
+    let work_schedule = schedule.for_date(e_attendance.work_date);
+    let shift_end = now.timezone().from_local_datetime(&e_attendance.attendance_type.shift_end_at(work_schedule, e_attendance.work_date)).unwrap();
 
-    let hourly_rate = employee.salary / ((total_working_days * (WORKING_HOUR.1 - WORKING_HOUR.0) as i64));
-    let overtime_rate = hourly_rate * 2;
-    
-    let res_overtimes = overtimes.into_iter().map(|overtime|
-        EmployeePayslipOvertime {
-            date: overtime.updated_at,
-            hours: overtime.extra_hours,
-            amount: (overtime_rate * overtime.extra_hours as i64),
-        }
-    ).collect::<Vec<_>>();
-    
-    let res_reimbursements = reimbursements.into_iter().map(|reimbursement| 
-        EmployeePayslipReimbursement {
-            description: reimbursement.description,
-            amount: reimbursement.amount,
-        }
-    ).collect::<Vec<_>>();
-    
-    let overtime_total = res_overtimes.iter().map(|o| o.amount).reduce(|a, b| a + b).unwrap_or_default();
-    let reimbursement_total = res_reimbursements.iter().map(|r| r.amount).reduce(|a, b| a + b).unwrap_or_default();
-    let prorated_amount = (employee.salary * attendance_days as i64) / total_working_days;
-
-    EmployeePayslip {
-        employee: EmployeePayslipEmployee {
-            id: employee.id,
-            username: employee.username,
-            base_salary: employee.salary,
-        },
-        period: EmployeePayslipPeriod {
-            start_at: attendance.start_at,
-            end_at: attendance.end_at,
-        },
-        attendance: EmployeePayslipAttendance {
-            total_days: attendance_days,
-            prorated_amount,
-        },
-        overtimes: res_overtimes,
-        reimbursements: res_reimbursements,
-        summary: EmployeePayslipSummary {
-            base_salary: employee.salary,
-            prorated_amount,
-            overtime_total,
-            reimbursement_total,
-            take_home_pay: prorated_amount + overtime_total + reimbursement_total,
-        },
+    if now < shift_end {
+        return None
     }
-}
 
-#[get("/{attendance_id}/payslip")]
-async fn get_payslip(db: web::Data<DatabaseConnection>, employee: user::Model, attendance: ProcessedAttendance) -> impl Responder {
-    let payslip = generate_employee_payslip(&db, employee, &attendance).await;
-    web::Json(payslip)
+    let checked_out_at = shift_end.max(e_attendance.created_at);
+
+    Some(employee_attendance::Model {
+        checked_out_at: Some(checked_out_at),
+        worked_minutes: Some(utils::count_worked_minutes(e_attendance.created_at, checked_out_at, work_schedule.break_minutes) as i32),
+        auto_checked_out: true,
+        ..e_attendance.clone()
+    })
 }
 
-#[get("/{attendance_id}/employee_payslips")]
-async fn get_employee_payslips(db: web::Data<DatabaseConnection>, _admin: Admin, attendance: ProcessedAttendance) -> impl Responder {
-    let employees = User::find()
-        .filter(user::Column::Role.eq(RoleType::Employee))
-        .all(db.as_ref()).await.unwrap();
-    
-    let payslips = futures_util::future::join_all(
-        employees.into_iter().map(|employee|
-            generate_employee_payslip(&db, employee, &attendance)
-        )
-    ).await;
+/// Closes every attendance whose shift has ended without a check-out, as if the employee
+/// checked-out right at the end of their shift, and flags it as such
+async fn close_forgotten_checkouts<C: ConnectionTrait>(db: &C, attendance: &attendance_period::Model, employee_id: Option<Uuid>) {
+    let now = Utc::now().with_timezone(&attendance.created_at.timezone());
 
-    web::Json(
-        EmployeePayslips {
-            total_take_home: payslips.iter().map(|p| p.summary.take_home_pay).reduce(|a, b| a + b).unwrap_or_default(),
-            payslips,
+    let mut query = EmployeeAttendance::find()
+        .filter(employee_attendance::Column::AttendancePeriodId.eq(attendance.id))
+        .filter(employee_attendance::Column::CheckedOutAt.is_null());
+    if let Some(employee_id) = employee_id {
+        query = query.filter(employee_attendance::Column::CreatedBy.eq(employee_id));
+    }
+
+    let open_attendances = query.all(db).await.unwrap();
+
+    let mut schedules = HashMap::new();
+
+    for e_attendance in open_attendances {
+        let Some(employee_id) = e_attendance.created_by else {
+            continue
+        };
+
+        if let Entry::Vacant(entry) = schedules.entry(employee_id) {
+            entry.insert(load_schedule(db, employee_id, attendance).await);
         }
-    )
+
+        let Some(closed) = close_at_shift_end(&e_attendance, &schedules[&employee_id], now) else {
+            continue
+        };
+
+        EmployeeAttendance::update(employee_attendance::ActiveModel {
+            id: Unchanged(closed.id),
+            updated_at: Set(Local::now().fixed_offset()),
+            checked_out_at: Set(closed.checked_out_at),
+            worked_minutes: Set(closed.worked_minutes),
+            auto_checked_out: Set(closed.auto_checked_out),
+            ..Default::default()
+        }).exec(db).await.unwrap();
+    }
 }
@@ -0,0 +1,248 @@
+use sea_orm::TransactionTrait as _;
+
+use crate::entity::attendance_correction;
+
+use super::*;
+
+pub(super) fn config(cfg: &mut web::ServiceConfig) {
+    cfg
+        .service(create_correction)
+        .service(get_corrections)
+        .service(get_my_corrections)
+        .service(approve_correction)
+        .service(reject_correction);
+}
+
+impl FromRequest for attendance_correction::Model {
+    type Error = actix_web::Error;
+    type Future = LocalBoxFuture<'static, Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _: &mut dev::Payload) -> Self::Future {
+        let req = req.clone();
+
+        Box::pin(async move {
+            let attendance = attendance_period::Model::from_request(&req, &mut dev::Payload::None).await?;
+
+            let correction_id = req.match_info().get("correction_id").expect("This extractor must be used under `correction_id` path");
+            let Ok(correction_id) = Uuid::from_str(correction_id) else {
+                return Err(actix_web::error::ErrorBadRequest("invalid `correction_id`"))
+            };
+
+            let db = req.app_data::<web::Data<DatabaseConnection>>().expect("DatabaseConnection must be attached");
+
+            let Some(correction) = AttendanceCorrection::find_by_id(correction_id)
+                .filter(attendance_correction::Column::AttendancePeriodId.eq(attendance.id))
+                .one(db.as_ref()).await.unwrap()
+            else {
+                return Err(actix_web::error::ErrorNotFound(""))
+            };
+
+            Ok(correction)
+        })
+    }
+}
+
+#[post("/{attendance_id}/correction")]
+async fn create_correction(db: web::Data<DatabaseConnection>, employee: user::Model, attendance: UnprocessedAttendance, payload: web::Json<CreateCorrection>) -> impl Responder {
+    let now = Utc::now().with_timezone(&attendance.created_at.timezone());
+    let schedule = load_schedule(db.as_ref(), employee.id, &attendance).await;
+    let work_date = payload.work_date;
+
+    if payload.reason.trim().is_empty() {
+        return Err(actix_web::error::ErrorBadRequest("reason must not be empty"))
+    }
+
+    if work_date >= schedule.work_date(now.naive_local()) {
+        return Err(actix_web::error::ErrorBadRequest("work_date must be in the past"))
+    }
+
+    if work_date < attendance.start_at.date_naive() || attendance.end_at.date_naive() < work_date {
+        return Err(actix_web::error::ErrorBadRequest("work_date is outside the attendance period"))
+    }
+
+    let day_schedule = schedule.for_date(work_date);
+
+    if !schedule.is_working_day(work_date) {
+        return Err(actix_web::error::ErrorBadRequest("work_date is not a working day"))
+    }
+
+    // Assume a full shift when the employee doesn't remember exactly
+    let checked_in_at = payload.checked_in_at
+        .unwrap_or_else(|| now.timezone().from_local_datetime(&day_schedule.shift_start_at(work_date)).unwrap());
+    let checked_out_at = payload.checked_out_at
+        .unwrap_or_else(|| now.timezone().from_local_datetime(&day_schedule.shift_end_at(work_date)).unwrap());
+
+    if checked_out_at <= checked_in_at {
+        return Err(actix_web::error::ErrorBadRequest("checked_out_at must be later than checked_in_at"))
+    }
+
+    if schedule.work_date(checked_in_at.with_timezone(&now.timezone()).naive_local()) != work_date || now < checked_out_at {
+        return Err(actix_web::error::ErrorBadRequest("checked_in_at and checked_out_at must be within work_date"))
+    }
+
+    let e_attendance = EmployeeAttendance::find()
+        .filter(employee_attendance::Column::WorkDate.eq(work_date))
+        .filter(employee_attendance::Column::CreatedBy.eq(employee.id))
+        .filter(employee_attendance::Column::AttendancePeriodId.eq(attendance.id))
+        .one(db.as_ref()).await.unwrap();
+
+    if e_attendance.is_some() {
+        return Err(actix_web::error::ErrorBadRequest("you have already checked-in on work_date"))
+    }
+
+    let pending_correction = AttendanceCorrection::find()
+        .filter(attendance_correction::Column::WorkDate.eq(work_date))
+        .filter(attendance_correction::Column::CreatedBy.eq(employee.id))
+        .filter(attendance_correction::Column::AttendancePeriodId.eq(attendance.id))
+        .filter(attendance_correction::Column::Status.eq(ApprovalStatus::Pending))
+        .one(db.as_ref()).await.unwrap();
+
+    if pending_correction.is_some() {
+        return Err(actix_web::error::ErrorBadRequest("a correction for work_date is already pending"))
+    }
+
+    let model = AttendanceCorrection::insert(attendance_correction::ActiveModel {
+        created_by: Set(Some(employee.id)),
+        updated_by: Set(Some(employee.id)),
+        created_at: Set(Local::now().fixed_offset()),
+        updated_at: Set(Local::now().fixed_offset()),
+        attendance_period_id: Set(attendance.id),
+        work_date: Set(work_date),
+        checked_in_at: Set(checked_in_at),
+        checked_out_at: Set(checked_out_at),
+        reason: Set(payload.reason.clone()),
+        ..Default::default()
+    }).exec_with_returning(db.as_ref()).await.unwrap();
+
+    Ok(
+        HttpResponse::Created()
+            .json(web::Json(model))
+    )
+}
+
+#[get("/{attendance_id}/correction")]
+async fn get_corrections(db: web::Data<DatabaseConnection>, _admin: Admin, attendance: attendance_period::Model, query: web::Query<CorrectionQuery>) -> impl Responder {
+    let mut corrections = AttendanceCorrection::find()
+        .filter(attendance_correction::Column::AttendancePeriodId.eq(attendance.id))
+        .order_by_asc(attendance_correction::Column::WorkDate);
+    if let Some(status) = query.status {
+        corrections = corrections.filter(attendance_correction::Column::Status.eq(status));
+    }
+
+    web::Json(corrections.all(db.as_ref()).await.unwrap())
+}
+
+#[get("/{attendance_id}/correction/me")]
+async fn get_my_corrections(db: web::Data<DatabaseConnection>, employee: user::Model, attendance: attendance_period::Model) -> impl Responder {
+    let corrections = AttendanceCorrection::find()
+        .filter(attendance_correction::Column::AttendancePeriodId.eq(attendance.id))
+        .filter(attendance_correction::Column::CreatedBy.eq(employee.id))
+        .order_by_asc(attendance_correction::Column::WorkDate)
+        .all(db.as_ref()).await.unwrap();
+
+    web::Json(corrections)
+}
+
+/// Approving creates the backdated attendance in the same transaction, so a correction
+/// is never approved without its attendance or the other way around
+async fn review_correction(
+    db: &DatabaseConnection,
+    admin: &Admin,
+    attendance: &attendance_period::Model,
+    correction: attendance_correction::Model,
+    status: ApprovalStatus,
+    review: ReviewCorrection,
+) -> actix_web::Result<attendance_correction::Model> {
+    if correction.status != ApprovalStatus::Pending {
+        return Err(actix_web::error::ErrorBadRequest("correction is already reviewed"))
+    }
+
+    let Some(employee_id) = correction.created_by else {
+        return Err(actix_web::error::ErrorBadRequest("employee does not exist"))
+    };
+
+    let txn = db.begin().await.unwrap();
+
+    // Whoever reviews it first wins, the status is checked again as it's updated
+    let model = AttendanceCorrection::update(attendance_correction::ActiveModel {
+        id: Unchanged(correction.id),
+        updated_by: Set(Some(admin.id)),
+        updated_at: Set(Local::now().fixed_offset()),
+        status: Set(status),
+        reviewed_by: Set(Some(admin.id)),
+        reviewed_at: Set(Some(Local::now().fixed_offset())),
+        review_comment: Set(review.comment),
+        ..Default::default()
+    })
+        .filter(attendance_correction::Column::Status.eq(ApprovalStatus::Pending))
+        .exec(&txn).await;
+
+    let model = match model {
+        Ok(model) => model,
+        Err(DbErr::RecordNotUpdated) => return Err(actix_web::error::ErrorConflict("correction was reviewed by someone else meanwhile")),
+        Err(err) => panic!("{err}"),
+    };
+
+    if status == ApprovalStatus::Approved {
+        let e_attendance = EmployeeAttendance::find()
+            .filter(employee_attendance::Column::WorkDate.eq(correction.work_date))
+            .filter(employee_attendance::Column::CreatedBy.eq(employee_id))
+            .filter(employee_attendance::Column::AttendancePeriodId.eq(attendance.id))
+            .one(&txn).await.unwrap();
+
+        if e_attendance.is_some() {
+            return Err(actix_web::error::ErrorBadRequest("employee has already checked-in on work_date"))
+        }
+
+        let timezone = attendance.created_at.timezone();
+        let schedule = load_schedule(db, employee_id, attendance).await;
+        let day_schedule = schedule.for_date(correction.work_date);
+
+        // A public holiday may have been added on the day since it was requested
+        if !schedule.is_working_day(correction.work_date) {
+            return Err(actix_web::error::ErrorBadRequest("work_date is not a working day"))
+        }
+
+        let inserted = EmployeeAttendance::insert(employee_attendance::ActiveModel {
+            attendance_period_id: Set(attendance.id),
+            work_date: Set(correction.work_date),
+            created_by: Set(Some(employee_id)),
+            updated_by: Set(Some(admin.id)),
+            created_at: Set(correction.checked_in_at),
+            updated_at: Set(Local::now().fixed_offset()),
+            checked_out_at: Set(Some(correction.checked_out_at)),
+            worked_minutes: Set(Some(utils::count_worked_minutes(correction.checked_in_at, correction.checked_out_at, day_schedule.break_minutes) as i32)),
+            late_minutes: Set(lateness::minutes_past(day_schedule.shift_start_at(correction.work_date), correction.checked_in_at.with_timezone(&timezone).naive_local()) as i32),
+            early_leave_minutes: Set(lateness::minutes_past(correction.checked_out_at.with_timezone(&timezone).naive_local(), day_schedule.shift_end_at(correction.work_date)) as i32),
+            attendance_correction_id: Set(Some(correction.id)),
+            approved_by: Set(Some(admin.id)),
+            ..Default::default()
+        })
+            .on_conflict(OnConflict::columns([employee_attendance::Column::CreatedBy, employee_attendance::Column::AttendancePeriodId, employee_attendance::Column::WorkDate])
+                .do_nothing()
+                .to_owned())
+            .do_nothing()
+            .exec(&txn).await.unwrap();
+
+        // The employee checked-in while the correction was being approved
+        if let TryInsertResult::Conflicted = inserted {
+            return Err(actix_web::error::ErrorBadRequest("employee has already checked-in on work_date"))
+        }
+    }
+
+    txn.commit().await.unwrap();
+
+    Ok(model)
+}
+
+#[post("/{attendance_id}/correction/{correction_id}/approve")]
+async fn approve_correction(db: web::Data<DatabaseConnection>, admin: Admin, attendance: UnprocessedAttendance, correction: attendance_correction::Model, payload: Option<web::Json<ReviewCorrection>>) -> impl Responder {
+    review_correction(&db, &admin, &attendance, correction, ApprovalStatus::Approved, payload.map(|p| p.into_inner()).unwrap_or_default()).await
+        .map(web::Json)
+}
+
+#[post("/{attendance_id}/correction/{correction_id}/reject")]
+async fn reject_correction(db: web::Data<DatabaseConnection>, admin: Admin, attendance: UnprocessedAttendance, correction: attendance_correction::Model, payload: Option<web::Json<ReviewCorrection>>) -> impl Responder {
+    review_correction(&db, &admin, &attendance, correction, ApprovalStatus::Rejected, payload.map(|p| p.into_inner()).unwrap_or_default()).await
+        .map(web::Json)
+}
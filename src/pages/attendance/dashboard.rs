@@ -0,0 +1,193 @@
+use chrono::NaiveDate;
+
+use crate::consts::ATTENDANCE_RATIO_THRESHOLD;
+
+use super::*;
+
+pub(super) fn config(cfg: &mut web::ServiceConfig) {
+    cfg
+        .service(get_dashboard)
+        .service(get_missing_attendances);
+}
+
+/// How a single day of the period went for an employee
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DayStatus {
+    NotWorking,
+    Attended,
+    Leave { paid: bool },
+    /// Working day that has passed without attendance nor leave
+    Missing,
+    /// Working day that hasn't passed yet
+    Upcoming,
+}
+
+pub(super) struct EmployeeDays {
+    pub(super) employee: user::Model,
+    working_days: i64,
+    /// Part of the salary not earned on attended days because of their type, e.g. half-days
+    unearned_percent: i64,
+    days: Vec<(NaiveDate, DayStatus)>,
+}
+
+impl EmployeeDays {
+    fn count(&self, status: DayStatus) -> u64 {
+        self.days.iter().filter(|(_, s)| *s == status).count() as u64
+    }
+
+    pub(super) fn missing_dates(&self) -> Vec<NaiveDate> {
+        self.days.iter()
+            .filter(|(_, status)| *status == DayStatus::Missing)
+            .map(|(date, _)| *date)
+            .collect()
+    }
+
+    fn summary(&self, threshold: f64) -> DashboardEmployee {
+        let attended_days = self.count(DayStatus::Attended);
+        let paid_leave_days = self.count(DayStatus::Leave { paid: true });
+        let unpaid_leave_days = self.count(DayStatus::Leave { paid: false });
+        let missing_dates = self.missing_dates();
+
+        // Upcoming days are assumed to be attended, so this is the best the employee can still get
+        let projected_ratio = if self.working_days > 0 {
+            let lost_percent = (missing_dates.len() as i64 + unpaid_leave_days as i64) * 100 + self.unearned_percent;
+            (self.working_days * 100 - lost_percent).max(0) as f64 / (self.working_days * 100) as f64
+        } else {
+            1.0
+        };
+
+        DashboardEmployee {
+            id: self.employee.id,
+            username: self.employee.username.clone(),
+            expected_days: self.working_days,
+            attended_days,
+            paid_leave_days,
+            unpaid_leave_days,
+            missing_dates,
+            projected_ratio: (projected_ratio * 100.0).round() / 100.0,
+            below_threshold: projected_ratio < threshold,
+        }
+    }
+}
+
+/// Classifies every day of the period the same way the payslip counts them
+async fn load_employee_days(db: &DatabaseConnection, attendance: &attendance_period::Model, employee: user::Model, attendances: HashMap<NaiveDate, AttendanceType>) -> EmployeeDays {
+    let now = Utc::now().with_timezone(&attendance.created_at.timezone());
+    let schedule = load_schedule(db, employee.id, attendance).await;
+    let today = schedule.work_date(now.naive_local());
+    let (period_start, period_end) = (attendance.start_at.date_naive(), attendance.end_at.date_naive());
+
+    let leaves = leave::approved_leaves(db, employee.id, period_start, period_end).await;
+
+    let days = period_start.iter_days()
+        .take_while(|date| *date <= period_end)
+        .map(|date| {
+            let status = if attendances.contains_key(&date) {
+                DayStatus::Attended
+            } else if !schedule.is_working_day(date) {
+                DayStatus::NotWorking
+            } else if let Some(leave) = leave::leave_on(&leaves, date) {
+                DayStatus::Leave { paid: leave.leave_type.is_paid() }
+            } else if date < today {
+                DayStatus::Missing
+            } else {
+                DayStatus::Upcoming
+            };
+
+            (date, status)
+        })
+        .collect();
+
+    EmployeeDays {
+        working_days: utils::count_working_days(attendance.start_at, attendance.end_at, &schedule),
+        unearned_percent: attendances.values().map(|attendance_type| 100 - attendance_type.weight_percent()).sum(),
+        employee,
+        days,
+    }
+}
+
+pub(super) async fn load_all_employee_days(db: &DatabaseConnection, attendance: &attendance_period::Model) -> Vec<EmployeeDays> {
+    let employees = User::find()
+        .filter(user::Column::Role.eq(RoleType::Employee))
+        .order_by_asc(user::Column::Username)
+        .all(db).await.unwrap();
+
+    let attendances = EmployeeAttendance::find()
+        .filter(employee_attendance::Column::AttendancePeriodId.eq(attendance.id))
+        .all(db).await.unwrap();
+    let mut attendances = attendances.into_iter()
+        .filter_map(|a| a.created_by.map(|employee_id| (employee_id, a)))
+        .fold(HashMap::<_, HashMap<_, _>>::new(), |mut attendances, (employee_id, a)| {
+            attendances.entry(employee_id).or_default().insert(a.work_date, a.attendance_type);
+            attendances
+        });
+
+    futures_util::future::join_all(
+        employees.into_iter().map(|employee| {
+            let employee_attendances = attendances.remove(&employee.id).unwrap_or_default();
+            load_employee_days(db, attendance, employee, employee_attendances)
+        })
+    ).await
+}
+
+fn parse_threshold(query: &DashboardQuery) -> actix_web::Result<f64> {
+    let threshold = query.threshold.unwrap_or(ATTENDANCE_RATIO_THRESHOLD);
+
+    if !(0.0..=1.0).contains(&threshold) {
+        return Err(actix_web::error::ErrorBadRequest("threshold must be between 0 and 1"))
+    }
+
+    Ok(threshold)
+}
+
+#[get("/{attendance_id}/dashboard")]
+async fn get_dashboard(db: web::Data<DatabaseConnection>, _admin: Admin, attendance: attendance_period::Model, query: web::Query<DashboardQuery>) -> impl Responder {
+    let threshold = parse_threshold(&query)?;
+    let employee_days = load_all_employee_days(&db, &attendance).await;
+
+    let (period_start, period_end) = (attendance.start_at.date_naive(), attendance.end_at.date_naive());
+    let days = period_start.iter_days()
+        .take_while(|date| *date <= period_end)
+        .enumerate()
+        .map(|(i, date)| {
+            let count = |matches: fn(DayStatus) -> bool| employee_days.iter()
+                .filter(|e| matches(e.days[i].1))
+                .count() as u64;
+
+            DashboardDay {
+                date,
+                expected: count(|status| status != DayStatus::NotWorking),
+                checked_in: count(|status| status == DayStatus::Attended),
+                on_leave: count(|status| matches!(status, DayStatus::Leave { .. })),
+                missing: count(|status| status == DayStatus::Missing),
+            }
+        })
+        .collect();
+
+    Ok::<_, actix_web::Error>(web::Json(
+        AttendanceDashboard {
+            period: EmployeePayslipPeriod {
+                start_at: attendance.start_at,
+                end_at: attendance.end_at,
+            },
+            threshold,
+            days,
+            employees: employee_days.iter().map(|e| e.summary(threshold)).collect(),
+        }
+    ))
+}
+
+/// Employees with missing days or who would be paid less than the threshold, so they can be
+/// chased before payroll is processed
+#[get("/{attendance_id}/missing")]
+async fn get_missing_attendances(db: web::Data<DatabaseConnection>, _admin: Admin, attendance: attendance_period::Model, query: web::Query<DashboardQuery>) -> impl Responder {
+    let threshold = parse_threshold(&query)?;
+    let employee_days = load_all_employee_days(&db, &attendance).await;
+
+    let employees = employee_days.iter()
+        .map(|e| e.summary(threshold))
+        .filter(|e| !e.missing_dates.is_empty() || e.below_threshold)
+        .collect::<Vec<_>>();
+
+    Ok::<_, actix_web::Error>(web::Json(employees))
+}
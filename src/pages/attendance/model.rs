@@ -1,4 +1,8 @@
-use chrono::{DateTime, FixedOffset};
+use chrono::{DateTime, FixedOffset, NaiveDate, Weekday};
+
+use sea_orm::JsonValue;
+
+use crate::{entity::{payroll_run, reimbursement_flag, sea_orm_active_enums::{LeaveType, PayslipLineKind, PtkpStatus, ReimbursementStatus}}, overtime::OvertimeLimitScope, tax::TerCategory};
 
 use super::*;
 
@@ -9,14 +13,219 @@ pub(super) struct CreateAttendance {
 }
 
 #[derive(Debug, Serialize, Deserialize)]
-pub(super) struct CreateOvertime {
-    pub(super) extra_hours: i16,
+pub(super) struct CheckIn {
+    /// Defaults to office
+    pub(super) attendance_type: Option<AttendanceType>,
+    pub(super) latitude: Option<f64>,
+    pub(super) longitude: Option<f64>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(super) struct CheckOut {
+    pub(super) break_started_at: Option<DateTimeWithTimeZone>,
+    pub(super) break_ended_at: Option<DateTimeWithTimeZone>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(super) struct CreateCorrection {
+    pub(super) work_date: NaiveDate,
+    /// Defaults to the shift start of `work_date`
+    pub(super) checked_in_at: Option<DateTimeWithTimeZone>,
+    /// Defaults to the shift end of `work_date`
+    pub(super) checked_out_at: Option<DateTimeWithTimeZone>,
+    pub(super) reason: String,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub(super) struct ReviewCorrection {
+    pub(super) comment: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(super) struct CorrectionQuery {
+    pub(super) status: Option<ApprovalStatus>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(super) struct SetOvertime {
+    pub(super) started_at: DateTimeWithTimeZone,
+    pub(super) ended_at: DateTimeWithTimeZone,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(super) struct OvertimeHeadroomQuery {
+    pub(super) date: Option<NaiveDate>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(super) struct OvertimeHeadroomResponse {
+    pub(super) work_date: NaiveDate,
+    pub(super) day: OvertimeDay,
+    /// Of the tightest limit
+    pub(super) remaining_minutes: i64,
+    pub(super) limits: Vec<OvertimeLimitHeadroom>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(super) struct OvertimeLimitHeadroom {
+    pub(super) scope: OvertimeLimitScope,
+    pub(super) limit_minutes: i64,
+    pub(super) taken_minutes: i64,
+    pub(super) remaining_minutes: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(super) struct CreateOvertimePreapproval {
+    pub(super) work_date: NaiveDate,
+    pub(super) reason: String,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub(super) struct ReviewOvertime {
+    pub(super) comment: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(super) struct ReviewOvertimes {
+    pub(super) overtime_ids: Vec<Uuid>,
+    pub(super) comment: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(super) struct OvertimeQuery {
+    pub(super) status: Option<ApprovalStatus>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(super) struct ProcessPayrollQuery {
+    /// Processes the payroll even though some overtime is still pending or some claims are still
+    /// submitted, leaving them unpaid
+    #[serde(default)]
+    pub(super) force: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub(super) struct CreateReimbursement {
+    pub(super) category_id: Uuid,
     pub(super) description: String,
+    /// In the minor unit of `currency`, e.g. cents for USD
     pub(super) amount: i64,
+    /// Rupiah when not set
+    pub(super) currency: Option<String>,
+    /// Picks the exchange rate, today when not set
+    pub(super) transaction_date: Option<NaiveDate>,
+}
+
+/// Fields not set are left as they are
+#[derive(Debug, Serialize, Deserialize)]
+pub(super) struct UpdateReimbursement {
+    pub(super) category_id: Option<Uuid>,
+    pub(super) description: Option<String>,
+    pub(super) amount: Option<i64>,
+    pub(super) currency: Option<String>,
+    pub(super) transaction_date: Option<NaiveDate>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub(super) struct ApproveReimbursement {
+    /// In rupiah, approves the whole claimed amount when not set
+    pub(super) approved_amount: Option<i64>,
+    pub(super) comment: Option<String>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub(super) struct ReviewReimbursement {
+    pub(super) comment: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(super) struct ReimbursementQuery {
+    pub(super) status: Option<ReimbursementStatus>,
+    /// Only claims screening has flagged, or only those it hasn't
+    pub(super) flagged: Option<bool>,
+}
+
+/// A claim in the review queue along with why screening thinks it needs a closer look
+#[derive(Debug, Serialize, Deserialize)]
+pub(super) struct QueuedReimbursement {
+    #[serde(flatten)]
+    pub(super) reimbursement: employee_reimbursement::Model,
+    pub(super) flags: Vec<reimbursement_flag::Model>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(super) struct EmployeeCalendar {
+    pub(super) period: EmployeePayslipPeriod,
+    pub(super) days: Vec<EmployeeCalendarDay>,
+    pub(super) summary: EmployeeCalendarSummary,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(super) struct EmployeeCalendarDay {
+    pub(super) date: NaiveDate,
+    pub(super) weekday: Weekday,
+    /// Public holidays are never working days
+    pub(super) working_day: bool,
+    pub(super) holiday: Option<String>,
+    /// Only approved leaves
+    pub(super) leave: Option<LeaveType>,
+    pub(super) checked_in: bool,
+    pub(super) attendance_type: Option<AttendanceType>,
+    pub(super) checked_in_at: Option<DateTimeWithTimeZone>,
+    pub(super) checked_out_at: Option<DateTimeWithTimeZone>,
+    pub(super) worked_minutes: Option<i32>,
+    /// Attendance was backdated through an approved correction
+    pub(super) correction: bool,
+    pub(super) overtime_minutes: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(super) struct EmployeeCalendarSummary {
+    pub(super) working_days: i64,
+    pub(super) attended_days: u64,
+    pub(super) paid_leave_days: u64,
+    pub(super) unpaid_leave_days: u64,
+    /// Past working days without attendance nor leave, which a correction may still cover
+    pub(super) missing_days: u64,
+    pub(super) overtime_minutes: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(super) struct DashboardQuery {
+    /// Defaults to [`crate::consts::ATTENDANCE_RATIO_THRESHOLD`]
+    pub(super) threshold: Option<f64>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(super) struct AttendanceDashboard {
+    pub(super) period: EmployeePayslipPeriod,
+    pub(super) threshold: f64,
+    pub(super) days: Vec<DashboardDay>,
+    pub(super) employees: Vec<DashboardEmployee>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(super) struct DashboardDay {
+    pub(super) date: NaiveDate,
+    /// Employees for whom the date is a working day
+    pub(super) expected: u64,
+    pub(super) checked_in: u64,
+    pub(super) on_leave: u64,
+    pub(super) missing: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(super) struct DashboardEmployee {
+    pub(super) id: Uuid,
+    pub(super) username: String,
+    pub(super) expected_days: i64,
+    pub(super) attended_days: u64,
+    pub(super) paid_leave_days: u64,
+    pub(super) unpaid_leave_days: u64,
+    pub(super) missing_dates: Vec<NaiveDate>,
+    /// Ratio of the base salary the employee is paid if they attend every remaining working day
+    pub(super) projected_ratio: f64,
+    pub(super) below_threshold: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -24,8 +233,13 @@ pub(super) struct EmployeePayslip {
     pub(super) employee: EmployeePayslipEmployee,
     pub(super) period: EmployeePayslipPeriod,
     pub(super) attendance: EmployeePayslipAttendance,
+    pub(super) lateness: EmployeePayslipLateness,
     pub(super) overtimes: Vec<EmployeePayslipOvertime>,
-    pub(super) reimbursements: Vec<EmployeePayslipReimbursement>,
+    /// Only approved claims are paid
+    pub(super) reimbursements: Vec<EmployeePayslipReimbursementCategory>,
+    pub(super) deductions: Vec<EmployeePayslipDeduction>,
+    /// How the PPh 21 deduction was worked out, `None` for employees without a tax profile
+    pub(super) tax: Option<EmployeePayslipTax>,
     pub(super) summary: EmployeePayslipSummary,
 }
 
@@ -44,20 +258,145 @@ pub(super) struct EmployeePayslipPeriod {
 
 #[derive(Debug, Serialize, Deserialize)]
 pub(super) struct EmployeePayslipAttendance {
+    /// In the employee's schedule, public holidays left out
+    pub(super) working_days: i64,
     pub(super) total_days: u64,
+    /// Attended days weighted by their type, which is what the prorated amount is based on
+    pub(super) weighted_days: f64,
+    pub(super) types: Vec<EmployeePayslipAttendanceType>,
+    pub(super) worked_minutes: i64,
+    pub(super) worked_hours: f64,
+    /// Days the employee forgot to check-out, which were closed at the end of their shift
+    pub(super) auto_checked_out_days: u64,
+    /// Working days on paid leave, which count as attended in the prorated amount
+    pub(super) paid_leave_days: u64,
+    pub(super) unpaid_leave_days: u64,
     pub(super) prorated_amount: i64,
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+pub(super) struct EmployeePayslipAttendanceType {
+    pub(super) attendance_type: AttendanceType,
+    pub(super) days: u64,
+    pub(super) weight: f64,
+    pub(super) weighted_days: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(super) struct EmployeePayslipLateness {
+    /// Only the days the employee was late or left early
+    pub(super) days: Vec<EmployeePayslipLatenessDay>,
+    /// Late and early minutes beyond the grace period over the whole period
+    pub(super) counted_minutes: i64,
+    pub(super) warning: bool,
+    pub(super) deducted_minutes: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(super) struct EmployeePayslipLatenessDay {
+    pub(super) work_date: NaiveDate,
+    pub(super) late_minutes: i32,
+    pub(super) early_leave_minutes: i32,
+    pub(super) counted_minutes: i64,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub(super) struct EmployeePayslipOvertime {
     pub(super) date: DateTime<FixedOffset>,
-    pub(super) hours: i16,
+    pub(super) work_date: NaiveDate,
+    pub(super) day: OvertimeDay,
+    /// Rounded minutes of every entry of the day
+    pub(super) minutes: i64,
+    pub(super) amount: i64,
+    pub(super) tiers: Vec<EmployeePayslipOvertimeTier>,
+    pub(super) entries: Vec<EmployeePayslipOvertimeEntry>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(super) struct EmployeePayslipOvertimeEntry {
+    /// Unknown for overtime recorded in hours
+    pub(super) started_at: Option<DateTimeWithTimeZone>,
+    pub(super) ended_at: Option<DateTimeWithTimeZone>,
+    pub(super) minutes: i32,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(super) struct EmployeePayslipOvertimeTier {
+    pub(super) hours: f64,
+    pub(super) multiplier: f64,
     pub(super) amount: i64,
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+pub(super) struct EmployeePayslipReimbursementCategory {
+    pub(super) category_id: Uuid,
+    pub(super) name: String,
+    pub(super) amount: i64,
+    pub(super) claims: Vec<EmployeePayslipReimbursement>,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub(super) struct EmployeePayslipReimbursement {
     pub(super) description: String,
+    pub(super) currency: String,
+    /// In the minor unit of `currency`
+    pub(super) original_amount: i64,
+    /// Millionths of a rupiah per unit of `currency` the claim was converted at
+    pub(super) exchange_rate: i64,
+    pub(super) claimed_amount: i64,
+    /// What was approved and paid, which may be less than claimed
+    pub(super) amount: i64,
+    pub(super) review_comment: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(super) struct EmployeePayslipDeduction {
+    pub(super) description: String,
+    pub(super) amount: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(super) struct EmployeePayslipTax {
+    pub(super) ptkp_status: PtkpStatus,
+    pub(super) npwp: bool,
+    /// The period's gross income the tax is withheld from
+    pub(super) taxable_income: i64,
+    pub(super) method: EmployeePayslipTaxMethod,
+    pub(super) tax: i64,
+    /// 20% of `tax` without a taxpayer number
+    pub(super) surcharge: i64,
+    /// Negative when more was withheld earlier than is due, which is paid back
+    pub(super) amount: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(super) enum EmployeePayslipTaxMethod {
+    Ter {
+        category: TerCategory,
+        /// In percent
+        rate: f64,
+        /// Gross income of every period ending in the month, which the rate is of
+        monthly_income: i64,
+        /// By the month's earlier payroll runs
+        withheld_before: i64,
+    },
+    AnnualReconciliation {
+        year: i32,
+        months: i64,
+        gross_income: i64,
+        occupational_cost: i64,
+        ptkp: i64,
+        taxable_income: i64,
+        brackets: Vec<EmployeePayslipTaxBracket>,
+        withheld_before: i64,
+    },
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(super) struct EmployeePayslipTaxBracket {
+    pub(super) taxable_income: i64,
+    /// In percent
+    pub(super) rate: f64,
     pub(super) amount: i64,
 }
 
@@ -67,11 +406,99 @@ pub(super) struct EmployeePayslipSummary {
     pub(super) prorated_amount: i64,
     pub(super) overtime_total: i64,
     pub(super) reimbursement_total: i64,
+    /// Earned in the period, which is what income tax is withheld from
+    pub(super) taxable_income: i64,
+    /// Negative when it's paid back
+    pub(super) income_tax: i64,
+    /// Income tax withheld included, what's paid back is added to the take-home pay instead
+    pub(super) deduction_total: i64,
     pub(super) take_home_pay: i64,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub(super) struct EmployeePayslips {
-    pub(super) payslips: Vec<EmployeePayslip>,
+    pub(super) payroll_run: payroll_run::Model,
+    pub(super) payslips: Vec<PersistedPayslip>,
+    pub(super) total_take_home: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(super) struct EmployeePayslipLine {
+    pub(super) kind: PayslipLineKind,
+    pub(super) description: String,
+    /// Deductions are positive as well, `kind` tells which way it goes
+    pub(super) amount: i64,
+}
+
+/// A payslip as it was computed when the payroll was processed
+#[derive(Debug, Serialize, Deserialize)]
+pub(super) struct PersistedPayslip {
+    /// [`EmployeePayslip`] as the engine of `engine_version` computed it
+    #[serde(flatten)]
+    pub(super) snapshot: JsonValue,
+    pub(super) payroll_run_id: Uuid,
+    pub(super) engine_version: i32,
+    pub(super) processed_at: DateTimeWithTimeZone,
+    pub(super) lines: Vec<EmployeePayslipLine>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(super) struct PayrollPreviewQuery {
+    /// Only the payslip of this employee, every employee's when not set
+    pub(super) employee_id: Option<Uuid>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub(super) enum PayrollWarningKind {
+    MissingAttendance,
+    NoWorkingDays,
+    NoAttendedDays,
+    PendingOvertime,
+    SubmittedReimbursement,
+    PendingCorrection,
+    PendingLeave,
+    MissingTaxProfile,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(super) struct PayrollWarning {
+    pub(super) kind: PayrollWarningKind,
+    pub(super) message: String,
+}
+
+/// Payroll of a period as if it were processed now, nothing in it is final
+#[derive(Debug, Serialize, Deserialize)]
+pub(super) struct PayrollPreview {
+    /// Always true, as opposed to the payslips of a processed payroll
+    pub(super) provisional: bool,
+    pub(super) as_of: DateTimeWithTimeZone,
+    pub(super) engine_version: i32,
+    /// Of every employee previewed together
+    pub(super) warnings: Vec<PayrollWarning>,
+    pub(super) summary: PayrollPreviewSummary,
+    pub(super) payslips: Vec<PreviewPayslip>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(super) struct PayrollPreviewSummary {
+    pub(super) employees: u64,
+    pub(super) prorated_total: i64,
+    pub(super) overtime_total: i64,
+    pub(super) reimbursement_total: i64,
+    pub(super) deduction_total: i64,
     pub(super) total_take_home: i64,
 }
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(super) struct PreviewPayslip {
+    #[serde(flatten)]
+    pub(super) payslip: EmployeePayslip,
+    pub(super) warnings: Vec<PayrollWarning>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(super) struct ProcessedPayroll {
+    #[serde(flatten)]
+    pub(super) attendance: attendance_period::Model,
+    pub(super) payroll_run: payroll_run::Model,
+}
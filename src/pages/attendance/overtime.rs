@@ -0,0 +1,560 @@
+use chrono::{Days, NaiveDate};
+use sea_orm::{prelude::Expr, ConnectionTrait, DatabaseTransaction, QuerySelect as _, Select, TransactionTrait as _};
+
+use crate::{entity::overtime_preapproval, overtime::{OvertimeLimits, OvertimeRounding, OvertimeTaken}};
+
+use super::*;
+
+pub(super) fn config(cfg: &mut web::ServiceConfig) {
+    cfg
+        .service(create_overtime)
+        .service(update_overtime)
+        .service(delete_overtime)
+        .service(get_overtime_headroom)
+        .service(get_overtimes)
+        .service(approve_overtimes)
+        .service(reject_overtimes)
+        .service(approve_overtime)
+        .service(reject_overtime)
+        .service(create_preapproval)
+        .service(get_preapprovals)
+        .service(get_my_preapprovals)
+        .service(approve_preapproval)
+        .service(reject_preapproval);
+}
+
+impl FromRequest for employee_overtime::Model {
+    type Error = actix_web::Error;
+    type Future = LocalBoxFuture<'static, Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _: &mut dev::Payload) -> Self::Future {
+        let req = req.clone();
+
+        Box::pin(async move {
+            let attendance = attendance_period::Model::from_request(&req, &mut dev::Payload::None).await?;
+
+            let overtime_id = req.match_info().get("overtime_id").expect("This extractor must be used under `overtime_id` path");
+            let Ok(overtime_id) = Uuid::from_str(overtime_id) else {
+                return Err(actix_web::error::ErrorBadRequest("invalid `overtime_id`"))
+            };
+
+            let db = req.app_data::<web::Data<DatabaseConnection>>().expect("DatabaseConnection must be attached");
+
+            let Some(overtime) = EmployeeOvertime::find_by_id(overtime_id)
+                .filter(employee_overtime::Column::AttendancePeriodId.eq(attendance.id))
+                .one(db.as_ref()).await.unwrap()
+            else {
+                return Err(actix_web::error::ErrorNotFound(""))
+            };
+
+            Ok(overtime)
+        })
+    }
+}
+
+impl FromRequest for overtime_preapproval::Model {
+    type Error = actix_web::Error;
+    type Future = LocalBoxFuture<'static, Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _: &mut dev::Payload) -> Self::Future {
+        let req = req.clone();
+
+        Box::pin(async move {
+            let attendance = attendance_period::Model::from_request(&req, &mut dev::Payload::None).await?;
+
+            let preapproval_id = req.match_info().get("preapproval_id").expect("This extractor must be used under `preapproval_id` path");
+            let Ok(preapproval_id) = Uuid::from_str(preapproval_id) else {
+                return Err(actix_web::error::ErrorBadRequest("invalid `preapproval_id`"))
+            };
+
+            let db = req.app_data::<web::Data<DatabaseConnection>>().expect("DatabaseConnection must be attached");
+
+            let Some(preapproval) = OvertimePreapproval::find_by_id(preapproval_id)
+                .filter(overtime_preapproval::Column::AttendancePeriodId.eq(attendance.id))
+                .one(db.as_ref()).await.unwrap()
+            else {
+                return Err(actix_web::error::ErrorNotFound(""))
+            };
+
+            Ok(preapproval)
+        })
+    }
+}
+
+async fn sum_overtime_minutes<C: ConnectionTrait>(conn: &C, overtimes: Select<EmployeeOvertime>) -> i64 {
+    overtimes
+        .select_only()
+        .column_as(Expr::col(employee_overtime::Column::Minutes).sum(), "minutes")
+        .into_tuple::<Option<i64>>()
+        .one(conn).await.unwrap()
+        .flatten()
+        .unwrap_or_default()
+}
+
+/// Overtime the employee has taken in the day, week and period of `work_date` other than
+/// `overtime_id`, rejected entries don't count and neither does the week's overtime on rest days
+/// and public holidays
+async fn load_overtime_taken<C: ConnectionTrait>(conn: &C, employee_id: Uuid, attendance_id: Uuid, work_date: NaiveDate, overtime_id: Option<Uuid>) -> OvertimeTaken {
+    let mut overtimes = EmployeeOvertime::find()
+        .filter(employee_overtime::Column::CreatedBy.eq(employee_id))
+        .filter(employee_overtime::Column::Status.ne(ApprovalStatus::Rejected));
+    if let Some(overtime_id) = overtime_id {
+        overtimes = overtimes.filter(employee_overtime::Column::Id.ne(overtime_id));
+    }
+
+    let week_start = work_date - Days::new(work_date.weekday().num_days_from_monday() as u64);
+    let week_end = week_start + Days::new(6);
+
+    let schedule = EmployeeSchedule::load(conn, employee_id, week_start, week_end).await;
+    let week_minutes = overtimes.clone()
+        .filter(employee_overtime::Column::WorkDate.between(week_start, week_end))
+        .select_only()
+        .column(employee_overtime::Column::WorkDate)
+        .column(employee_overtime::Column::Minutes)
+        .into_tuple::<(NaiveDate, i32)>()
+        .all(conn).await.unwrap()
+        .into_iter()
+        .filter(|(date, _)| OvertimeDay::of(&schedule, *date) == OvertimeDay::Workday)
+        .map(|(_, minutes)| minutes as i64)
+        .sum();
+
+    OvertimeTaken {
+        day_minutes: sum_overtime_minutes(conn, overtimes.clone().filter(employee_overtime::Column::WorkDate.eq(work_date))).await,
+        week_minutes,
+        period_minutes: sum_overtime_minutes(conn, overtimes.filter(employee_overtime::Column::AttendancePeriodId.eq(attendance_id))).await,
+    }
+}
+
+/// Checks an overtime entry against the employee's shift and their other entries, then works out
+/// the day it belongs to and its rounded minutes. `overtime_id` is of the entry being edited, so it
+/// doesn't count against itself
+#[allow(clippy::too_many_arguments)]
+async fn validate_overtime(
+    db: &DatabaseConnection,
+    txn: &DatabaseTransaction,
+    non_working_day_overtime: &NonWorkingDayOvertime,
+    rounding: &OvertimeRounding,
+    limits: &OvertimeLimits,
+    employee: &user::Model,
+    attendance: &attendance_period::Model,
+    payload: &SetOvertime,
+    overtime_id: Option<Uuid>,
+) -> actix_web::Result<(NaiveDate, i64)> {
+    let now = Utc::now().with_timezone(&attendance.created_at.timezone());
+    let schedule = load_schedule(db, employee.id, attendance).await;
+
+    if payload.ended_at <= payload.started_at {
+        return Err(actix_web::error::ErrorBadRequest("ended_at must be later than started_at"))
+    }
+
+    if now < payload.ended_at {
+        return Err(actix_web::error::ErrorBadRequest("ended_at must not be in the future"))
+    }
+
+    let started_at = payload.started_at.with_timezone(&now.timezone()).naive_local();
+    let work_date = schedule.work_date(started_at);
+    let day = OvertimeDay::of(&schedule, work_date);
+
+    if work_date < attendance.start_at.date_naive() || attendance.end_at.date_naive() < work_date {
+        return Err(actix_web::error::ErrorBadRequest("overtime is outside the attendance period"))
+    }
+
+    if day == OvertimeDay::Workday {
+        let e_attendance = EmployeeAttendance::find()
+            .filter(employee_attendance::Column::WorkDate.eq(work_date))
+            .filter(employee_attendance::Column::CreatedBy.eq(employee.id))
+            .filter(employee_attendance::Column::AttendancePeriodId.eq(attendance.id))
+            .one(db).await.unwrap();
+
+        if e_attendance.is_none() {
+            return Err(actix_web::error::ErrorBadRequest("you have not checked-in on that day"))
+        }
+
+        if started_at < schedule.for_date(work_date).shift_end_at(work_date) {
+            return Err(actix_web::error::ErrorBadRequest("overtime must start after your shift ends"))
+        }
+    } else if non_working_day_overtime.require_preapproval {
+        let preapproval = OvertimePreapproval::find()
+            .filter(overtime_preapproval::Column::WorkDate.eq(work_date))
+            .filter(overtime_preapproval::Column::CreatedBy.eq(employee.id))
+            .filter(overtime_preapproval::Column::AttendancePeriodId.eq(attendance.id))
+            .filter(overtime_preapproval::Column::Status.eq(ApprovalStatus::Approved))
+            .one(db).await.unwrap();
+
+        if preapproval.is_none() {
+            return Err(actix_web::error::ErrorBadRequest("your overtime on that day has not been pre-approved"))
+        }
+    }
+
+    let minutes = rounding.round((payload.ended_at - payload.started_at).num_minutes());
+    if minutes == 0 {
+        return Err(actix_web::error::ErrorBadRequest("overtime is too short to be counted"))
+    }
+
+    // Entries of the employee are written one at a time, so concurrent requests can't overlap
+    // each other or go past the limits together
+    User::find_by_id(employee.id)
+        .lock_exclusive()
+        .one(txn).await.unwrap();
+
+    let mut overlapping = EmployeeOvertime::find()
+        .filter(employee_overtime::Column::CreatedBy.eq(employee.id))
+        .filter(employee_overtime::Column::Status.ne(ApprovalStatus::Rejected))
+        .filter(employee_overtime::Column::StartedAt.lt(payload.ended_at))
+        .filter(employee_overtime::Column::EndedAt.gt(payload.started_at));
+    if let Some(overtime_id) = overtime_id {
+        overlapping = overlapping.filter(employee_overtime::Column::Id.ne(overtime_id));
+    }
+
+    if overlapping.one(txn).await.unwrap().is_some() {
+        return Err(actix_web::error::ErrorBadRequest("overtime overlaps with another entry"))
+    }
+
+    let taken = load_overtime_taken(txn, employee.id, attendance.id, work_date, overtime_id).await;
+    if let Some(headroom) = limits.exceeded(day, taken, minutes) {
+        return Err(actix_web::error::ErrorBadRequest(headroom.exceeded_message()))
+    }
+
+    Ok((work_date, minutes))
+}
+
+#[post("/{attendance_id}/overtime")]
+async fn create_overtime(
+    db: web::Data<DatabaseConnection>,
+    non_working_day_overtime: web::Data<NonWorkingDayOvertime>,
+    rounding: web::Data<OvertimeRounding>,
+    limits: web::Data<OvertimeLimits>,
+    employee: user::Model,
+    attendance: UnprocessedAttendance,
+    payload: web::Json<SetOvertime>,
+) -> impl Responder {
+    let txn = db.begin().await.unwrap();
+
+    let (work_date, minutes) = validate_overtime(&db, &txn, &non_working_day_overtime, &rounding, &limits, &employee, &attendance, &payload, None).await?;
+
+    let model = EmployeeOvertime::insert(employee_overtime::ActiveModel {
+        created_by: Set(Some(employee.id)),
+        updated_by: Set(Some(employee.id)),
+        created_at: Set(Local::now().fixed_offset()),
+        updated_at: Set(Local::now().fixed_offset()),
+        attendance_period_id: Set(attendance.id),
+        work_date: Set(work_date),
+        started_at: Set(Some(payload.started_at)),
+        ended_at: Set(Some(payload.ended_at)),
+        minutes: Set(minutes as i32),
+        ..Default::default()
+    }).exec_with_returning(&txn).await.unwrap();
+
+    txn.commit().await.unwrap();
+
+    Ok::<_, actix_web::Error>(
+        HttpResponse::Created()
+            .json(web::Json(model))
+    )
+}
+
+/// Changed entries haven't been signed-off yet, so they go back to pending
+#[allow(clippy::too_many_arguments)]
+#[put("/{attendance_id}/overtime/{overtime_id}")]
+async fn update_overtime(
+    db: web::Data<DatabaseConnection>,
+    non_working_day_overtime: web::Data<NonWorkingDayOvertime>,
+    rounding: web::Data<OvertimeRounding>,
+    limits: web::Data<OvertimeLimits>,
+    employee: user::Model,
+    attendance: UnprocessedAttendance,
+    overtime: employee_overtime::Model,
+    payload: web::Json<SetOvertime>,
+) -> impl Responder {
+    if overtime.created_by != Some(employee.id) {
+        return Err(actix_web::error::ErrorNotFound(""))
+    }
+
+    let txn = db.begin().await.unwrap();
+
+    let (work_date, minutes) = validate_overtime(&db, &txn, &non_working_day_overtime, &rounding, &limits, &employee, &attendance, &payload, Some(overtime.id)).await?;
+
+    let model = EmployeeOvertime::update(employee_overtime::ActiveModel {
+        id: Unchanged(overtime.id),
+        updated_by: Set(Some(employee.id)),
+        updated_at: Set(Local::now().fixed_offset()),
+        work_date: Set(work_date),
+        started_at: Set(Some(payload.started_at)),
+        ended_at: Set(Some(payload.ended_at)),
+        minutes: Set(minutes as i32),
+        status: Set(ApprovalStatus::Pending),
+        reviewed_by: Set(None),
+        reviewed_at: Set(None),
+        review_comment: Set(None),
+        ..Default::default()
+    }).exec(&txn).await.unwrap();
+
+    txn.commit().await.unwrap();
+
+    Ok(web::Json(model))
+}
+
+#[delete("/{attendance_id}/overtime/{overtime_id}")]
+async fn delete_overtime(db: web::Data<DatabaseConnection>, employee: user::Model, _attendance: UnprocessedAttendance, overtime: employee_overtime::Model) -> impl Responder {
+    if overtime.created_by != Some(employee.id) {
+        return Err(actix_web::error::ErrorNotFound(""))
+    }
+
+    overtime.delete(db.as_ref()).await.unwrap();
+
+    Ok(HttpResponse::NoContent())
+}
+
+/// How much more overtime the employee can take on `date`, which defaults to today
+#[get("/{attendance_id}/overtime/headroom")]
+async fn get_overtime_headroom(
+    db: web::Data<DatabaseConnection>,
+    limits: web::Data<OvertimeLimits>,
+    employee: user::Model,
+    attendance: attendance_period::Model,
+    query: web::Query<OvertimeHeadroomQuery>,
+) -> impl Responder {
+    let now = Utc::now().with_timezone(&attendance.created_at.timezone());
+    let schedule = load_schedule(db.as_ref(), employee.id, &attendance).await;
+    let work_date = query.date.unwrap_or_else(|| schedule.work_date(now.naive_local()));
+
+    if work_date < attendance.start_at.date_naive() || attendance.end_at.date_naive() < work_date {
+        return Err(actix_web::error::ErrorBadRequest("date is outside the attendance period"))
+    }
+
+    let day = OvertimeDay::of(&schedule, work_date);
+    let taken = load_overtime_taken(db.as_ref(), employee.id, attendance.id, work_date, None).await;
+    let limits = limits.headroom(day, taken);
+
+    Ok(web::Json(
+        OvertimeHeadroomResponse {
+            work_date,
+            day,
+            remaining_minutes: limits.iter().map(|headroom| headroom.remaining).min().unwrap_or_default(),
+            limits: limits.into_iter()
+                .map(|headroom| OvertimeLimitHeadroom {
+                    scope: headroom.scope,
+                    limit_minutes: headroom.limit,
+                    taken_minutes: headroom.taken,
+                    remaining_minutes: headroom.remaining,
+                })
+                .collect(),
+        }
+    ))
+}
+
+#[get("/{attendance_id}/overtime")]
+async fn get_overtimes(db: web::Data<DatabaseConnection>, _admin: Admin, attendance: attendance_period::Model, query: web::Query<OvertimeQuery>) -> impl Responder {
+    let mut overtimes = EmployeeOvertime::find()
+        .filter(employee_overtime::Column::AttendancePeriodId.eq(attendance.id))
+        .order_by_asc(employee_overtime::Column::WorkDate)
+        .order_by_asc(employee_overtime::Column::StartedAt);
+    if let Some(status) = query.status {
+        overtimes = overtimes.filter(employee_overtime::Column::Status.eq(status));
+    }
+
+    web::Json(overtimes.all(db.as_ref()).await.unwrap())
+}
+
+/// Reviews every overtime at once, so either all of them are reviewed or none is
+async fn review_overtimes(
+    db: &DatabaseConnection,
+    admin: &Admin,
+    attendance: &attendance_period::Model,
+    overtime_ids: &[Uuid],
+    status: ApprovalStatus,
+    comment: Option<String>,
+) -> actix_web::Result<Vec<employee_overtime::Model>> {
+    if overtime_ids.is_empty() {
+        return Err(actix_web::error::ErrorBadRequest("overtime_ids must not be empty"))
+    }
+
+    let txn = db.begin().await.unwrap();
+
+    let overtimes = EmployeeOvertime::find()
+        .filter(employee_overtime::Column::Id.is_in(overtime_ids.iter().copied()))
+        .filter(employee_overtime::Column::AttendancePeriodId.eq(attendance.id))
+        .all(&txn).await.unwrap();
+
+    if overtimes.len() != overtime_ids.iter().collect::<HashSet<_>>().len() {
+        return Err(actix_web::error::ErrorBadRequest("overtime does not exist in this attendance period"))
+    }
+
+    if overtimes.iter().any(|overtime| overtime.status != ApprovalStatus::Pending) {
+        return Err(actix_web::error::ErrorBadRequest("overtime is already reviewed"))
+    }
+
+    let mut models = Vec::with_capacity(overtimes.len());
+
+    for overtime in overtimes {
+        let model = EmployeeOvertime::update(employee_overtime::ActiveModel {
+            id: Unchanged(overtime.id),
+            updated_by: Set(Some(admin.id)),
+            updated_at: Set(Local::now().fixed_offset()),
+            status: Set(status),
+            reviewed_by: Set(Some(admin.id)),
+            reviewed_at: Set(Some(Local::now().fixed_offset())),
+            review_comment: Set(comment.clone()),
+            ..Default::default()
+        })
+            // The entry may have been edited since it was loaded, which needs its own review
+            .filter(employee_overtime::Column::Status.eq(ApprovalStatus::Pending))
+            .filter(employee_overtime::Column::UpdatedAt.eq(overtime.updated_at))
+            .exec(&txn).await;
+
+        match model {
+            Ok(model) => models.push(model),
+            Err(DbErr::RecordNotUpdated) => return Err(actix_web::error::ErrorConflict("overtime was changed while being reviewed")),
+            Err(err) => panic!("{err}"),
+        }
+    }
+
+    txn.commit().await.unwrap();
+
+    Ok(models)
+}
+
+#[post("/{attendance_id}/overtime/approve")]
+async fn approve_overtimes(db: web::Data<DatabaseConnection>, admin: Admin, attendance: UnprocessedAttendance, payload: web::Json<ReviewOvertimes>) -> impl Responder {
+    let payload = payload.into_inner();
+
+    review_overtimes(&db, &admin, &attendance, &payload.overtime_ids, ApprovalStatus::Approved, payload.comment).await
+        .map(web::Json)
+}
+
+#[post("/{attendance_id}/overtime/reject")]
+async fn reject_overtimes(db: web::Data<DatabaseConnection>, admin: Admin, attendance: UnprocessedAttendance, payload: web::Json<ReviewOvertimes>) -> impl Responder {
+    let payload = payload.into_inner();
+
+    review_overtimes(&db, &admin, &attendance, &payload.overtime_ids, ApprovalStatus::Rejected, payload.comment).await
+        .map(web::Json)
+}
+
+#[post("/{attendance_id}/overtime/{overtime_id}/approve")]
+async fn approve_overtime(db: web::Data<DatabaseConnection>, admin: Admin, attendance: UnprocessedAttendance, overtime: employee_overtime::Model, payload: Option<web::Json<ReviewOvertime>>) -> impl Responder {
+    let payload = payload.map(|p| p.into_inner()).unwrap_or_default();
+
+    review_overtimes(&db, &admin, &attendance, &[overtime.id], ApprovalStatus::Approved, payload.comment).await
+        .map(|mut models| web::Json(models.remove(0)))
+}
+
+#[post("/{attendance_id}/overtime/{overtime_id}/reject")]
+async fn reject_overtime(db: web::Data<DatabaseConnection>, admin: Admin, attendance: UnprocessedAttendance, overtime: employee_overtime::Model, payload: Option<web::Json<ReviewOvertime>>) -> impl Responder {
+    let payload = payload.map(|p| p.into_inner()).unwrap_or_default();
+
+    review_overtimes(&db, &admin, &attendance, &[overtime.id], ApprovalStatus::Rejected, payload.comment).await
+        .map(|mut models| web::Json(models.remove(0)))
+}
+
+/// Asks beforehand to take overtime on a rest day or public holiday
+#[post("/{attendance_id}/overtime/preapproval")]
+async fn create_preapproval(db: web::Data<DatabaseConnection>, employee: user::Model, attendance: UnprocessedAttendance, payload: web::Json<CreateOvertimePreapproval>) -> impl Responder {
+    let now = Utc::now().with_timezone(&attendance.created_at.timezone());
+    let schedule = load_schedule(db.as_ref(), employee.id, &attendance).await;
+    let work_date = payload.work_date;
+
+    if payload.reason.trim().is_empty() {
+        return Err(actix_web::error::ErrorBadRequest("reason must not be empty"))
+    }
+
+    if work_date < schedule.work_date(now.naive_local()) {
+        return Err(actix_web::error::ErrorBadRequest("work_date must not be in the past"))
+    }
+
+    if work_date < attendance.start_at.date_naive() || attendance.end_at.date_naive() < work_date {
+        return Err(actix_web::error::ErrorBadRequest("work_date is outside the attendance period"))
+    }
+
+    if OvertimeDay::of(&schedule, work_date) == OvertimeDay::Workday {
+        return Err(actix_web::error::ErrorBadRequest("work_date is a working day"))
+    }
+
+    let existing_preapproval = OvertimePreapproval::find()
+        .filter(overtime_preapproval::Column::WorkDate.eq(work_date))
+        .filter(overtime_preapproval::Column::CreatedBy.eq(employee.id))
+        .filter(overtime_preapproval::Column::AttendancePeriodId.eq(attendance.id))
+        .filter(overtime_preapproval::Column::Status.is_in([ApprovalStatus::Pending, ApprovalStatus::Approved]))
+        .one(db.as_ref()).await.unwrap();
+
+    if existing_preapproval.is_some() {
+        return Err(actix_web::error::ErrorBadRequest("a pre-approval for work_date is already pending or approved"))
+    }
+
+    let model = OvertimePreapproval::insert(overtime_preapproval::ActiveModel {
+        created_by: Set(Some(employee.id)),
+        updated_by: Set(Some(employee.id)),
+        created_at: Set(Local::now().fixed_offset()),
+        updated_at: Set(Local::now().fixed_offset()),
+        attendance_period_id: Set(attendance.id),
+        work_date: Set(work_date),
+        reason: Set(payload.reason.clone()),
+        ..Default::default()
+    }).exec_with_returning(db.as_ref()).await.unwrap();
+
+    Ok(
+        HttpResponse::Created()
+            .json(web::Json(model))
+    )
+}
+
+#[get("/{attendance_id}/overtime/preapproval")]
+async fn get_preapprovals(db: web::Data<DatabaseConnection>, _admin: Admin, attendance: attendance_period::Model, query: web::Query<OvertimeQuery>) -> impl Responder {
+    let mut preapprovals = OvertimePreapproval::find()
+        .filter(overtime_preapproval::Column::AttendancePeriodId.eq(attendance.id))
+        .order_by_asc(overtime_preapproval::Column::WorkDate);
+    if let Some(status) = query.status {
+        preapprovals = preapprovals.filter(overtime_preapproval::Column::Status.eq(status));
+    }
+
+    web::Json(preapprovals.all(db.as_ref()).await.unwrap())
+}
+
+#[get("/{attendance_id}/overtime/preapproval/me")]
+async fn get_my_preapprovals(db: web::Data<DatabaseConnection>, employee: user::Model, attendance: attendance_period::Model) -> impl Responder {
+    let preapprovals = OvertimePreapproval::find()
+        .filter(overtime_preapproval::Column::AttendancePeriodId.eq(attendance.id))
+        .filter(overtime_preapproval::Column::CreatedBy.eq(employee.id))
+        .order_by_asc(overtime_preapproval::Column::WorkDate)
+        .all(db.as_ref()).await.unwrap();
+
+    web::Json(preapprovals)
+}
+
+async fn review_preapproval(
+    db: &DatabaseConnection,
+    admin: &Admin,
+    preapproval: overtime_preapproval::Model,
+    status: ApprovalStatus,
+    review: ReviewOvertime,
+) -> actix_web::Result<overtime_preapproval::Model> {
+    let model = OvertimePreapproval::update(overtime_preapproval::ActiveModel {
+        id: Unchanged(preapproval.id),
+        updated_by: Set(Some(admin.id)),
+        updated_at: Set(Local::now().fixed_offset()),
+        status: Set(status),
+        reviewed_by: Set(Some(admin.id)),
+        reviewed_at: Set(Some(Local::now().fixed_offset())),
+        review_comment: Set(review.comment),
+        ..Default::default()
+    })
+        .filter(overtime_preapproval::Column::Status.eq(ApprovalStatus::Pending))
+        .exec(db).await;
+
+    match model {
+        Ok(model) => Ok(model),
+        Err(DbErr::RecordNotUpdated) => Err(actix_web::error::ErrorBadRequest("pre-approval is already reviewed")),
+        Err(err) => panic!("{err}"),
+    }
+}
+
+#[post("/{attendance_id}/overtime/preapproval/{preapproval_id}/approve")]
+async fn approve_preapproval(db: web::Data<DatabaseConnection>, admin: Admin, _attendance: UnprocessedAttendance, preapproval: overtime_preapproval::Model, payload: Option<web::Json<ReviewOvertime>>) -> impl Responder {
+    review_preapproval(&db, &admin, preapproval, ApprovalStatus::Approved, payload.map(|p| p.into_inner()).unwrap_or_default()).await
+        .map(web::Json)
+}
+
+#[post("/{attendance_id}/overtime/preapproval/{preapproval_id}/reject")]
+async fn reject_preapproval(db: web::Data<DatabaseConnection>, admin: Admin, _attendance: UnprocessedAttendance, preapproval: overtime_preapproval::Model, payload: Option<web::Json<ReviewOvertime>>) -> impl Responder {
+    review_preapproval(&db, &admin, preapproval, ApprovalStatus::Rejected, payload.map(|p| p.into_inner()).unwrap_or_default()).await
+        .map(web::Json)
+}
@@ -0,0 +1,829 @@
+use chrono::NaiveDate;
+use sea_orm::{DatabaseTransaction, JoinType, LoaderTrait as _, QuerySelect as _, RelationTrait as _, TransactionTrait as _};
+
+use crate::{consts::PAYROLL_ENGINE_VERSION, entity::{attendance_correction, employee_leave, payroll_run, payslip, payslip_line, sea_orm_active_enums::PayslipLineKind, tax_profile}, tax::{self, WithholdingMethod}};
+
+use super::*;
+
+pub(super) fn config(cfg: &mut web::ServiceConfig) {
+    cfg
+        .service(process_payroll)
+        .service(get_payroll_preview)
+        .service(get_payslip)
+        .service(get_employee_payslips)
+        .service(backfill_payroll_run);
+}
+
+/// Pays the period, keeping every employee's payslip as it is computed now. Later changes to
+/// salaries, policies or the period's data don't change what was paid
+#[post("/{attendance_id}/process_payroll")]
+async fn process_payroll(
+    db: web::Data<DatabaseConnection>,
+    lateness_policy: web::Data<LatenessPolicy>,
+    overtime_rate_policy: web::Data<dyn OvertimeRatePolicy>,
+    admin: Admin,
+    attendance: UnprocessedAttendance,
+    query: web::Query<ProcessPayrollQuery>,
+) -> impl Responder {
+    let txn = db.begin().await.unwrap();
+
+    // Only one request gets to process the payroll
+    let attendance = AttendancePeriod::find_by_id(attendance.id)
+        .lock_exclusive()
+        .one(&txn).await.unwrap()
+        .expect("attendance must exist");
+
+    if attendance.processed {
+        return Err(actix_web::error::ErrorBadRequest("attendance is already processed"))
+    }
+
+    let pending_overtimes = EmployeeOvertime::find()
+        .filter(employee_overtime::Column::AttendancePeriodId.eq(attendance.id))
+        .filter(employee_overtime::Column::Status.eq(ApprovalStatus::Pending))
+        .count(&txn).await.unwrap();
+
+    // Pending overtime isn't paid, and can't be reviewed anymore once the payroll is processed
+    if pending_overtimes > 0 && !query.force {
+        return Err(actix_web::error::ErrorBadRequest(format!("{pending_overtimes} overtime requests are still pending, review them first or process with `force=true` to leave them unpaid")))
+    }
+
+    let submitted_reimbursements = EmployeeReimbursement::find()
+        .filter(employee_reimbursement::Column::AttendancePeriodId.eq(attendance.id))
+        .filter(employee_reimbursement::Column::Status.eq(ReimbursementStatus::Submitted))
+        .count(&txn).await.unwrap();
+
+    // Same goes for submitted claims
+    if submitted_reimbursements > 0 && !query.force {
+        return Err(actix_web::error::ErrorBadRequest(format!("{submitted_reimbursements} reimbursement claims are still submitted, review them first or process with `force=true` to leave them unpaid")))
+    }
+
+    settle_payroll(&txn, &admin, &attendance).await;
+
+    let model = AttendancePeriod::update(attendance_period::ActiveModel {
+        id: Unchanged(attendance.id),
+        processed: Set(true),
+        updated_by: Set(Some(admin.id)),
+        updated_at: Set(Local::now().fixed_offset()),
+        ..Default::default()
+    }).exec(&txn).await.unwrap();
+
+    let payroll_run = create_payroll_run(&txn, &lateness_policy, overtime_rate_policy.as_ref(), &model, Some(admin.id), false).await;
+
+    txn.commit().await.unwrap();
+
+    Ok(HttpResponse::Ok().json(web::Json(ProcessedPayroll { attendance: model, payroll_run })))
+}
+
+/// Closes what's left open in the period when the payroll is processed, which is what the payslips
+/// are then computed from
+async fn settle_payroll(txn: &DatabaseTransaction, admin: &Admin, attendance: &attendance_period::Model) {
+    close_forgotten_checkouts(txn, attendance, None).await;
+
+    EmployeeReimbursement::update_many()
+        .col_expr(employee_reimbursement::Column::Status, ReimbursementStatus::Paid.as_enum())
+        .col_expr(employee_reimbursement::Column::UpdatedBy, Expr::value(admin.id))
+        .col_expr(employee_reimbursement::Column::UpdatedAt, Expr::value(Local::now().fixed_offset()))
+        .filter(employee_reimbursement::Column::AttendancePeriodId.eq(attendance.id))
+        .filter(employee_reimbursement::Column::Status.is_in([ReimbursementStatus::Approved, ReimbursementStatus::PartiallyApproved]))
+        .exec(txn).await.unwrap();
+}
+
+/// What the payroll would pay if it were processed now, computed from the period as processing would
+/// settle it without writing anything. Warnings point at what may still change the numbers before
+/// it's actually processed
+#[get("/{attendance_id}/payroll_preview")]
+async fn get_payroll_preview(
+    db: web::Data<DatabaseConnection>,
+    lateness_policy: web::Data<LatenessPolicy>,
+    overtime_rate_policy: web::Data<dyn OvertimeRatePolicy>,
+    _admin: Admin,
+    attendance: UnprocessedAttendance,
+    query: web::Query<PayrollPreviewQuery>,
+) -> impl Responder {
+    let mut employee_days = dashboard::load_all_employee_days(&db, &attendance).await;
+    if let Some(employee_id) = query.employee_id {
+        employee_days.retain(|e| e.employee.id == employee_id);
+
+        if employee_days.is_empty() {
+            return Err(actix_web::error::ErrorNotFound(""))
+        }
+    }
+
+    let pending = PendingApprovals::load(&db, &attendance).await;
+
+    let mut payslips = Vec::new();
+    for days in employee_days {
+        let missing_dates = days.missing_dates();
+        let payslip = generate_employee_payslip(db.as_ref(), &lateness_policy, overtime_rate_policy.as_ref(), days.employee, &attendance, true).await;
+        let warnings = pending.warnings(&payslip, &missing_dates);
+
+        payslips.push(PreviewPayslip { payslip, warnings });
+    }
+
+    let count_warned = |kind: PayrollWarningKind| payslips.iter()
+        .filter(|p| p.warnings.iter().any(|w| w.kind == kind))
+        .count();
+
+    let mut warnings = Vec::new();
+    for (kind, employees, message) in [
+        (PayrollWarningKind::MissingAttendance, count_warned(PayrollWarningKind::MissingAttendance), "have working days without attendance nor leave"),
+        (PayrollWarningKind::NoWorkingDays, count_warned(PayrollWarningKind::NoWorkingDays), "have no working days in the period and aren't paid a salary"),
+        (PayrollWarningKind::NoAttendedDays, count_warned(PayrollWarningKind::NoAttendedDays), "have no days attended nor on paid leave"),
+        (PayrollWarningKind::MissingTaxProfile, count_warned(PayrollWarningKind::MissingTaxProfile), "have no tax profile and aren't withheld income tax"),
+    ] {
+        if employees > 0 {
+            warnings.push(PayrollWarning { kind, message: format!("{employees} employees {message}") });
+        }
+    }
+    warnings.extend(pending.warnings_for(payslips.iter().map(|p| p.payslip.employee.id)));
+
+    let summary = PayrollPreviewSummary {
+        employees: payslips.len() as u64,
+        prorated_total: payslips.iter().map(|p| p.payslip.summary.prorated_amount).sum(),
+        overtime_total: payslips.iter().map(|p| p.payslip.summary.overtime_total).sum(),
+        reimbursement_total: payslips.iter().map(|p| p.payslip.summary.reimbursement_total).sum(),
+        deduction_total: payslips.iter().map(|p| p.payslip.summary.deduction_total).sum(),
+        total_take_home: payslips.iter().map(|p| p.payslip.summary.take_home_pay).sum(),
+    };
+
+    Ok(web::Json(
+        PayrollPreview {
+            provisional: true,
+            as_of: Local::now().fixed_offset(),
+            engine_version: PAYROLL_ENGINE_VERSION,
+            warnings,
+            summary,
+            payslips,
+        }
+    ))
+}
+
+/// What's still waiting on a review in the period, per employee
+struct PendingApprovals {
+    overtimes: HashMap<Uuid, u64>,
+    reimbursements: HashMap<Uuid, u64>,
+    corrections: HashMap<Uuid, u64>,
+    leaves: HashMap<Uuid, u64>,
+}
+
+impl PendingApprovals {
+    async fn load(db: &DatabaseConnection, attendance: &attendance_period::Model) -> Self {
+        fn count_by_employee(employee_ids: impl IntoIterator<Item = Option<Uuid>>) -> HashMap<Uuid, u64> {
+            employee_ids.into_iter()
+                .flatten()
+                .fold(HashMap::new(), |mut counts, employee_id| {
+                    *counts.entry(employee_id).or_default() += 1;
+                    counts
+                })
+        }
+
+        let overtimes = EmployeeOvertime::find()
+            .filter(employee_overtime::Column::AttendancePeriodId.eq(attendance.id))
+            .filter(employee_overtime::Column::Status.eq(ApprovalStatus::Pending))
+            .all(db).await.unwrap();
+
+        let reimbursements = EmployeeReimbursement::find()
+            .filter(employee_reimbursement::Column::AttendancePeriodId.eq(attendance.id))
+            .filter(employee_reimbursement::Column::Status.eq(ReimbursementStatus::Submitted))
+            .all(db).await.unwrap();
+
+        let corrections = AttendanceCorrection::find()
+            .filter(attendance_correction::Column::AttendancePeriodId.eq(attendance.id))
+            .filter(attendance_correction::Column::Status.eq(ApprovalStatus::Pending))
+            .all(db).await.unwrap();
+
+        let leaves = EmployeeLeave::find()
+            .filter(employee_leave::Column::Status.eq(ApprovalStatus::Pending))
+            .filter(employee_leave::Column::StartDate.lte(attendance.end_at.date_naive()))
+            .filter(employee_leave::Column::EndDate.gte(attendance.start_at.date_naive()))
+            .all(db).await.unwrap();
+
+        Self {
+            overtimes: count_by_employee(overtimes.into_iter().map(|o| o.created_by)),
+            reimbursements: count_by_employee(reimbursements.into_iter().map(|r| r.created_by)),
+            corrections: count_by_employee(corrections.into_iter().map(|c| c.created_by)),
+            leaves: count_by_employee(leaves.into_iter().map(|l| l.created_by)),
+        }
+    }
+
+    fn counts(&self) -> [(PayrollWarningKind, &HashMap<Uuid, u64>, &'static str); 4] {
+        [
+            (PayrollWarningKind::PendingOvertime, &self.overtimes, "overtime requests are still pending"),
+            (PayrollWarningKind::SubmittedReimbursement, &self.reimbursements, "reimbursement claims are still submitted"),
+            (PayrollWarningKind::PendingCorrection, &self.corrections, "attendance corrections are still pending"),
+            (PayrollWarningKind::PendingLeave, &self.leaves, "leave requests in the period are still pending"),
+        ]
+    }
+
+    /// Everything about a single employee's payslip that may still change before it's processed
+    fn warnings(&self, payslip: &EmployeePayslip, missing_dates: &[NaiveDate]) -> Vec<PayrollWarning> {
+        let mut warnings = Vec::new();
+
+        if !missing_dates.is_empty() {
+            warnings.push(PayrollWarning {
+                kind: PayrollWarningKind::MissingAttendance,
+                message: format!("no attendance nor leave on {}", missing_dates.iter().map(|date| date.to_string()).collect::<Vec<_>>().join(", ")),
+            });
+        }
+
+        if payslip.attendance.working_days == 0 {
+            warnings.push(PayrollWarning {
+                kind: PayrollWarningKind::NoWorkingDays,
+                message: "no working days in the period, no salary is prorated".to_string(),
+            });
+        } else if payslip.attendance.total_days == 0 && payslip.attendance.paid_leave_days == 0 {
+            warnings.push(PayrollWarning {
+                kind: PayrollWarningKind::NoAttendedDays,
+                message: "no days attended nor on paid leave".to_string(),
+            });
+        }
+
+        if payslip.tax.is_none() {
+            warnings.push(PayrollWarning {
+                kind: PayrollWarningKind::MissingTaxProfile,
+                message: "no tax profile, no income tax is withheld".to_string(),
+            });
+        }
+
+        warnings.extend(self.warnings_for([payslip.employee.id]));
+
+        warnings
+    }
+
+    /// Reviews still waiting on the employees, which aren't paid unless done before processing
+    fn warnings_for(&self, employee_ids: impl IntoIterator<Item = Uuid>) -> Vec<PayrollWarning> {
+        let employee_ids = employee_ids.into_iter().collect::<HashSet<_>>();
+
+        self.counts().into_iter()
+            .filter_map(|(kind, counts, message)| {
+                let count = counts.iter()
+                    .filter(|(employee_id, _)| employee_ids.contains(employee_id))
+                    .map(|(_, count)| count)
+                    .sum::<u64>();
+
+                (count > 0).then(|| PayrollWarning { kind, message: format!("{count} {message}") })
+            })
+            .collect()
+    }
+}
+
+/// Computes and keeps the payslip of every employee for the period, along with its lines
+async fn create_payroll_run(
+    txn: &DatabaseTransaction,
+    lateness_policy: &LatenessPolicy,
+    overtime_rate_policy: &dyn OvertimeRatePolicy,
+    attendance: &attendance_period::Model,
+    admin_id: Option<Uuid>,
+    backfilled: bool,
+) -> payroll_run::Model {
+    let employees = User::find()
+        .filter(user::Column::Role.eq(RoleType::Employee))
+        .order_by_asc(user::Column::Username)
+        .all(txn).await.unwrap();
+
+    let mut payslips = Vec::new();
+    for employee in employees {
+        payslips.push(generate_employee_payslip(txn, lateness_policy, overtime_rate_policy, employee, attendance, false).await);
+    }
+
+    let payroll_run = PayrollRun::insert(payroll_run::ActiveModel {
+        created_by: Set(admin_id),
+        updated_by: Set(admin_id),
+        created_at: Set(Local::now().fixed_offset()),
+        updated_at: Set(Local::now().fixed_offset()),
+        attendance_period_id: Set(attendance.id),
+        engine_version: Set(PAYROLL_ENGINE_VERSION),
+        total_take_home: Set(payslips.iter().map(|p| p.summary.take_home_pay).sum()),
+        backfilled: Set(backfilled),
+        ..Default::default()
+    }).exec_with_returning(txn).await.unwrap();
+
+    for payslip in payslips {
+        let model = Payslip::insert(payslip::ActiveModel {
+            created_at: Set(Local::now().fixed_offset()),
+            updated_at: Set(Local::now().fixed_offset()),
+            payroll_run_id: Set(payroll_run.id),
+            employee_id: Set(payslip.employee.id),
+            base_salary: Set(payslip.summary.base_salary),
+            prorated_amount: Set(payslip.summary.prorated_amount),
+            overtime_total: Set(payslip.summary.overtime_total),
+            reimbursement_total: Set(payslip.summary.reimbursement_total),
+            deduction_total: Set(payslip.summary.deduction_total),
+            take_home_pay: Set(payslip.summary.take_home_pay),
+            taxable_income: Set(payslip.summary.taxable_income),
+            income_tax: Set(payslip.summary.income_tax),
+            snapshot: Set(serde_json::to_value(&payslip).unwrap()),
+            ..Default::default()
+        }).exec_with_returning(txn).await.unwrap();
+
+        PayslipLine::insert_many(
+            payslip_lines(&payslip).into_iter()
+                .enumerate()
+                .map(|(position, line)| payslip_line::ActiveModel {
+                    created_at: Set(Local::now().fixed_offset()),
+                    updated_at: Set(Local::now().fixed_offset()),
+                    payslip_id: Set(model.id),
+                    position: Set(position as i32),
+                    kind: Set(line.kind),
+                    description: Set(line.description),
+                    amount: Set(line.amount),
+                    ..Default::default()
+                })
+        ).exec(txn).await.unwrap();
+    }
+
+    payroll_run
+}
+
+/// What the take-home pay is made of, from what's earned to what's deducted
+fn payslip_lines(payslip: &EmployeePayslip) -> Vec<EmployeePayslipLine> {
+    let mut lines = vec![
+        EmployeePayslipLine {
+            kind: PayslipLineKind::Salary,
+            description: format!("Salary for {} days attended or on paid leave", payslip.attendance.weighted_days + payslip.attendance.paid_leave_days as f64),
+            amount: payslip.attendance.prorated_amount,
+        },
+    ];
+
+    lines.extend(payslip.overtimes.iter().map(|overtime| EmployeePayslipLine {
+        kind: PayslipLineKind::Overtime,
+        description: format!("Overtime on {}", overtime.work_date),
+        amount: overtime.amount,
+    }));
+
+    lines.extend(payslip.reimbursements.iter().map(|category| EmployeePayslipLine {
+        kind: PayslipLineKind::Reimbursement,
+        description: format!("{} reimbursements", category.name),
+        amount: category.amount,
+    }));
+
+    lines.extend(payslip.tax.iter().filter(|tax| tax.amount < 0).map(|tax| EmployeePayslipLine {
+        kind: PayslipLineKind::TaxRefund,
+        description: income_tax_description(tax),
+        amount: -tax.amount,
+    }));
+
+    lines.extend(payslip.deductions.iter().map(|deduction| EmployeePayslipLine {
+        kind: PayslipLineKind::Deduction,
+        description: deduction.description.clone(),
+        amount: deduction.amount,
+    }));
+
+    lines
+}
+
+/// Payroll run of a processed period. Periods processed before runs were kept have none until it's
+/// backfilled
+async fn find_payroll_run(db: &DatabaseConnection, attendance: &ProcessedAttendance) -> actix_web::Result<payroll_run::Model> {
+    PayrollRun::find()
+        .filter(payroll_run::Column::AttendancePeriodId.eq(attendance.id))
+        .one(db).await.unwrap()
+        .ok_or_else(|| actix_web::error::ErrorConflict("payroll run of the period isn't backfilled yet"))
+}
+
+/// Keeps the payroll run of a period processed before runs were kept. It's computed from the
+/// period's data as it is now, which may have changed since it was paid, so it's marked as backfilled
+#[post("/{attendance_id}/backfill_payroll_run")]
+async fn backfill_payroll_run(
+    db: web::Data<DatabaseConnection>,
+    lateness_policy: web::Data<LatenessPolicy>,
+    overtime_rate_policy: web::Data<dyn OvertimeRatePolicy>,
+    admin: Admin,
+    attendance: ProcessedAttendance,
+) -> impl Responder {
+    let txn = db.begin().await.unwrap();
+
+    // Only one request gets to backfill it
+    AttendancePeriod::find_by_id(attendance.id)
+        .lock_exclusive()
+        .one(&txn).await.unwrap();
+
+    let payroll_run = PayrollRun::find()
+        .filter(payroll_run::Column::AttendancePeriodId.eq(attendance.id))
+        .one(&txn).await.unwrap();
+
+    if payroll_run.is_some() {
+        return Err(actix_web::error::ErrorConflict("payroll run of the period is already kept"))
+    }
+
+    let payroll_run = create_payroll_run(&txn, &lateness_policy, overtime_rate_policy.as_ref(), &attendance, Some(admin.id), true).await;
+
+    txn.commit().await.unwrap();
+
+    Ok(HttpResponse::Created().json(payroll_run))
+}
+
+fn persisted_payslip(payroll_run: &payroll_run::Model, payslip: payslip::Model, lines: Vec<payslip_line::Model>) -> PersistedPayslip {
+    PersistedPayslip {
+        snapshot: payslip.snapshot,
+        payroll_run_id: payroll_run.id,
+        engine_version: payroll_run.engine_version,
+        processed_at: payroll_run.created_at,
+        lines: lines.into_iter()
+            .map(|line| EmployeePayslipLine {
+                kind: line.kind,
+                description: line.description,
+                amount: line.amount,
+            })
+            .collect(),
+    }
+}
+
+/// Computes the payslip from the period's data as it is now, which is only ever kept as a snapshot
+/// once the payroll is processed. With `settle` the period is taken as processing would leave it,
+/// i.e. approved claims as paid and attendances past their shift as checked-out at its end
+async fn generate_employee_payslip<C: ConnectionTrait>(
+    db: &C,
+    lateness_policy: &LatenessPolicy,
+    overtime_rate_policy: &dyn OvertimeRatePolicy,
+    employee: user::Model,
+    attendance: &attendance_period::Model,
+    settle: bool,
+) -> EmployeePayslip {
+    let schedule = load_schedule(db, employee.id, attendance).await;
+
+    let mut attendances = EmployeeAttendance::find()
+        .filter(employee_attendance::Column::AttendancePeriodId.eq(attendance.id))
+        .filter(employee_attendance::Column::CreatedBy.eq(employee.id))
+        .all(db).await.unwrap();
+    if settle {
+        let now = Utc::now().with_timezone(&attendance.created_at.timezone());
+
+        attendances = attendances.into_iter()
+            .map(|a| match a.checked_out_at {
+                None => close_at_shift_end(&a, &schedule, now).unwrap_or(a),
+                Some(_) => a,
+            })
+            .collect();
+    }
+    let attendance_days = attendances.len() as u64;
+    let worked_minutes = attendances.iter().filter_map(|a| a.worked_minutes).map(|m| m as i64).sum::<i64>();
+
+    let overtimes = EmployeeOvertime::find()
+        .filter(employee_overtime::Column::AttendancePeriodId.eq(attendance.id))
+        .filter(employee_overtime::Column::CreatedBy.eq(employee.id))
+        .filter(employee_overtime::Column::Status.eq(ApprovalStatus::Approved))
+        .order_by_asc(employee_overtime::Column::StartedAt)
+        .all(db).await.unwrap();
+    
+    let paid_statuses = if settle {
+        vec![ReimbursementStatus::Approved, ReimbursementStatus::PartiallyApproved, ReimbursementStatus::Paid]
+    } else {
+        vec![ReimbursementStatus::Paid]
+    };
+
+    let reimbursements = EmployeeReimbursement::find()
+        .filter(employee_reimbursement::Column::AttendancePeriodId.eq(attendance.id))
+        .filter(employee_reimbursement::Column::CreatedBy.eq(employee.id))
+        .filter(employee_reimbursement::Column::Status.is_in(paid_statuses))
+        .find_also_related(ReimbursementCategory)
+        .order_by_asc(employee_reimbursement::Column::CreatedAt)
+        .all(db).await.unwrap();
+
+    let total_working_days = utils::count_working_days(attendance.start_at, attendance.end_at, &schedule);
+    let total_working_minutes = utils::count_working_minutes(attendance.start_at, attendance.end_at, &schedule);
+
+    let (period_start, period_end) = (attendance.start_at.date_naive(), attendance.end_at.date_naive());
+    let leaves = leave::approved_leaves(db, employee.id, period_start, period_end).await;
+    let attended_dates = attendances.iter().map(|a| a.work_date).collect::<HashSet<_>>();
+
+    // Working days spent on leave instead of checking-in, only paid leave counts as attended
+    let leave_days = schedule.working_days(period_start, period_end)
+        .filter(|(date, _)| !attended_dates.contains(date))
+        .filter_map(|(date, _)| leave::leave_on(&leaves, date))
+        .collect::<Vec<_>>();
+    let paid_leave_days = leave_days.iter().filter(|leave| leave.leave_type.is_paid()).count() as u64;
+    let unpaid_leave_days = leave_days.len() as u64 - paid_leave_days;
+
+    let hourly_rate = overtime_rate_policy.hourly_rate(employee.salary, total_working_minutes);
+
+    // Tiers apply to the whole day, e.g. only the first hour of the day is paid less
+    let overtime_days = overtimes.into_iter()
+        .fold(BTreeMap::<_, Vec<_>>::new(), |mut days, overtime| {
+            days.entry(overtime.work_date).or_default().push(overtime);
+            days
+        });
+
+    let res_overtimes = overtime_days.into_iter().map(|(work_date, entries)| {
+        let day = OvertimeDay::of(&schedule, work_date);
+        let working_days_per_week = schedule.for_date(work_date).working_days.count_ones();
+        let minutes = entries.iter().map(|entry| entry.minutes as i64).sum();
+
+        let tiers = overtime_rate_policy.tiers(day, minutes, working_days_per_week).into_iter()
+            .map(|tier| EmployeePayslipOvertimeTier {
+                hours: tier.minutes as f64 / 60.0,
+                multiplier: tier.multiplier_percent as f64 / 100.0,
+                amount: tier.amount(hourly_rate),
+            })
+            .collect::<Vec<_>>();
+
+        EmployeePayslipOvertime {
+            date: entries.iter().map(|entry| entry.updated_at).max().unwrap(),
+            work_date,
+            day,
+            minutes,
+            amount: tiers.iter().map(|tier| tier.amount).sum(),
+            tiers,
+            entries: entries.into_iter()
+                .map(|entry| EmployeePayslipOvertimeEntry {
+                    started_at: entry.started_at,
+                    ended_at: entry.ended_at,
+                    minutes: entry.minutes,
+                })
+                .collect(),
+        }
+    }).collect::<Vec<_>>();
+    
+    let res_reimbursements = reimbursements.into_iter()
+        .filter_map(|(reimbursement, category)| Some((category?, reimbursement)))
+        .fold(BTreeMap::<_, EmployeePayslipReimbursementCategory>::new(), |mut categories, (category, reimbursement)| {
+            let res_category = categories.entry(category.name.clone()).or_insert_with(|| EmployeePayslipReimbursementCategory {
+                category_id: category.id,
+                name: category.name,
+                amount: 0,
+                claims: Vec::new(),
+            });
+            let amount = reimbursement.approved_amount.unwrap_or(reimbursement.amount);
+
+            res_category.amount += amount;
+            res_category.claims.push(EmployeePayslipReimbursement {
+                description: reimbursement.description,
+                currency: reimbursement.currency,
+                original_amount: reimbursement.original_amount,
+                exchange_rate: reimbursement.exchange_rate,
+                claimed_amount: reimbursement.amount,
+                amount,
+                review_comment: reimbursement.review_comment,
+            });
+
+            categories
+        })
+        .into_values()
+        .collect::<Vec<_>>();
+    
+    let overtime_total = res_overtimes.iter().map(|o| o.amount).reduce(|a, b| a + b).unwrap_or_default();
+    let reimbursement_total = res_reimbursements.iter().map(|r| r.amount).reduce(|a, b| a + b).unwrap_or_default();
+
+    // Attended days are weighted by their type, e.g. a half-day only earns half a day
+    let attendance_types = AttendanceType::iter()
+        .map(|attendance_type| {
+            let days = attendances.iter().filter(|a| a.attendance_type == attendance_type).count() as u64;
+
+            EmployeePayslipAttendanceType {
+                attendance_type,
+                days,
+                weight: attendance_type.weight_percent() as f64 / 100.0,
+                weighted_days: (days as i64 * attendance_type.weight_percent()) as f64 / 100.0,
+            }
+        })
+        .filter(|t| t.days > 0)
+        .collect::<Vec<_>>();
+    let weighted_percent = attendances.iter().map(|a| a.attendance_type.weight_percent()).sum::<i64>();
+    // Nothing is prorated when the period has no working days for the employee
+    let prorated_amount = match total_working_days {
+        0 => 0,
+        _ => (employee.salary * (weighted_percent + paid_leave_days as i64 * 100)) / (total_working_days * 100),
+    };
+
+    let lateness_days = attendances.iter()
+        .filter(|a| a.late_minutes > 0 || a.early_leave_minutes > 0)
+        .map(|a| EmployeePayslipLatenessDay {
+            work_date: a.work_date,
+            late_minutes: a.late_minutes,
+            early_leave_minutes: a.early_leave_minutes,
+            counted_minutes: lateness_policy.counted_minutes(a.late_minutes as i64) + lateness_policy.counted_minutes(a.early_leave_minutes as i64),
+        })
+        .collect::<Vec<_>>();
+    let lateness_outcome = lateness_policy.evaluate(lateness_days.iter().map(|d| d.counted_minutes).sum());
+
+    let mut res_deductions = Vec::new();
+    if lateness_outcome.deducted_minutes > 0 {
+        res_deductions.push(EmployeePayslipDeduction {
+            description: format!("Lateness of {} minutes beyond the tolerated {}", lateness_outcome.deducted_minutes, lateness_outcome.counted_minutes - lateness_outcome.deducted_minutes),
+            // Deducted at the same per-minute rate the salary is earned, but never more than what was earned
+            amount: (employee.salary * lateness_outcome.deducted_minutes).checked_div(total_working_minutes).unwrap_or_default().min(prorated_amount),
+        });
+    }
+
+    // Reimbursements only pay back expenses, they aren't income
+    let taxable_income = prorated_amount + overtime_total - res_deductions.iter().map(|d| d.amount).sum::<i64>();
+
+    let tax_profile = TaxProfile::find()
+        .filter(tax_profile::Column::UserId.eq(employee.id))
+        .one(db).await.unwrap();
+
+    let res_tax = match tax_profile {
+        Some(tax_profile) => Some(withhold_income_tax(db, &tax_profile, taxable_income, attendance).await),
+        None => None,
+    };
+    let income_tax = res_tax.as_ref().map(|tax| tax.amount).unwrap_or_default();
+
+    if let Some(tax) = res_tax.as_ref().filter(|tax| tax.amount > 0) {
+        res_deductions.push(EmployeePayslipDeduction {
+            description: income_tax_description(tax),
+            amount: tax.amount,
+        });
+    }
+
+    let deduction_total = res_deductions.iter().map(|d| d.amount).sum::<i64>();
+    let tax_refund = (-income_tax).max(0);
+
+    EmployeePayslip {
+        employee: EmployeePayslipEmployee {
+            id: employee.id,
+            username: employee.username,
+            base_salary: employee.salary,
+        },
+        period: EmployeePayslipPeriod {
+            start_at: attendance.start_at,
+            end_at: attendance.end_at,
+        },
+        attendance: EmployeePayslipAttendance {
+            working_days: total_working_days,
+            total_days: attendance_days,
+            weighted_days: weighted_percent as f64 / 100.0,
+            types: attendance_types,
+            worked_minutes,
+            worked_hours: (worked_minutes as f64 / 60.0 * 100.0).round() / 100.0,
+            auto_checked_out_days: attendances.iter().filter(|a| a.auto_checked_out).count() as u64,
+            paid_leave_days,
+            unpaid_leave_days,
+            prorated_amount,
+        },
+        lateness: EmployeePayslipLateness {
+            days: lateness_days,
+            counted_minutes: lateness_outcome.counted_minutes,
+            warning: lateness_outcome.warning,
+            deducted_minutes: lateness_outcome.deducted_minutes,
+        },
+        overtimes: res_overtimes,
+        reimbursements: res_reimbursements,
+        deductions: res_deductions,
+        tax: res_tax,
+        summary: EmployeePayslipSummary {
+            base_salary: employee.salary,
+            prorated_amount,
+            overtime_total,
+            reimbursement_total,
+            taxable_income,
+            income_tax,
+            deduction_total,
+            take_home_pay: prorated_amount + overtime_total + reimbursement_total + tax_refund - deduction_total,
+        },
+    }
+}
+
+fn income_tax_description(tax: &EmployeePayslipTax) -> String {
+    match &tax.method {
+        EmployeePayslipTaxMethod::Ter { rate, monthly_income, withheld_before: 0, .. } => format!("PPh 21 at {rate}% of {monthly_income}"),
+        EmployeePayslipTaxMethod::Ter { rate, monthly_income, .. } => format!("PPh 21 at {rate}% of {monthly_income} earned this month, after what was withheld earlier"),
+        EmployeePayslipTaxMethod::AnnualReconciliation { year, .. } if tax.amount > 0 => format!("PPh 21 for {year} after what was withheld earlier"),
+        EmployeePayslipTaxMethod::AnnualReconciliation { year, .. } => format!("PPh 21 withheld over in {year}, paid back"),
+    }
+}
+
+/// PPh 21 withheld from the period's income. Periods are taxed in the month they end in, together
+/// with the month's earlier payroll runs, and the ones ending in December settle the year against
+/// what the year's earlier payroll runs withheld
+async fn withhold_income_tax<C: ConnectionTrait>(
+    db: &C,
+    tax_profile: &tax_profile::Model,
+    taxable_income: i64,
+    attendance: &attendance_period::Model,
+) -> EmployeePayslipTax {
+    let profile = tax::TaxProfile {
+        ptkp_status: tax_profile.ptkp_status,
+        has_npwp: tax_profile.npwp.is_some(),
+    };
+
+    let timezone = attendance.end_at.timezone();
+    let year = attendance.end_at.year();
+    let month = attendance.end_at.month();
+
+    // Only what was kept counts, periods aren't taxed until their payroll is processed
+    let earlier_payslips = Payslip::find()
+        .select_only()
+        .column(payslip::Column::TaxableIncome)
+        .column(payslip::Column::IncomeTax)
+        .column(attendance_period::Column::EndAt)
+        .inner_join(PayrollRun)
+        .join(JoinType::InnerJoin, payroll_run::Relation::AttendancePeriod.def())
+        .filter(payslip::Column::EmployeeId.eq(tax_profile.user_id))
+        .filter(attendance_period::Column::EndAt.gte(timezone.with_ymd_and_hms(year, 1, 1, 0, 0, 0).unwrap()))
+        .filter(attendance_period::Column::EndAt.lt(attendance.start_at))
+        .into_tuple::<(i64, i64, DateTimeWithTimeZone)>()
+        .all(db).await.unwrap()
+        .into_iter()
+        .map(|(taxable_income, income_tax, end_at)| (taxable_income, income_tax, end_at.with_timezone(&timezone).month()))
+        .collect::<Vec<_>>();
+
+    // The whole year so far, or only the month's when it's given
+    let to_date = |in_month: Option<u32>| earlier_payslips.iter()
+        .filter(|(_, _, end_month)| in_month.is_none_or(|month| *end_month == month))
+        .fold(tax::ToDate::default(), |to_date, (taxable_income, income_tax, _)| tax::ToDate {
+            taxable_income: to_date.taxable_income + taxable_income,
+            withheld: to_date.withheld + income_tax,
+        });
+
+    let withholding = if month == 12 {
+        let months = earlier_payslips.iter()
+            .map(|(_, _, month)| *month)
+            .chain([12])
+            .collect::<HashSet<_>>()
+            .len() as i64;
+
+        tax::december_withholding(profile, taxable_income, to_date(None), months)
+    } else {
+        tax::monthly_withholding(profile, taxable_income, to_date(Some(month)))
+    };
+
+    let percent = |rate: i64| rate as f64 / 100.0;
+
+    EmployeePayslipTax {
+        ptkp_status: tax_profile.ptkp_status,
+        npwp: profile.has_npwp,
+        taxable_income,
+        method: match withholding.method {
+            WithholdingMethod::Ter { category, rate, monthly_income, withheld_before } => EmployeePayslipTaxMethod::Ter {
+                category,
+                rate: percent(rate),
+                monthly_income,
+                withheld_before,
+            },
+            WithholdingMethod::AnnualReconciliation(annual) => EmployeePayslipTaxMethod::AnnualReconciliation {
+                year,
+                months: annual.months,
+                gross_income: annual.gross_income,
+                occupational_cost: annual.occupational_cost,
+                ptkp: annual.ptkp,
+                taxable_income: annual.taxable_income,
+                brackets: annual.brackets.into_iter()
+                    .map(|bracket| EmployeePayslipTaxBracket {
+                        taxable_income: bracket.taxable_income,
+                        rate: percent(bracket.rate),
+                        amount: bracket.amount,
+                    })
+                    .collect(),
+                withheld_before: annual.withheld_before,
+            },
+        },
+        tax: withholding.tax,
+        surcharge: withholding.surcharge,
+        amount: withholding.amount,
+    }
+}
+
+/// The employee's payslip as it was when the payroll was processed
+#[get("/{attendance_id}/payslip")]
+async fn get_payslip(
+    db: web::Data<DatabaseConnection>,
+    employee: user::Model,
+    attendance: ProcessedAttendance,
+) -> impl Responder {
+    let payroll_run = find_payroll_run(&db, &attendance).await?;
+
+    // Nothing was paid to whoever wasn't an employee by then
+    let Some(payslip) = Payslip::find()
+        .filter(payslip::Column::PayrollRunId.eq(payroll_run.id))
+        .filter(payslip::Column::EmployeeId.eq(employee.id))
+        .one(db.as_ref()).await.unwrap()
+    else {
+        return Err(actix_web::error::ErrorNotFound(""))
+    };
+
+    let lines = payslip.find_related(PayslipLine)
+        .order_by_asc(payslip_line::Column::Position)
+        .all(db.as_ref()).await.unwrap();
+
+    Ok(web::Json(persisted_payslip(&payroll_run, payslip, lines)))
+}
+
+#[get("/{attendance_id}/employee_payslips")]
+async fn get_employee_payslips(
+    db: web::Data<DatabaseConnection>,
+    _admin: Admin,
+    attendance: ProcessedAttendance,
+) -> impl Responder {
+    let payroll_run = find_payroll_run(&db, &attendance).await?;
+
+    let payslips = payroll_run.find_related(Payslip)
+        .inner_join(User)
+        .order_by_asc(user::Column::Username)
+        .all(db.as_ref()).await.unwrap();
+
+    let lines = payslips.load_many(
+        PayslipLine::find().order_by_asc(payslip_line::Column::Position),
+        db.as_ref(),
+    ).await.unwrap();
+
+    Ok::<_, actix_web::Error>(web::Json(
+        EmployeePayslips {
+            total_take_home: payroll_run.total_take_home,
+            payslips: payslips.into_iter()
+                .zip(lines)
+                .map(|(payslip, lines)| persisted_payslip(&payroll_run, payslip, lines))
+                .collect(),
+            payroll_run,
+        }
+    ))
+}
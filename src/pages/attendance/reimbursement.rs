@@ -0,0 +1,763 @@
+use actix_multipart::Multipart;
+use actix_web::{http::header::{ContentDisposition, DispositionParam, DispositionType}, patch, web::{Bytes, BytesMut}};
+use chrono::{Datelike as _, Duration, NaiveDate};
+use futures_util::TryStreamExt as _;
+use sea_orm::{prelude::Expr, sea_query::{Alias, Func, Query}, ConnectionTrait, DatabaseTransaction, LoaderTrait as _, QuerySelect as _, Select, TransactionTrait as _};
+
+use crate::{consts::{BASE_CURRENCY, REIMBURSEMENT_SCREENING_HISTORY_CLAIMS}, currency, entity::{reimbursement_category, reimbursement_flag, reimbursement_receipt, sea_orm_active_enums::ReimbursementFlagKind}, receipt::{self, ReceiptKind, ReceiptLimits, ReceiptStorage}, reimbursement::{self, ReimbursementClaimed, ReimbursementLimits, ReimbursementScreening}};
+
+use super::*;
+
+pub(super) fn config(cfg: &mut web::ServiceConfig) {
+    cfg
+        .service(create_reimbursement)
+        .service(update_reimbursement)
+        .service(withdraw_reimbursement)
+        .service(upload_receipts)
+        .service(get_receipts)
+        .service(download_receipt)
+        .service(get_reimbursements)
+        .service(approve_reimbursement)
+        .service(reject_reimbursement);
+}
+
+impl FromRequest for employee_reimbursement::Model {
+    type Error = actix_web::Error;
+    type Future = LocalBoxFuture<'static, Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _: &mut dev::Payload) -> Self::Future {
+        let req = req.clone();
+
+        Box::pin(async move {
+            let attendance = attendance_period::Model::from_request(&req, &mut dev::Payload::None).await?;
+
+            let reimbursement_id = req.match_info().get("reimbursement_id").expect("This extractor must be used under `reimbursement_id` path");
+            let Ok(reimbursement_id) = Uuid::from_str(reimbursement_id) else {
+                return Err(actix_web::error::ErrorBadRequest("invalid `reimbursement_id`"))
+            };
+
+            let db = req.app_data::<web::Data<DatabaseConnection>>().expect("DatabaseConnection must be attached");
+
+            let Some(reimbursement) = EmployeeReimbursement::find_by_id(reimbursement_id)
+                .filter(employee_reimbursement::Column::AttendancePeriodId.eq(attendance.id))
+                .one(db.as_ref()).await.unwrap()
+            else {
+                return Err(actix_web::error::ErrorNotFound(""))
+            };
+
+            Ok(reimbursement)
+        })
+    }
+}
+
+impl FromRequest for reimbursement_receipt::Model {
+    type Error = actix_web::Error;
+    type Future = LocalBoxFuture<'static, Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _: &mut dev::Payload) -> Self::Future {
+        let req = req.clone();
+
+        Box::pin(async move {
+            let reimbursement = employee_reimbursement::Model::from_request(&req, &mut dev::Payload::None).await?;
+
+            let receipt_id = req.match_info().get("receipt_id").expect("This extractor must be used under `receipt_id` path");
+            let Ok(receipt_id) = Uuid::from_str(receipt_id) else {
+                return Err(actix_web::error::ErrorBadRequest("invalid `receipt_id`"))
+            };
+
+            let db = req.app_data::<web::Data<DatabaseConnection>>().expect("DatabaseConnection must be attached");
+
+            let Some(receipt) = ReimbursementReceipt::find_by_id(receipt_id)
+                .filter(reimbursement_receipt::Column::EmployeeReimbursementId.eq(reimbursement.id))
+                .one(db.as_ref()).await.unwrap()
+            else {
+                return Err(actix_web::error::ErrorNotFound(""))
+            };
+
+            Ok(receipt)
+        })
+    }
+}
+
+/// Receipts of a claim are only for the claimant and admins to see
+fn can_view_receipts(user: &user::Model, reimbursement: &employee_reimbursement::Model) -> bool {
+    user.role == RoleType::Admin || reimbursement.created_by == Some(user.id)
+}
+
+async fn sum_reimbursement_amount<C: ConnectionTrait>(conn: &C, reimbursements: Select<EmployeeReimbursement>) -> i64 {
+    reimbursements
+        .select_only()
+        // Reviewed claims only count for what was approved
+        .column_as(Expr::expr(Func::coalesce([
+            Expr::col((EmployeeReimbursement, employee_reimbursement::Column::ApprovedAmount)).into(),
+            Expr::col((EmployeeReimbursement, employee_reimbursement::Column::Amount)).into(),
+        ])).sum().cast_as(Alias::new("bigint")), "amount")
+        .into_tuple::<Option<i64>>()
+        .one(conn).await.unwrap()
+        .flatten()
+        .unwrap_or_default()
+}
+
+/// What the employee has claimed under `category_id` in the period and in the year it starts in,
+/// other than `reimbursement_id`, rejected and withdrawn claims don't count
+async fn load_reimbursement_claimed<C: ConnectionTrait>(conn: &C, employee_id: Uuid, attendance: &attendance_period::Model, category_id: Uuid, reimbursement_id: Option<Uuid>) -> ReimbursementClaimed {
+    let mut reimbursements = EmployeeReimbursement::find()
+        .filter(employee_reimbursement::Column::CreatedBy.eq(employee_id))
+        .filter(employee_reimbursement::Column::CategoryId.eq(category_id))
+        .filter(employee_reimbursement::Column::Status.is_not_in([ReimbursementStatus::Rejected, ReimbursementStatus::Withdrawn]));
+    if let Some(reimbursement_id) = reimbursement_id {
+        reimbursements = reimbursements.filter(employee_reimbursement::Column::Id.ne(reimbursement_id));
+    }
+
+    let timezone = attendance.start_at.timezone();
+    let year = attendance.start_at.year();
+    let year_start = timezone.with_ymd_and_hms(year, 1, 1, 0, 0, 0).unwrap();
+    let next_year_start = timezone.with_ymd_and_hms(year + 1, 1, 1, 0, 0, 0).unwrap();
+
+    ReimbursementClaimed {
+        period_amount: sum_reimbursement_amount(conn, reimbursements.clone().filter(employee_reimbursement::Column::AttendancePeriodId.eq(attendance.id))).await,
+        yearly_amount: sum_reimbursement_amount(conn, reimbursements
+            .inner_join(AttendancePeriod)
+            .filter(attendance_period::Column::StartAt.gte(year_start))
+            .filter(attendance_period::Column::StartAt.lt(next_year_start))).await,
+    }
+}
+
+/// A claim converted to rupiah
+struct ValidatedReimbursement {
+    category: reimbursement_category::Model,
+    currency: String,
+    transaction_date: NaiveDate,
+    exchange_rate: i64,
+    amount: i64,
+}
+
+/// Converts `original_amount` of `currency` at the rate effective on `transaction_date`, giving
+/// back the rate and the amount in rupiah
+async fn convert_reimbursement<C: ConnectionTrait>(conn: &C, currency: &str, original_amount: i64, transaction_date: NaiveDate) -> actix_web::Result<(i64, i64)> {
+    let Some(exchange_rate) = currency::effective_rate(conn, currency, transaction_date).await else {
+        return Err(actix_web::error::ErrorBadRequest(format!("no {currency} exchange rate is effective on {transaction_date}")))
+    };
+
+    let Some(amount) = currency::convert(original_amount, currency, exchange_rate) else {
+        return Err(actix_web::error::ErrorBadRequest("amount is too large"))
+    };
+
+    Ok((exchange_rate, amount))
+}
+
+/// Checks a claim against its category's limits once converted to rupiah. `reimbursement_id` is
+/// of the claim being edited, so it doesn't count against itself
+async fn validate_reimbursement(
+    txn: &DatabaseTransaction,
+    employee: &user::Model,
+    attendance: &attendance_period::Model,
+    payload: &CreateReimbursement,
+    reimbursement_id: Option<Uuid>,
+) -> actix_web::Result<ValidatedReimbursement> {
+    let today = Utc::now().with_timezone(&attendance.created_at.timezone()).date_naive();
+    let currency = payload.currency.clone().unwrap_or_else(|| BASE_CURRENCY.to_string());
+    let transaction_date = payload.transaction_date.unwrap_or(today);
+
+    if payload.description.trim().is_empty() {
+        return Err(actix_web::error::ErrorBadRequest("description must not be empty"))
+    }
+
+    if payload.amount <= 0 {
+        return Err(actix_web::error::ErrorBadRequest("amount must be more than 0"))
+    }
+
+    if currency::minor_digits(&currency).is_none() {
+        return Err(actix_web::error::ErrorBadRequest("currency is not supported"))
+    }
+
+    if today < transaction_date {
+        return Err(actix_web::error::ErrorBadRequest("transaction_date must not be in the future"))
+    }
+
+    let Some(category) = ReimbursementCategory::find_by_id(payload.category_id)
+        .one(txn).await.unwrap()
+    else {
+        return Err(actix_web::error::ErrorBadRequest("category does not exist"))
+    };
+
+    let (exchange_rate, amount) = convert_reimbursement(txn, &currency, payload.amount, transaction_date).await?;
+
+    // Claims of the employee are written one at a time, so concurrent claims can't go past the
+    // limits together
+    User::find_by_id(employee.id)
+        .lock_exclusive()
+        .one(txn).await.unwrap();
+
+    let claimed = load_reimbursement_claimed(txn, employee.id, attendance, category.id, reimbursement_id).await;
+
+    if let Some(headroom) = ReimbursementLimits::from(&category).exceeded(claimed, amount) {
+        return Err(actix_web::error::ErrorBadRequest(headroom.exceeded_message(&category.name)))
+    }
+
+    Ok(ValidatedReimbursement { category, currency, transaction_date, exchange_rate, amount })
+}
+
+/// Flags what looks off about a claim for whoever reviews it, in place of what it was flagged for
+/// before. Rejected and withdrawn claims are left out, making a claim again once it's rejected or
+/// withdrawn is expected
+async fn screen_reimbursement<C: ConnectionTrait>(conn: &C, screening: &ReimbursementScreening, reimbursement: &employee_reimbursement::Model) {
+    ReimbursementFlag::delete_many()
+        .filter(reimbursement_flag::Column::EmployeeReimbursementId.eq(reimbursement.id))
+        .exec(conn).await.unwrap();
+
+    let mut flags: Vec<(ReimbursementFlagKind, String, Option<Uuid>)> = Vec::new();
+
+    let others = EmployeeReimbursement::find()
+        .filter(employee_reimbursement::Column::Id.ne(reimbursement.id))
+        .filter(employee_reimbursement::Column::Status.is_not_in([ReimbursementStatus::Rejected, ReimbursementStatus::Withdrawn]));
+
+    let window = Duration::days(screening.duplicate_window_days);
+    let description = reimbursement::normalize_description(&reimbursement.description);
+    let duplicates = others.clone()
+        .filter(employee_reimbursement::Column::CreatedBy.eq(reimbursement.created_by))
+        .filter(employee_reimbursement::Column::Currency.eq(&reimbursement.currency))
+        .filter(employee_reimbursement::Column::OriginalAmount.eq(reimbursement.original_amount))
+        .filter(employee_reimbursement::Column::TransactionDate.between(reimbursement.transaction_date - window, reimbursement.transaction_date + window))
+        .order_by_asc(employee_reimbursement::Column::TransactionDate)
+        .all(conn).await.unwrap();
+
+    for duplicate in duplicates {
+        if reimbursement::normalize_description(&duplicate.description) == description {
+            flags.push((
+                ReimbursementFlagKind::Duplicate,
+                format!("same amount and description as a claim made on {}", duplicate.transaction_date),
+                Some(duplicate.id),
+            ));
+        }
+    }
+
+    let receipts = ReimbursementReceipt::find()
+        .filter(reimbursement_receipt::Column::EmployeeReimbursementId.eq(reimbursement.id))
+        .order_by_asc(reimbursement_receipt::Column::CreatedAt)
+        .all(conn).await.unwrap();
+
+    for receipt in receipts {
+        let reused = others.clone()
+            .inner_join(ReimbursementReceipt)
+            .filter(reimbursement_receipt::Column::Sha256.eq(&receipt.sha256))
+            .order_by_asc(employee_reimbursement::Column::CreatedAt)
+            .all(conn).await.unwrap();
+
+        for other in reused {
+            let reason = if other.created_by == reimbursement.created_by {
+                format!("receipt `{}` is also attached to a claim made on {}", receipt.file_name, other.transaction_date)
+            } else {
+                format!("receipt `{}` is also attached to another employee's claim", receipt.file_name)
+            };
+
+            flags.push((ReimbursementFlagKind::ReceiptReused, reason, Some(other.id)));
+        }
+    }
+
+    let category = ReimbursementCategory::find_by_id(reimbursement.category_id)
+        .one(conn).await.unwrap()
+        .expect("category of a claim must exist");
+
+    let history = others
+        .filter(employee_reimbursement::Column::CategoryId.eq(category.id))
+        .select_only()
+        .column(employee_reimbursement::Column::Amount)
+        .order_by_desc(employee_reimbursement::Column::CreatedAt)
+        .limit(REIMBURSEMENT_SCREENING_HISTORY_CLAIMS);
+
+    let employee_history = history.clone()
+        .filter(employee_reimbursement::Column::CreatedBy.eq(reimbursement.created_by))
+        .into_tuple::<i64>()
+        .all(conn).await.unwrap();
+
+    if let Some(median) = screening.outlier(reimbursement.amount, &employee_history) {
+        flags.push((
+            ReimbursementFlagKind::EmployeeOutlier,
+            format!("{} is over {}% of the employee's typical {} claim of {median}", reimbursement.amount, screening.outlier_percent, category.name),
+            None,
+        ));
+    }
+
+    let category_history = history
+        .into_tuple::<i64>()
+        .all(conn).await.unwrap();
+
+    if let Some(median) = screening.outlier(reimbursement.amount, &category_history) {
+        flags.push((
+            ReimbursementFlagKind::CategoryOutlier,
+            format!("{} is over {}% of the typical {} claim of {median}", reimbursement.amount, screening.outlier_percent, category.name),
+            None,
+        ));
+    }
+
+    if flags.is_empty() {
+        return
+    }
+
+    ReimbursementFlag::insert_many(
+        flags.into_iter()
+            .map(|(kind, reason, related_reimbursement_id)| reimbursement_flag::ActiveModel {
+                created_at: Set(Local::now().fixed_offset()),
+                updated_at: Set(Local::now().fixed_offset()),
+                employee_reimbursement_id: Set(reimbursement.id),
+                kind: Set(kind),
+                reason: Set(reason),
+                related_reimbursement_id: Set(related_reimbursement_id),
+                ..Default::default()
+            })
+    ).exec(conn).await.unwrap();
+}
+
+/// Claims in another currency are converted at the rate effective on their transaction date, it's
+/// converted again when approved in case the rate has been corrected since
+#[post("/{attendance_id}/reimburse")]
+async fn create_reimbursement(
+    db: web::Data<DatabaseConnection>,
+    screening: web::Data<ReimbursementScreening>,
+    employee: user::Model,
+    attendance: UnprocessedAttendance,
+    payload: web::Json<CreateReimbursement>,
+) -> impl Responder {
+    let txn = db.begin().await.unwrap();
+
+    let validated = validate_reimbursement(&txn, &employee, &attendance, &payload, None).await?;
+
+    let model = EmployeeReimbursement::insert(employee_reimbursement::ActiveModel {
+        created_by: Set(Some(employee.id)),
+        updated_by: Set(Some(employee.id)),
+        created_at: Set(Local::now().fixed_offset()),
+        updated_at: Set(Local::now().fixed_offset()),
+        category_id: Set(validated.category.id),
+        description: Set(payload.description.clone()),
+        currency: Set(validated.currency),
+        original_amount: Set(payload.amount),
+        transaction_date: Set(validated.transaction_date),
+        exchange_rate: Set(validated.exchange_rate),
+        amount: Set(validated.amount),
+        attendance_period_id: Set(attendance.id),
+        ..Default::default()
+    }).exec_with_returning(&txn).await.unwrap();
+
+    screen_reimbursement(&txn, &screening, &model).await;
+
+    txn.commit().await.unwrap();
+
+    Ok::<_, actix_web::Error>(HttpResponse::Created().json(web::Json(model)))
+}
+
+/// Claims can only be changed by the claimant until they're reviewed
+fn check_editable(employee: &user::Model, reimbursement: &employee_reimbursement::Model) -> actix_web::Result<()> {
+    if reimbursement.created_by != Some(employee.id) {
+        return Err(actix_web::error::ErrorNotFound(""))
+    }
+
+    match reimbursement.status {
+        ReimbursementStatus::Submitted => Ok(()),
+        ReimbursementStatus::Withdrawn => Err(actix_web::error::ErrorBadRequest("claim is withdrawn")),
+        _ => Err(actix_web::error::ErrorBadRequest("claim is already reviewed")),
+    }
+}
+
+#[patch("/{attendance_id}/reimburse/{reimbursement_id}")]
+async fn update_reimbursement(
+    db: web::Data<DatabaseConnection>,
+    screening: web::Data<ReimbursementScreening>,
+    employee: user::Model,
+    attendance: UnprocessedAttendance,
+    reimbursement: employee_reimbursement::Model,
+    payload: web::Json<UpdateReimbursement>,
+) -> impl Responder {
+    check_editable(&employee, &reimbursement)?;
+
+    let payload = payload.into_inner();
+    let payload = CreateReimbursement {
+        category_id: payload.category_id.unwrap_or(reimbursement.category_id),
+        description: payload.description.unwrap_or(reimbursement.description),
+        amount: payload.amount.unwrap_or(reimbursement.original_amount),
+        currency: payload.currency.or(Some(reimbursement.currency)),
+        transaction_date: payload.transaction_date.or(Some(reimbursement.transaction_date)),
+    };
+
+    let txn = db.begin().await.unwrap();
+
+    let validated = validate_reimbursement(&txn, &employee, &attendance, &payload, Some(reimbursement.id)).await?;
+
+    let model = EmployeeReimbursement::update(employee_reimbursement::ActiveModel {
+        id: Unchanged(reimbursement.id),
+        updated_by: Set(Some(employee.id)),
+        updated_at: Set(Local::now().fixed_offset()),
+        category_id: Set(validated.category.id),
+        description: Set(payload.description),
+        currency: Set(validated.currency),
+        original_amount: Set(payload.amount),
+        transaction_date: Set(validated.transaction_date),
+        exchange_rate: Set(validated.exchange_rate),
+        amount: Set(validated.amount),
+        ..Default::default()
+    })
+        // The claim may have been reviewed since it was loaded
+        .filter(employee_reimbursement::Column::Status.eq(ReimbursementStatus::Submitted))
+        .exec(&txn).await;
+
+    let model = match model {
+        Ok(model) => model,
+        Err(DbErr::RecordNotUpdated) => return Err(actix_web::error::ErrorConflict("claim was reviewed while being edited")),
+        Err(err) => panic!("{err}"),
+    };
+
+    screen_reimbursement(&txn, &screening, &model).await;
+
+    txn.commit().await.unwrap();
+
+    Ok(web::Json(model))
+}
+
+/// Takes the claim back, it's kept as withdrawn so claiming the same expense again still gets
+/// flagged, but it's neither reviewed nor paid
+#[delete("/{attendance_id}/reimburse/{reimbursement_id}")]
+async fn withdraw_reimbursement(db: web::Data<DatabaseConnection>, employee: user::Model, _attendance: UnprocessedAttendance, reimbursement: employee_reimbursement::Model) -> impl Responder {
+    check_editable(&employee, &reimbursement)?;
+
+    let res = EmployeeReimbursement::update_many()
+        .col_expr(employee_reimbursement::Column::Status, ReimbursementStatus::Withdrawn.as_enum())
+        .col_expr(employee_reimbursement::Column::UpdatedBy, Expr::value(employee.id))
+        .col_expr(employee_reimbursement::Column::UpdatedAt, Expr::value(Local::now().fixed_offset()))
+        .filter(employee_reimbursement::Column::Id.eq(reimbursement.id))
+        .filter(employee_reimbursement::Column::Status.eq(ReimbursementStatus::Submitted))
+        .exec(db.as_ref()).await.unwrap();
+
+    if res.rows_affected == 0 {
+        return Err(actix_web::error::ErrorConflict("claim was reviewed while being withdrawn"))
+    }
+
+    Ok(HttpResponse::NoContent())
+}
+
+/// Attaches every file of the multipart form to the claim
+///
+/// What the file is gets sniffed from its content, whatever the client says it is. Every file is
+/// checked before any is stored, and they're only attached together once every one is stored, so a
+/// rejected or failed upload attaches nothing. A file already attached to the claim is given back
+/// instead of attached twice. The upload is refused as soon as it goes past the receipt limits,
+/// without reading the rest of it, and only a submitted claim takes receipts
+#[post("/{attendance_id}/reimburse/{reimbursement_id}/receipt")]
+#[allow(clippy::too_many_arguments)]
+async fn upload_receipts(
+    db: web::Data<DatabaseConnection>,
+    storage: web::Data<dyn ReceiptStorage>,
+    limits: web::Data<ReceiptLimits>,
+    screening: web::Data<ReimbursementScreening>,
+    employee: user::Model,
+    _attendance: UnprocessedAttendance,
+    reimbursement: employee_reimbursement::Model,
+    mut payload: Multipart,
+) -> impl Responder {
+    check_editable(&employee, &reimbursement)?;
+
+    let mut files: Vec<(String, ReceiptKind, Bytes)> = Vec::new();
+    let mut total_bytes = 0;
+
+    while let Some(mut field) = payload.try_next().await? {
+        if files.len() == limits.max_files {
+            return Err(actix_web::error::ErrorPayloadTooLarge(limits.too_many_files_message()))
+        }
+
+        // Browsers may send the client's full path, only the last segment is the name
+        let Some(file_name) = field.content_disposition()
+            .and_then(|disposition| disposition.get_filename())
+            .and_then(|file_name| file_name.rsplit(['/', '\\']).next())
+            .filter(|file_name| !file_name.is_empty())
+            .map(str::to_string)
+        else {
+            return Err(actix_web::error::ErrorBadRequest("receipt must be uploaded as a file"))
+        };
+
+        let mut content = BytesMut::new();
+        while let Some(chunk) = field.try_next().await? {
+            if content.len() + chunk.len() > limits.max_bytes {
+                return Err(actix_web::error::ErrorPayloadTooLarge(limits.exceeded_message()))
+            }
+
+            total_bytes += chunk.len();
+            if total_bytes > limits.max_total_bytes {
+                return Err(actix_web::error::ErrorPayloadTooLarge(limits.total_exceeded_message()))
+            }
+
+            content.extend_from_slice(&chunk);
+        }
+
+        let Some(kind) = ReceiptKind::sniff(&content) else {
+            return Err(actix_web::error::ErrorUnsupportedMediaType("receipt must be a JPEG, PNG or WebP image, or a PDF"))
+        };
+
+        files.push((file_name, kind, content.freeze()));
+    }
+
+    if files.is_empty() {
+        return Err(actix_web::error::ErrorBadRequest("no receipt is uploaded"))
+    }
+
+    let mut stored_files: Vec<(String, ReceiptKind, String, String, i64)> = Vec::new();
+
+    for (file_name, kind, content) in files {
+        let sha256 = receipt::digest(&content);
+        let storage_key = receipt::storage_key(&sha256);
+        let size_bytes = content.len() as i64;
+
+        // Objects are only referenced once they're stored, so a referenced one never needs storing again
+        let stored = ReimbursementReceipt::find()
+            .filter(reimbursement_receipt::Column::StorageKey.eq(&storage_key))
+            .count(db.as_ref()).await.unwrap() > 0;
+
+        if !stored {
+            storage.put(&storage_key, kind.content_type(), content).await
+                .map_err(|err| {
+                    tracing::error!("unable to store receipt `{storage_key}`: {err}");
+                    actix_web::error::ErrorInternalServerError("unable to store the receipt")
+                })?;
+        }
+
+        stored_files.push((file_name, kind, sha256, storage_key, size_bytes));
+    }
+
+    let txn = db.begin().await.unwrap();
+
+    // The claim may have been reviewed or withdrawn while the files were being stored
+    let reimbursement = EmployeeReimbursement::find_by_id(reimbursement.id)
+        .lock_exclusive()
+        .one(&txn).await.unwrap()
+        .expect("claim must exist while it's being uploaded to");
+    check_editable(&employee, &reimbursement)?;
+
+    let mut receipts: Vec<reimbursement_receipt::Model> = Vec::new();
+
+    for (file_name, kind, sha256, storage_key, size_bytes) in stored_files {
+        ReimbursementReceipt::insert(reimbursement_receipt::ActiveModel {
+            created_by: Set(Some(employee.id)),
+            updated_by: Set(Some(employee.id)),
+            created_at: Set(Local::now().fixed_offset()),
+            updated_at: Set(Local::now().fixed_offset()),
+            employee_reimbursement_id: Set(reimbursement.id),
+            file_name: Set(file_name),
+            content_type: Set(kind.content_type().to_string()),
+            size_bytes: Set(size_bytes),
+            sha256: Set(sha256.clone()),
+            storage_key: Set(storage_key),
+            ..Default::default()
+        })
+            .on_conflict(OnConflict::columns([reimbursement_receipt::Column::EmployeeReimbursementId, reimbursement_receipt::Column::Sha256])
+                .do_nothing()
+                .to_owned())
+            .do_nothing()
+            .exec(&txn).await.unwrap();
+
+        let receipt = ReimbursementReceipt::find()
+            .filter(reimbursement_receipt::Column::EmployeeReimbursementId.eq(reimbursement.id))
+            .filter(reimbursement_receipt::Column::Sha256.eq(sha256))
+            .one(&txn).await.unwrap()
+            .expect("receipt must exist after uploading");
+
+        if !receipts.iter().any(|r| r.id == receipt.id) {
+            receipts.push(receipt);
+        }
+    }
+
+    txn.commit().await.unwrap();
+
+    screen_reimbursement(db.as_ref(), &screening, &reimbursement).await;
+
+    Ok(HttpResponse::Created().json(web::Json(receipts)))
+}
+
+#[get("/{attendance_id}/reimburse/{reimbursement_id}/receipt")]
+async fn get_receipts(db: web::Data<DatabaseConnection>, user: user::Model, reimbursement: employee_reimbursement::Model) -> impl Responder {
+    if !can_view_receipts(&user, &reimbursement) {
+        return Err(actix_web::error::ErrorNotFound(""))
+    }
+
+    let receipts = ReimbursementReceipt::find()
+        .filter(reimbursement_receipt::Column::EmployeeReimbursementId.eq(reimbursement.id))
+        .order_by_asc(reimbursement_receipt::Column::CreatedAt)
+        .all(db.as_ref()).await.unwrap();
+
+    Ok(web::Json(receipts))
+}
+
+#[get("/{attendance_id}/reimburse/{reimbursement_id}/receipt/{receipt_id}")]
+async fn download_receipt(
+    storage: web::Data<dyn ReceiptStorage>,
+    user: user::Model,
+    reimbursement: employee_reimbursement::Model,
+    receipt: reimbursement_receipt::Model,
+) -> impl Responder {
+    if !can_view_receipts(&user, &reimbursement) {
+        return Err(actix_web::error::ErrorNotFound(""))
+    }
+
+    let content = storage.get(&receipt.storage_key).await
+        .map_err(|err| {
+            tracing::error!("unable to load receipt `{}`: {err}", receipt.storage_key);
+            actix_web::error::ErrorInternalServerError("unable to load the receipt")
+        })?;
+
+    Ok(
+        HttpResponse::Ok()
+            .content_type(receipt.content_type)
+            .insert_header(ContentDisposition {
+                disposition: DispositionType::Attachment,
+                parameters: vec![ DispositionParam::Filename(receipt.file_name) ],
+            })
+            .insert_header(("X-Content-Type-Options", "nosniff"))
+            .body(content)
+    )
+}
+
+#[get("/{attendance_id}/reimburse")]
+async fn get_reimbursements(db: web::Data<DatabaseConnection>, _admin: Admin, attendance: attendance_period::Model, query: web::Query<ReimbursementQuery>) -> impl Responder {
+    let mut reimbursements = EmployeeReimbursement::find()
+        .filter(employee_reimbursement::Column::AttendancePeriodId.eq(attendance.id))
+        .order_by_asc(employee_reimbursement::Column::CreatedAt);
+    // Withdrawn claims are only listed when asked for
+    reimbursements = match query.status {
+        Some(status) => reimbursements.filter(employee_reimbursement::Column::Status.eq(status)),
+        None => reimbursements.filter(employee_reimbursement::Column::Status.ne(ReimbursementStatus::Withdrawn)),
+    };
+    if let Some(flagged) = query.flagged {
+        let flagged_ids = Query::select()
+            .column(reimbursement_flag::Column::EmployeeReimbursementId)
+            .from(ReimbursementFlag)
+            .to_owned();
+
+        reimbursements = reimbursements.filter(if flagged {
+            employee_reimbursement::Column::Id.in_subquery(flagged_ids)
+        } else {
+            employee_reimbursement::Column::Id.not_in_subquery(flagged_ids)
+        });
+    }
+
+    let reimbursements = reimbursements.all(db.as_ref()).await.unwrap();
+    let flags = reimbursements.load_many(ReimbursementFlag, db.as_ref()).await.unwrap();
+
+    web::Json(
+        reimbursements.into_iter()
+            .zip(flags)
+            .map(|(reimbursement, flags)| QueuedReimbursement { reimbursement, flags })
+            .collect::<Vec<_>>()
+    )
+}
+
+/// Records the review on top of what `review` sets, i.e. the status and what's approved
+async fn review_reimbursement<C: ConnectionTrait>(
+    db: &C,
+    admin: &Admin,
+    reimbursement: employee_reimbursement::Model,
+    review: employee_reimbursement::ActiveModel,
+    comment: Option<String>,
+) -> actix_web::Result<employee_reimbursement::Model> {
+    if reimbursement.status != ReimbursementStatus::Submitted {
+        return Err(actix_web::error::ErrorBadRequest("claim is already reviewed"))
+    }
+
+    let model = EmployeeReimbursement::update(employee_reimbursement::ActiveModel {
+        id: Unchanged(reimbursement.id),
+        updated_by: Set(Some(admin.id)),
+        updated_at: Set(Local::now().fixed_offset()),
+        reviewed_by: Set(Some(admin.id)),
+        reviewed_at: Set(Some(Local::now().fixed_offset())),
+        review_comment: Set(comment),
+        ..review
+    })
+        // The claim may have been edited since it was loaded, which needs its own review
+        .filter(employee_reimbursement::Column::Status.eq(ReimbursementStatus::Submitted))
+        .filter(employee_reimbursement::Column::UpdatedAt.eq(reimbursement.updated_at))
+        .exec(db).await;
+
+    match model {
+        Ok(model) => Ok(model),
+        Err(DbErr::RecordNotUpdated) => Err(actix_web::error::ErrorConflict("claim was changed while being reviewed")),
+        Err(err) => panic!("{err}"),
+    }
+}
+
+/// Approves the claim for what's claimed or less in rupiah, claims of categories requiring a receipt
+/// need one attached first. The claim is converted again at the rate effective on its transaction
+/// date, which it keeps from then on, and what's approved has to fit the category's limits
+#[post("/{attendance_id}/reimburse/{reimbursement_id}/approve")]
+async fn approve_reimbursement(
+    db: web::Data<DatabaseConnection>,
+    admin: Admin,
+    attendance: UnprocessedAttendance,
+    reimbursement: employee_reimbursement::Model,
+    payload: Option<web::Json<ApproveReimbursement>>,
+) -> impl Responder {
+    let payload = payload.map(|p| p.into_inner()).unwrap_or_default();
+
+    let (exchange_rate, amount) = convert_reimbursement(db.as_ref(), &reimbursement.currency, reimbursement.original_amount, reimbursement.transaction_date).await?;
+    let approved_amount = payload.approved_amount.unwrap_or(amount);
+
+    let status = reimbursement::approval_status(amount, approved_amount)
+        .map_err(actix_web::error::ErrorBadRequest)?;
+
+    let category = ReimbursementCategory::find_by_id(reimbursement.category_id)
+        .one(db.as_ref()).await.unwrap()
+        .expect("category of a claim must exist");
+
+    if category.receipt_required {
+        let receipts = ReimbursementReceipt::find()
+            .filter(reimbursement_receipt::Column::EmployeeReimbursementId.eq(reimbursement.id))
+            .count(db.as_ref()).await.unwrap();
+
+        if receipts == 0 {
+            return Err(actix_web::error::ErrorBadRequest(format!("{} claims require a receipt to be approved", category.name)))
+        }
+    }
+
+    let txn = db.begin().await.unwrap();
+
+    // The rate may have changed since the claim was made, so it's checked against the limits
+    // again under the same lock claims are made with
+    let employee_id = reimbursement.created_by.expect("claims are made by an employee");
+    User::find_by_id(employee_id)
+        .lock_exclusive()
+        .one(&txn).await.unwrap();
+
+    let claimed = load_reimbursement_claimed(&txn, employee_id, &attendance, category.id, Some(reimbursement.id)).await;
+
+    if let Some(headroom) = ReimbursementLimits::from(&category).exceeded(claimed, approved_amount) {
+        return Err(actix_web::error::ErrorBadRequest(headroom.exceeded_message(&category.name)))
+    }
+
+    let review = employee_reimbursement::ActiveModel {
+        status: Set(status),
+        exchange_rate: Set(exchange_rate),
+        amount: Set(amount),
+        approved_amount: Set(Some(approved_amount)),
+        ..Default::default()
+    };
+
+    let model = review_reimbursement(&txn, &admin, reimbursement, review, payload.comment).await?;
+
+    txn.commit().await.unwrap();
+
+    Ok(web::Json(model))
+}
+
+#[post("/{attendance_id}/reimburse/{reimbursement_id}/reject")]
+async fn reject_reimbursement(
+    db: web::Data<DatabaseConnection>,
+    admin: Admin,
+    _attendance: UnprocessedAttendance,
+    reimbursement: employee_reimbursement::Model,
+    payload: Option<web::Json<ReviewReimbursement>>,
+) -> impl Responder {
+    let payload = payload.map(|p| p.into_inner()).unwrap_or_default();
+
+    let review = employee_reimbursement::ActiveModel {
+        status: Set(ReimbursementStatus::Rejected),
+        ..Default::default()
+    };
+
+    review_reimbursement(db.as_ref(), &admin, reimbursement, review, payload.comment).await
+        .map(web::Json)
+}
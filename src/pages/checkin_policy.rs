@@ -0,0 +1,137 @@
+use std::str::FromStr;
+
+use actix_web::{delete, dev, get, post, web, FromRequest, HttpRequest, HttpResponse, Responder};
+use chrono::Local;
+use futures_util::future::LocalBoxFuture;
+use sea_orm::{ActiveValue::Set, ColumnTrait, DatabaseConnection, EntityTrait, ModelTrait, QueryFilter, QueryOrder};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{auth::Admin, checkin::{self, Coordinate}, entity::{checkin_policy, prelude::*}};
+
+pub(super) fn config(cfg: &mut web::ServiceConfig) {
+    cfg
+        .service(create_policy)
+        .service(get_policies)
+        .service(delete_policy);
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CreateCheckinPolicy {
+    name: String,
+    /// Applies to every period when not set
+    attendance_period_id: Option<Uuid>,
+    #[serde(default)]
+    allowed_networks: Vec<String>,
+    geofence: Option<Geofence>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Geofence {
+    #[serde(flatten)]
+    center: Coordinate,
+    radius_meters: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CheckinPolicyQuery {
+    attendance_period_id: Option<Uuid>,
+}
+
+impl FromRequest for checkin_policy::Model {
+    type Error = actix_web::Error;
+    type Future = LocalBoxFuture<'static, Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _: &mut dev::Payload) -> Self::Future {
+        let req = req.clone();
+
+        Box::pin(async move {
+            let policy_id = req.match_info().get("policy_id").expect("This extractor must be used under `policy_id` path");
+            let Ok(policy_id) = Uuid::from_str(policy_id) else {
+                return Err(actix_web::error::ErrorBadRequest("invalid `policy_id`"))
+            };
+
+            let db = req.app_data::<web::Data<DatabaseConnection>>().expect("DatabaseConnection must be attached");
+
+            let Some(policy) = CheckinPolicy::find_by_id(policy_id)
+                .one(db.as_ref()).await.unwrap()
+            else {
+                return Err(actix_web::error::ErrorNotFound(""))
+            };
+
+            Ok(policy)
+        })
+    }
+}
+
+#[post("")]
+async fn create_policy(db: web::Data<DatabaseConnection>, admin: Admin, payload: web::Json<CreateCheckinPolicy>) -> impl Responder {
+    if payload.allowed_networks.is_empty() && payload.geofence.is_none() {
+        return Err(actix_web::error::ErrorBadRequest("policy must restrict either allowed_networks or geofence"))
+    }
+
+    let Some(allowed_networks) = payload.allowed_networks.iter()
+        .map(|network| checkin::parse_network(network).map(|network| network.to_string()))
+        .collect::<Option<Vec<_>>>()
+    else {
+        return Err(actix_web::error::ErrorBadRequest("allowed_networks must only contain IP addresses or CIDRs"))
+    };
+
+    if let Some(geofence) = &payload.geofence {
+        if !geofence.center.is_valid() {
+            return Err(actix_web::error::ErrorBadRequest("geofence latitude or longitude is out of range"))
+        }
+
+        if geofence.radius_meters <= 0.0 {
+            return Err(actix_web::error::ErrorBadRequest("geofence radius_meters must be positive"))
+        }
+    }
+
+    if let Some(attendance_period_id) = payload.attendance_period_id {
+        let Some(_) = AttendancePeriod::find_by_id(attendance_period_id)
+            .one(db.as_ref()).await.unwrap()
+        else {
+            return Err(actix_web::error::ErrorBadRequest("attendance period does not exist"))
+        };
+    }
+
+    let model = CheckinPolicy::insert(checkin_policy::ActiveModel {
+        created_by: Set(Some(admin.id)),
+        updated_by: Set(Some(admin.id)),
+        created_at: Set(Local::now().fixed_offset()),
+        updated_at: Set(Local::now().fixed_offset()),
+        name: Set(payload.name.clone()),
+        attendance_period_id: Set(payload.attendance_period_id),
+        allowed_networks: Set(allowed_networks),
+        latitude: Set(payload.geofence.as_ref().map(|g| g.center.latitude)),
+        longitude: Set(payload.geofence.as_ref().map(|g| g.center.longitude)),
+        radius_meters: Set(payload.geofence.as_ref().map(|g| g.radius_meters)),
+        ..Default::default()
+    }).exec_with_returning(db.as_ref()).await.unwrap();
+
+    Ok(
+        HttpResponse::Created()
+            .json(web::Json(model))
+    )
+}
+
+#[get("")]
+async fn get_policies(db: web::Data<DatabaseConnection>, _admin: Admin, query: web::Query<CheckinPolicyQuery>) -> impl Responder {
+    let mut policies = CheckinPolicy::find();
+    if let Some(attendance_period_id) = query.attendance_period_id {
+        policies = policies.filter(checkin_policy::Column::AttendancePeriodId.eq(attendance_period_id));
+    }
+
+    let policies = policies
+        .order_by_asc(checkin_policy::Column::CreatedAt)
+        .all(db.as_ref()).await.unwrap();
+
+    web::Json(policies)
+}
+
+#[delete("/{policy_id}")]
+async fn delete_policy(db: web::Data<DatabaseConnection>, _admin: Admin, policy: checkin_policy::Model) -> impl Responder {
+    policy.delete(db.as_ref()).await.unwrap();
+
+    HttpResponse::NoContent()
+}
@@ -0,0 +1,118 @@
+use std::str::FromStr;
+
+use actix_web::{delete, dev, get, post, web, FromRequest, HttpRequest, HttpResponse, Responder};
+use chrono::{Local, NaiveDate};
+use futures_util::future::LocalBoxFuture;
+use sea_orm::{ActiveValue::Set, ColumnTrait, DatabaseConnection, EntityTrait, ModelTrait, QueryFilter, QueryOrder};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{auth::Admin, consts::BASE_CURRENCY, currency, entity::{exchange_rate, prelude::*, user}};
+
+pub(super) fn config(cfg: &mut web::ServiceConfig) {
+    cfg
+        .service(create_exchange_rate)
+        .service(get_exchange_rates)
+        .service(delete_exchange_rate);
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CreateExchangeRate {
+    currency: String,
+    effective_from: NaiveDate,
+    /// Millionths of a rupiah per unit of the currency, e.g. `16250500000` for 16,250.5
+    rate: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ExchangeRateQuery {
+    currency: Option<String>,
+}
+
+impl FromRequest for exchange_rate::Model {
+    type Error = actix_web::Error;
+    type Future = LocalBoxFuture<'static, Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _: &mut dev::Payload) -> Self::Future {
+        let req = req.clone();
+
+        Box::pin(async move {
+            let exchange_rate_id = req.match_info().get("exchange_rate_id").expect("This extractor must be used under `exchange_rate_id` path");
+            let Ok(exchange_rate_id) = Uuid::from_str(exchange_rate_id) else {
+                return Err(actix_web::error::ErrorBadRequest("invalid `exchange_rate_id`"))
+            };
+
+            let db = req.app_data::<web::Data<DatabaseConnection>>().expect("DatabaseConnection must be attached");
+
+            let Some(exchange_rate) = ExchangeRate::find_by_id(exchange_rate_id)
+                .one(db.as_ref()).await.unwrap()
+            else {
+                return Err(actix_web::error::ErrorNotFound(""))
+            };
+
+            Ok(exchange_rate)
+        })
+    }
+}
+
+/// Sets the rate of a currency from a date on, until another rate is set from a later date
+#[post("")]
+async fn create_exchange_rate(db: web::Data<DatabaseConnection>, admin: Admin, payload: web::Json<CreateExchangeRate>) -> impl Responder {
+    if payload.currency == BASE_CURRENCY {
+        return Err(actix_web::error::ErrorBadRequest(format!("{BASE_CURRENCY} is always at par")))
+    }
+
+    if currency::minor_digits(&payload.currency).is_none() {
+        return Err(actix_web::error::ErrorBadRequest("currency is not supported"))
+    }
+
+    if payload.rate <= 0 {
+        return Err(actix_web::error::ErrorBadRequest("rate must be more than 0"))
+    }
+
+    let existing = ExchangeRate::find()
+        .filter(exchange_rate::Column::Currency.eq(&payload.currency))
+        .filter(exchange_rate::Column::EffectiveFrom.eq(payload.effective_from))
+        .one(db.as_ref()).await.unwrap();
+
+    if existing.is_some() {
+        return Err(actix_web::error::ErrorBadRequest(format!("a {} rate is already effective from {}", payload.currency, payload.effective_from)))
+    }
+
+    let model = ExchangeRate::insert(exchange_rate::ActiveModel {
+        created_by: Set(Some(admin.id)),
+        updated_by: Set(Some(admin.id)),
+        created_at: Set(Local::now().fixed_offset()),
+        updated_at: Set(Local::now().fixed_offset()),
+        currency: Set(payload.currency.clone()),
+        effective_from: Set(payload.effective_from),
+        rate: Set(payload.rate),
+        ..Default::default()
+    }).exec_with_returning(db.as_ref()).await.unwrap();
+
+    Ok(
+        HttpResponse::Created()
+            .json(web::Json(model))
+    )
+}
+
+/// Employees need these to know what their claims are worth
+#[get("")]
+async fn get_exchange_rates(db: web::Data<DatabaseConnection>, _user: user::Model, query: web::Query<ExchangeRateQuery>) -> impl Responder {
+    let mut exchange_rates = ExchangeRate::find()
+        .order_by_asc(exchange_rate::Column::Currency)
+        .order_by_desc(exchange_rate::Column::EffectiveFrom);
+    if let Some(currency) = &query.currency {
+        exchange_rates = exchange_rates.filter(exchange_rate::Column::Currency.eq(currency));
+    }
+
+    web::Json(exchange_rates.all(db.as_ref()).await.unwrap())
+}
+
+/// Claims keep the rate they were converted at
+#[delete("/{exchange_rate_id}")]
+async fn delete_exchange_rate(db: web::Data<DatabaseConnection>, _admin: Admin, exchange_rate: exchange_rate::Model) -> impl Responder {
+    exchange_rate.delete(db.as_ref()).await.unwrap();
+
+    HttpResponse::NoContent()
+}
@@ -0,0 +1,112 @@
+use std::str::FromStr;
+
+use actix_web::{delete, dev, get, post, web, FromRequest, HttpRequest, HttpResponse, Responder};
+use chrono::{Datelike as _, Local, NaiveDate};
+use futures_util::future::LocalBoxFuture;
+use sea_orm::{ActiveValue::Set, ColumnTrait, DatabaseConnection, EntityTrait, ModelTrait, QueryFilter, QueryOrder};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{auth::Admin, entity::{employee_attendance, prelude::*, public_holiday, user}};
+
+pub(super) fn config(cfg: &mut web::ServiceConfig) {
+    cfg
+        .service(create_holiday)
+        .service(get_holidays)
+        .service(delete_holiday);
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CreatePublicHoliday {
+    date: NaiveDate,
+    name: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct HolidayQuery {
+    year: Option<i32>,
+}
+
+impl FromRequest for public_holiday::Model {
+    type Error = actix_web::Error;
+    type Future = LocalBoxFuture<'static, Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _: &mut dev::Payload) -> Self::Future {
+        let req = req.clone();
+
+        Box::pin(async move {
+            let holiday_id = req.match_info().get("holiday_id").expect("This extractor must be used under `holiday_id` path");
+            let Ok(holiday_id) = Uuid::from_str(holiday_id) else {
+                return Err(actix_web::error::ErrorBadRequest("invalid `holiday_id`"))
+            };
+
+            let db = req.app_data::<web::Data<DatabaseConnection>>().expect("DatabaseConnection must be attached");
+
+            let Some(holiday) = PublicHoliday::find_by_id(holiday_id)
+                .one(db.as_ref()).await.unwrap()
+            else {
+                return Err(actix_web::error::ErrorNotFound(""))
+            };
+
+            Ok(holiday)
+        })
+    }
+}
+
+#[post("")]
+async fn create_holiday(db: web::Data<DatabaseConnection>, admin: Admin, payload: web::Json<CreatePublicHoliday>) -> impl Responder {
+    let existing = PublicHoliday::find()
+        .filter(public_holiday::Column::Date.eq(payload.date))
+        .one(db.as_ref()).await.unwrap();
+
+    if existing.is_some() {
+        return Err(actix_web::error::ErrorBadRequest("date is already a public holiday"))
+    }
+
+    // Attendance on the day would be paid on top of a period with one working day less
+    let attended = EmployeeAttendance::find()
+        .filter(employee_attendance::Column::WorkDate.eq(payload.date))
+        .one(db.as_ref()).await.unwrap();
+
+    if attended.is_some() {
+        return Err(actix_web::error::ErrorBadRequest("employees have already checked-in on date"))
+    }
+
+    let model = PublicHoliday::insert(public_holiday::ActiveModel {
+        created_by: Set(Some(admin.id)),
+        updated_by: Set(Some(admin.id)),
+        created_at: Set(Local::now().fixed_offset()),
+        updated_at: Set(Local::now().fixed_offset()),
+        date: Set(payload.date),
+        name: Set(payload.name.clone()),
+        ..Default::default()
+    }).exec_with_returning(db.as_ref()).await.unwrap();
+
+    Ok(
+        HttpResponse::Created()
+            .json(web::Json(model))
+    )
+}
+
+#[get("")]
+async fn get_holidays(db: web::Data<DatabaseConnection>, _user: user::Model, query: web::Query<HolidayQuery>) -> impl Responder {
+    let year = query.year.unwrap_or_else(|| Local::now().year());
+
+    let (Some(start), Some(end)) = (NaiveDate::from_ymd_opt(year, 1, 1), NaiveDate::from_ymd_opt(year, 12, 31)) else {
+        return Err(actix_web::error::ErrorBadRequest("year is out of range"))
+    };
+
+    let holidays = PublicHoliday::find()
+        .filter(public_holiday::Column::Date.between(start, end))
+        .order_by_asc(public_holiday::Column::Date)
+        .all(db.as_ref()).await.unwrap();
+
+    Ok(web::Json(holidays))
+}
+
+#[delete("/{holiday_id}")]
+async fn delete_holiday(db: web::Data<DatabaseConnection>, _admin: Admin, holiday: public_holiday::Model) -> impl Responder {
+    holiday.delete(db.as_ref()).await.unwrap();
+
+    HttpResponse::NoContent()
+}
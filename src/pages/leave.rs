@@ -0,0 +1,209 @@
+use std::str::FromStr;
+
+use actix_web::{dev, get, post, put, web, FromRequest, HttpRequest, HttpResponse, Responder};
+use chrono::{Datelike as _, Local, NaiveDate};
+use futures_util::future::LocalBoxFuture;
+use sea_orm::{sea_query::OnConflict, ActiveValue::{Set, Unchanged}, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{auth::Admin, entity::{employee_leave, leave_entitlement, prelude::*, sea_orm_active_enums::{ApprovalStatus, LeaveType}, user}, leave, schedule::EmployeeSchedule};
+
+use model::*;
+
+mod model;
+
+pub(super) fn config(cfg: &mut web::ServiceConfig) {
+    cfg
+        .service(create_leave)
+        .service(get_leaves)
+        .service(get_my_leaves)
+        .service(get_my_balance)
+        .service(set_entitlement)
+        .service(approve_leave)
+        .service(reject_leave);
+}
+
+impl FromRequest for employee_leave::Model {
+    type Error = actix_web::Error;
+    type Future = LocalBoxFuture<'static, Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _: &mut dev::Payload) -> Self::Future {
+        let req = req.clone();
+
+        Box::pin(async move {
+            let leave_id = req.match_info().get("leave_id").expect("This extractor must be used under `leave_id` path");
+            let Ok(leave_id) = Uuid::from_str(leave_id) else {
+                return Err(actix_web::error::ErrorBadRequest("invalid `leave_id`"))
+            };
+
+            let db = req.app_data::<web::Data<DatabaseConnection>>().expect("DatabaseConnection must be attached");
+
+            let Some(leave) = EmployeeLeave::find_by_id(leave_id)
+                .one(db.as_ref()).await.unwrap()
+            else {
+                return Err(actix_web::error::ErrorNotFound(""))
+            };
+
+            Ok(leave)
+        })
+    }
+}
+
+#[post("")]
+async fn create_leave(db: web::Data<DatabaseConnection>, employee: user::Model, payload: web::Json<CreateLeave>) -> impl Responder {
+    if payload.end_date < payload.start_date {
+        return Err(actix_web::error::ErrorBadRequest("end_date is lower than start_date"))
+    }
+
+    let Some(year) = leave::leave_year(payload.start_date, payload.end_date) else {
+        return Err(actix_web::error::ErrorBadRequest("leave cannot span across years"))
+    };
+
+    let schedule = EmployeeSchedule::load(db.as_ref(), employee.id, payload.start_date, payload.end_date).await;
+    let days = schedule.working_days(payload.start_date, payload.end_date).count() as i32;
+
+    if days == 0 {
+        return Err(actix_web::error::ErrorBadRequest("leave does not cover any working day"))
+    }
+
+    let overlapping = EmployeeLeave::find()
+        .filter(employee_leave::Column::CreatedBy.eq(employee.id))
+        .filter(employee_leave::Column::Status.ne(ApprovalStatus::Rejected))
+        .filter(employee_leave::Column::StartDate.lte(payload.end_date))
+        .filter(employee_leave::Column::EndDate.gte(payload.start_date))
+        .one(db.as_ref()).await.unwrap();
+
+    if overlapping.is_some() {
+        return Err(actix_web::error::ErrorBadRequest("leave overlaps an existing one"))
+    }
+
+    let balance = leave::balance(&db, employee.id, payload.leave_type, year).await;
+
+    if balance.remaining_days.is_some_and(|remaining| remaining < days) {
+        return Err(actix_web::error::ErrorBadRequest("insufficient leave balance"))
+    }
+
+    leave::ensure_entitlement(&db, employee.id, payload.leave_type, year).await;
+
+    let model = EmployeeLeave::insert(employee_leave::ActiveModel {
+        created_by: Set(Some(employee.id)),
+        updated_by: Set(Some(employee.id)),
+        created_at: Set(Local::now().fixed_offset()),
+        updated_at: Set(Local::now().fixed_offset()),
+        leave_type: Set(payload.leave_type),
+        start_date: Set(payload.start_date),
+        end_date: Set(payload.end_date),
+        days: Set(days),
+        reason: Set(payload.reason.clone()),
+        ..Default::default()
+    }).exec_with_returning(db.as_ref()).await.unwrap();
+
+    Ok(
+        HttpResponse::Created()
+            .json(web::Json(model))
+    )
+}
+
+#[get("")]
+async fn get_leaves(db: web::Data<DatabaseConnection>, _admin: Admin, query: web::Query<LeaveQuery>) -> impl Responder {
+    let mut leaves = EmployeeLeave::find()
+        .order_by_asc(employee_leave::Column::StartDate);
+    if let Some(status) = query.status {
+        leaves = leaves.filter(employee_leave::Column::Status.eq(status));
+    }
+
+    web::Json(leaves.all(db.as_ref()).await.unwrap())
+}
+
+#[get("/me")]
+async fn get_my_leaves(db: web::Data<DatabaseConnection>, employee: user::Model) -> impl Responder {
+    let leaves = EmployeeLeave::find()
+        .filter(employee_leave::Column::CreatedBy.eq(employee.id))
+        .order_by_asc(employee_leave::Column::StartDate)
+        .all(db.as_ref()).await.unwrap();
+
+    web::Json(leaves)
+}
+
+#[get("/balance")]
+async fn get_my_balance(db: web::Data<DatabaseConnection>, employee: user::Model, query: web::Query<BalanceQuery>) -> impl Responder {
+    let year = query.year.unwrap_or_else(|| Local::now().year());
+
+    if NaiveDate::from_ymd_opt(year, 1, 1).is_none() || NaiveDate::from_ymd_opt(year, 12, 31).is_none() {
+        return Err(actix_web::error::ErrorBadRequest("year is out of range"))
+    }
+
+    let balances = futures_util::future::join_all(
+        [LeaveType::Annual, LeaveType::Sick, LeaveType::Unpaid, LeaveType::Maternity].into_iter().map(|leave_type|
+            leave::balance(&db, employee.id, leave_type, year)
+        )
+    ).await;
+
+    Ok(web::Json(balances))
+}
+
+#[put("/entitlement")]
+async fn set_entitlement(db: web::Data<DatabaseConnection>, _admin: Admin, payload: web::Json<SetEntitlement>) -> impl Responder {
+    if payload.leave_type.default_days().is_none() {
+        return Err(actix_web::error::ErrorBadRequest("leave type is unlimited"))
+    }
+
+    if payload.days < 0 || payload.carried_over_days < 0 {
+        return Err(actix_web::error::ErrorBadRequest("days cannot be negative"))
+    }
+
+    let Some(_) = User::find_by_id(payload.user_id)
+        .one(db.as_ref()).await.unwrap()
+    else {
+        return Err(actix_web::error::ErrorBadRequest("user does not exist"))
+    };
+
+    let model = LeaveEntitlement::insert(leave_entitlement::ActiveModel {
+        created_at: Set(Local::now().fixed_offset()),
+        updated_at: Set(Local::now().fixed_offset()),
+        user_id: Set(payload.user_id),
+        leave_type: Set(payload.leave_type),
+        year: Set(payload.year),
+        days: Set(payload.days),
+        carried_over_days: Set(payload.carried_over_days),
+        ..Default::default()
+    })
+        .on_conflict(OnConflict::columns([leave_entitlement::Column::UserId, leave_entitlement::Column::LeaveType, leave_entitlement::Column::Year])
+            .update_columns([leave_entitlement::Column::UpdatedAt, leave_entitlement::Column::Days, leave_entitlement::Column::CarriedOverDays])
+            .to_owned())
+        .exec_with_returning(db.as_ref()).await.unwrap();
+
+    Ok(web::Json(model))
+}
+
+async fn review_leave(db: &DatabaseConnection, admin: &Admin, leave: employee_leave::Model, status: ApprovalStatus, review: ReviewLeave) -> actix_web::Result<employee_leave::Model> {
+    if leave.status != ApprovalStatus::Pending {
+        return Err(actix_web::error::ErrorBadRequest("leave is already reviewed"))
+    }
+
+    let model = EmployeeLeave::update(employee_leave::ActiveModel {
+        id: Unchanged(leave.id),
+        updated_by: Set(Some(admin.id)),
+        updated_at: Set(Local::now().fixed_offset()),
+        status: Set(status),
+        reviewed_by: Set(Some(admin.id)),
+        reviewed_at: Set(Some(Local::now().fixed_offset())),
+        review_comment: Set(review.comment),
+        ..Default::default()
+    }).exec(db).await.unwrap();
+
+    Ok(model)
+}
+
+#[post("/{leave_id}/approve")]
+async fn approve_leave(db: web::Data<DatabaseConnection>, admin: Admin, leave: employee_leave::Model, payload: Option<web::Json<ReviewLeave>>) -> impl Responder {
+    review_leave(&db, &admin, leave, ApprovalStatus::Approved, payload.map(|p| p.into_inner()).unwrap_or_default()).await
+        .map(web::Json)
+}
+
+#[post("/{leave_id}/reject")]
+async fn reject_leave(db: web::Data<DatabaseConnection>, admin: Admin, leave: employee_leave::Model, payload: Option<web::Json<ReviewLeave>>) -> impl Responder {
+    review_leave(&db, &admin, leave, ApprovalStatus::Rejected, payload.map(|p| p.into_inner()).unwrap_or_default()).await
+        .map(web::Json)
+}
@@ -0,0 +1,35 @@
+use chrono::NaiveDate;
+
+use super::*;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(super) struct CreateLeave {
+    pub(super) leave_type: LeaveType,
+    pub(super) start_date: NaiveDate,
+    pub(super) end_date: NaiveDate,
+    pub(super) reason: String,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub(super) struct ReviewLeave {
+    pub(super) comment: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(super) struct SetEntitlement {
+    pub(super) user_id: Uuid,
+    pub(super) leave_type: LeaveType,
+    pub(super) year: i32,
+    pub(super) days: i32,
+    pub(super) carried_over_days: i32,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(super) struct LeaveQuery {
+    pub(super) status: Option<ApprovalStatus>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(super) struct BalanceQuery {
+    pub(super) year: Option<i32>,
+}
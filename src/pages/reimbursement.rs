@@ -0,0 +1,51 @@
+use actix_web::{get, web, Responder};
+use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder};
+use serde::{Deserialize, Serialize};
+
+use crate::entity::{attendance_period, employee_reimbursement, prelude::*, reimbursement_category, sea_orm_active_enums::ReimbursementStatus, user};
+
+pub(super) fn config(cfg: &mut web::ServiceConfig) {
+    cfg
+        .service(get_my_reimbursements);
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct MyReimbursementQuery {
+    status: Option<ReimbursementStatus>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct MyReimbursement {
+    #[serde(flatten)]
+    reimbursement: employee_reimbursement::Model,
+    category: Option<reimbursement_category::Model>,
+    attendance_period: Option<attendance_period::Model>,
+}
+
+/// Claims of the employee across every period, from the most recent period
+#[get("/me")]
+async fn get_my_reimbursements(db: web::Data<DatabaseConnection>, employee: user::Model, query: web::Query<MyReimbursementQuery>) -> impl Responder {
+    let mut reimbursements = EmployeeReimbursement::find()
+        .filter(employee_reimbursement::Column::CreatedBy.eq(employee.id))
+        .find_also_related(AttendancePeriod)
+        .order_by_desc(attendance_period::Column::StartAt)
+        .order_by_asc(employee_reimbursement::Column::CreatedAt);
+    if let Some(status) = query.status {
+        reimbursements = reimbursements.filter(employee_reimbursement::Column::Status.eq(status));
+    }
+
+    let reimbursements = reimbursements.all(db.as_ref()).await.unwrap();
+
+    let categories = ReimbursementCategory::find()
+        .all(db.as_ref()).await.unwrap();
+
+    web::Json(
+        reimbursements.into_iter()
+            .map(|(reimbursement, attendance_period)| MyReimbursement {
+                category: categories.iter().find(|category| category.id == reimbursement.category_id).cloned(),
+                reimbursement,
+                attendance_period,
+            })
+            .collect::<Vec<_>>()
+    )
+}
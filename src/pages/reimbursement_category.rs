@@ -0,0 +1,145 @@
+use std::str::FromStr;
+
+use actix_web::{dev, get, post, put, web, FromRequest, HttpRequest, HttpResponse, Responder};
+use chrono::Local;
+use futures_util::future::LocalBoxFuture;
+use sea_orm::{ActiveValue::{Set, Unchanged}, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{auth::Admin, entity::{prelude::*, reimbursement_category, user}};
+
+pub(super) fn config(cfg: &mut web::ServiceConfig) {
+    cfg
+        .service(create_category)
+        .service(get_categories)
+        .service(update_category);
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct SetReimbursementCategory {
+    name: String,
+    /// No limit when not set
+    per_claim_max_amount: Option<i64>,
+    period_max_amount: Option<i64>,
+    yearly_max_amount: Option<i64>,
+    #[serde(default)]
+    receipt_required: bool,
+}
+
+impl SetReimbursementCategory {
+    fn validate(&self) -> Result<(), actix_web::Error> {
+        if self.name.trim().is_empty() {
+            return Err(actix_web::error::ErrorBadRequest("name must not be empty"))
+        }
+
+        for (field, amount) in [
+            ("per_claim_max_amount", self.per_claim_max_amount),
+            ("period_max_amount", self.period_max_amount),
+            ("yearly_max_amount", self.yearly_max_amount),
+        ] {
+            if amount.is_some_and(|amount| amount <= 0) {
+                return Err(actix_web::error::ErrorBadRequest(format!("{field} must be more than 0")))
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl FromRequest for reimbursement_category::Model {
+    type Error = actix_web::Error;
+    type Future = LocalBoxFuture<'static, Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _: &mut dev::Payload) -> Self::Future {
+        let req = req.clone();
+
+        Box::pin(async move {
+            let category_id = req.match_info().get("category_id").expect("This extractor must be used under `category_id` path");
+            let Ok(category_id) = Uuid::from_str(category_id) else {
+                return Err(actix_web::error::ErrorBadRequest("invalid `category_id`"))
+            };
+
+            let db = req.app_data::<web::Data<DatabaseConnection>>().expect("DatabaseConnection must be attached");
+
+            let Some(category) = ReimbursementCategory::find_by_id(category_id)
+                .one(db.as_ref()).await.unwrap()
+            else {
+                return Err(actix_web::error::ErrorNotFound(""))
+            };
+
+            Ok(category)
+        })
+    }
+}
+
+async fn is_name_taken(db: &DatabaseConnection, name: &str, category_id: Option<Uuid>) -> bool {
+    let mut existing = ReimbursementCategory::find()
+        .filter(reimbursement_category::Column::Name.eq(name));
+    if let Some(category_id) = category_id {
+        existing = existing.filter(reimbursement_category::Column::Id.ne(category_id));
+    }
+
+    existing.one(db).await.unwrap().is_some()
+}
+
+#[post("")]
+async fn create_category(db: web::Data<DatabaseConnection>, admin: Admin, payload: web::Json<SetReimbursementCategory>) -> impl Responder {
+    payload.validate()?;
+
+    if is_name_taken(&db, payload.name.trim(), None).await {
+        return Err(actix_web::error::ErrorBadRequest("category name is already taken"))
+    }
+
+    let model = ReimbursementCategory::insert(reimbursement_category::ActiveModel {
+        created_by: Set(Some(admin.id)),
+        updated_by: Set(Some(admin.id)),
+        created_at: Set(Local::now().fixed_offset()),
+        updated_at: Set(Local::now().fixed_offset()),
+        name: Set(payload.name.trim().to_string()),
+        per_claim_max_amount: Set(payload.per_claim_max_amount),
+        period_max_amount: Set(payload.period_max_amount),
+        yearly_max_amount: Set(payload.yearly_max_amount),
+        receipt_required: Set(payload.receipt_required),
+        ..Default::default()
+    }).exec_with_returning(db.as_ref()).await.unwrap();
+
+    Ok(
+        HttpResponse::Created()
+            .json(web::Json(model))
+    )
+}
+
+/// Employees need these to pick a category when claiming
+#[get("")]
+async fn get_categories(db: web::Data<DatabaseConnection>, _user: user::Model) -> impl Responder {
+    let categories = ReimbursementCategory::find()
+        .order_by_asc(reimbursement_category::Column::Name)
+        .all(db.as_ref()).await.unwrap();
+
+    web::Json(categories)
+}
+
+/// Limits only apply to claims submitted afterwards
+#[put("/{category_id}")]
+async fn update_category(db: web::Data<DatabaseConnection>, admin: Admin, category: reimbursement_category::Model, payload: web::Json<SetReimbursementCategory>) -> impl Responder {
+    payload.validate()?;
+
+    if is_name_taken(&db, payload.name.trim(), Some(category.id)).await {
+        return Err(actix_web::error::ErrorBadRequest("category name is already taken"))
+    }
+
+    let model = ReimbursementCategory::update(reimbursement_category::ActiveModel {
+        id: Unchanged(category.id),
+        updated_by: Set(Some(admin.id)),
+        updated_at: Set(Local::now().fixed_offset()),
+        name: Set(payload.name.trim().to_string()),
+        per_claim_max_amount: Set(payload.per_claim_max_amount),
+        period_max_amount: Set(payload.period_max_amount),
+        yearly_max_amount: Set(payload.yearly_max_amount),
+        receipt_required: Set(payload.receipt_required),
+        ..Default::default()
+    }).exec(db.as_ref()).await.unwrap();
+
+    Ok::<_, actix_web::Error>(web::Json(model))
+}
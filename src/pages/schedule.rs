@@ -0,0 +1,194 @@
+use std::str::FromStr;
+
+use actix_web::{dev, get, post, web, FromRequest, HttpRequest, HttpResponse, Responder};
+use chrono::{Days, Local, NaiveDate, NaiveTime, Weekday};
+use futures_util::future::LocalBoxFuture;
+use sea_orm::{ActiveValue::{Set, Unchanged}, ColumnTrait, Condition, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{auth::Admin, entity::{employee_work_schedule, prelude::*, user, work_schedule}, schedule::Schedule};
+
+pub(super) fn config(cfg: &mut web::ServiceConfig) {
+    cfg
+        .service(create_schedule)
+        .service(get_schedules)
+        .service(get_my_schedules)
+        .service(assign_schedule);
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CreateWorkSchedule {
+    name: String,
+    working_days: Vec<Weekday>,
+    shift_start: NaiveTime,
+    shift_end: NaiveTime,
+    #[serde(default)]
+    break_minutes: i16,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct AssignWorkSchedule {
+    user_id: Uuid,
+    effective_from: NaiveDate,
+    effective_until: Option<NaiveDate>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct EmployeeWorkScheduleAssignment {
+    #[serde(flatten)]
+    assignment: employee_work_schedule::Model,
+    schedule: Option<work_schedule::Model>,
+}
+
+impl FromRequest for work_schedule::Model {
+    type Error = actix_web::Error;
+    type Future = LocalBoxFuture<'static, Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _: &mut dev::Payload) -> Self::Future {
+        let req = req.clone();
+
+        Box::pin(async move {
+            let schedule_id = req.match_info().get("schedule_id").expect("This extractor must be used under `schedule_id` path");
+            let Ok(schedule_id) = Uuid::from_str(schedule_id) else {
+                return Err(actix_web::error::ErrorBadRequest("invalid `schedule_id`"))
+            };
+
+            let db = req.app_data::<web::Data<DatabaseConnection>>().expect("DatabaseConnection must be attached");
+
+            let Some(schedule) = WorkSchedule::find_by_id(schedule_id)
+                .one(db.as_ref()).await.unwrap()
+            else {
+                return Err(actix_web::error::ErrorNotFound(""))
+            };
+
+            Ok(schedule)
+        })
+    }
+}
+
+#[post("")]
+async fn create_schedule(db: web::Data<DatabaseConnection>, admin: Admin, payload: web::Json<CreateWorkSchedule>) -> impl Responder {
+    if payload.working_days.is_empty() {
+        return Err(actix_web::error::ErrorBadRequest("working_days must not be empty"))
+    }
+
+    // A shift ending before it starts runs overnight, only a zero-length shift makes no sense
+    if payload.shift_end == payload.shift_start {
+        return Err(actix_web::error::ErrorBadRequest("shift_end must differ from shift_start"))
+    }
+
+    let schedule = Schedule {
+        working_days: Schedule::weekdays_mask(&payload.working_days),
+        shift_start: payload.shift_start,
+        shift_end: payload.shift_end,
+        break_minutes: payload.break_minutes as i64,
+    };
+
+    if schedule.break_minutes < 0 || schedule.working_minutes() <= 0 {
+        return Err(actix_web::error::ErrorBadRequest("break_minutes must be shorter than the shift"))
+    }
+
+    let model = WorkSchedule::insert(work_schedule::ActiveModel {
+        created_by: Set(Some(admin.id)),
+        updated_by: Set(Some(admin.id)),
+        created_at: Set(Local::now().fixed_offset()),
+        updated_at: Set(Local::now().fixed_offset()),
+        name: Set(payload.name.clone()),
+        working_days: Set(schedule.working_days),
+        shift_start: Set(schedule.shift_start),
+        shift_end: Set(schedule.shift_end),
+        break_minutes: Set(payload.break_minutes),
+        ..Default::default()
+    }).exec_with_returning(db.as_ref()).await.unwrap();
+
+    Ok(
+        HttpResponse::Created()
+            .json(web::Json(model))
+    )
+}
+
+#[get("")]
+async fn get_schedules(db: web::Data<DatabaseConnection>, _admin: Admin) -> impl Responder {
+    let schedules = WorkSchedule::find()
+        .order_by_asc(work_schedule::Column::CreatedAt)
+        .all(db.as_ref()).await.unwrap();
+
+    web::Json(schedules)
+}
+
+#[get("/me")]
+async fn get_my_schedules(db: web::Data<DatabaseConnection>, employee: user::Model) -> impl Responder {
+    let assignments = EmployeeWorkSchedule::find()
+        .find_also_related(WorkSchedule)
+        .filter(employee_work_schedule::Column::UserId.eq(employee.id))
+        .order_by_asc(employee_work_schedule::Column::EffectiveFrom)
+        .all(db.as_ref()).await.unwrap();
+
+    web::Json(
+        assignments.into_iter()
+            .map(|(assignment, schedule)| EmployeeWorkScheduleAssignment { assignment, schedule })
+            .collect::<Vec<_>>()
+    )
+}
+
+#[post("/{schedule_id}/assign")]
+async fn assign_schedule(db: web::Data<DatabaseConnection>, admin: Admin, schedule: work_schedule::Model, payload: web::Json<AssignWorkSchedule>) -> impl Responder {
+    if payload.effective_until.is_some_and(|until| until < payload.effective_from) {
+        return Err(actix_web::error::ErrorBadRequest("effective_until is lower than effective_from"))
+    }
+
+    let Some(_) = User::find_by_id(payload.user_id)
+        .one(db.as_ref()).await.unwrap()
+    else {
+        return Err(actix_web::error::ErrorBadRequest("user does not exist"))
+    };
+
+    let mut overlapping = Condition::all()
+        .add(employee_work_schedule::Column::UserId.eq(payload.user_id))
+        .add(Condition::any()
+            .add(employee_work_schedule::Column::EffectiveUntil.is_null())
+            .add(employee_work_schedule::Column::EffectiveUntil.gte(payload.effective_from)));
+    if let Some(until) = payload.effective_until {
+        overlapping = overlapping.add(employee_work_schedule::Column::EffectiveFrom.lte(until));
+    }
+
+    let overlapping = EmployeeWorkSchedule::find()
+        .filter(overlapping)
+        .all(db.as_ref()).await.unwrap();
+
+    // An open-ended assignment that started earlier gets superseded by the new one,
+    // anything else overlapping has to be sorted out by the admin first
+    for assignment in &overlapping {
+        if assignment.effective_until.is_some() || assignment.effective_from >= payload.effective_from {
+            return Err(actix_web::error::ErrorBadRequest("schedule assignment overlaps an existing one"))
+        }
+    }
+
+    for assignment in overlapping {
+        EmployeeWorkSchedule::update(employee_work_schedule::ActiveModel {
+            id: Unchanged(assignment.id),
+            updated_by: Set(Some(admin.id)),
+            updated_at: Set(Local::now().fixed_offset()),
+            effective_until: Set(payload.effective_from.checked_sub_days(Days::new(1))),
+            ..Default::default()
+        }).exec(db.as_ref()).await.unwrap();
+    }
+
+    let model = EmployeeWorkSchedule::insert(employee_work_schedule::ActiveModel {
+        created_by: Set(Some(admin.id)),
+        updated_by: Set(Some(admin.id)),
+        created_at: Set(Local::now().fixed_offset()),
+        updated_at: Set(Local::now().fixed_offset()),
+        user_id: Set(payload.user_id),
+        work_schedule_id: Set(schedule.id),
+        effective_from: Set(payload.effective_from),
+        effective_until: Set(payload.effective_until),
+        ..Default::default()
+    }).exec_with_returning(db.as_ref()).await.unwrap();
+
+    Ok(
+        HttpResponse::Created()
+            .json(web::Json(model))
+    )
+}
@@ -0,0 +1,85 @@
+use actix_web::{get, put, web, Responder};
+use chrono::Local;
+use sea_orm::{sea_query::OnConflict, ActiveValue::Set, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{auth::Admin, entity::{prelude::*, sea_orm_active_enums::PtkpStatus, tax_profile, user}};
+
+pub(super) fn config(cfg: &mut web::ServiceConfig) {
+    cfg
+        .service(set_tax_profile)
+        .service(get_tax_profiles)
+        .service(get_my_tax_profile);
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct SetTaxProfile {
+    user_id: Uuid,
+    ptkp_status: PtkpStatus,
+    /// Taxpayer number, either the 15 digits one or the 16 digits NIK, separators are ignored
+    npwp: Option<String>,
+}
+
+/// What PPh 21 is withheld by from the next payroll on, employees without one aren't withheld any
+#[put("")]
+async fn set_tax_profile(db: web::Data<DatabaseConnection>, admin: Admin, payload: web::Json<SetTaxProfile>) -> impl Responder {
+    let npwp = match &payload.npwp {
+        Some(npwp) => {
+            let digits = npwp.chars()
+                .filter(|c| !matches!(c, '.' | '-' | ' '))
+                .collect::<String>();
+
+            if !digits.chars().all(|c| c.is_ascii_digit()) || !matches!(digits.len(), 15 | 16) {
+                return Err(actix_web::error::ErrorBadRequest("npwp must be 15 or 16 digits"))
+            }
+
+            Some(digits)
+        },
+        None => None,
+    };
+
+    let Some(_) = User::find_by_id(payload.user_id)
+        .one(db.as_ref()).await.unwrap()
+    else {
+        return Err(actix_web::error::ErrorBadRequest("user does not exist"))
+    };
+
+    let model = TaxProfile::insert(tax_profile::ActiveModel {
+        created_by: Set(Some(admin.id)),
+        updated_by: Set(Some(admin.id)),
+        created_at: Set(Local::now().fixed_offset()),
+        updated_at: Set(Local::now().fixed_offset()),
+        user_id: Set(payload.user_id),
+        ptkp_status: Set(payload.ptkp_status),
+        npwp: Set(npwp),
+        ..Default::default()
+    })
+        .on_conflict(OnConflict::column(tax_profile::Column::UserId)
+            .update_columns([tax_profile::Column::UpdatedBy, tax_profile::Column::UpdatedAt, tax_profile::Column::PtkpStatus, tax_profile::Column::Npwp])
+            .to_owned())
+        .exec_with_returning(db.as_ref()).await.unwrap();
+
+    Ok(web::Json(model))
+}
+
+#[get("")]
+async fn get_tax_profiles(db: web::Data<DatabaseConnection>, _admin: Admin) -> impl Responder {
+    let tax_profiles = TaxProfile::find()
+        .order_by_asc(tax_profile::Column::CreatedAt)
+        .all(db.as_ref()).await.unwrap();
+
+    web::Json(tax_profiles)
+}
+
+#[get("/me")]
+async fn get_my_tax_profile(db: web::Data<DatabaseConnection>, employee: user::Model) -> impl Responder {
+    let Some(tax_profile) = TaxProfile::find()
+        .filter(tax_profile::Column::UserId.eq(employee.id))
+        .one(db.as_ref()).await.unwrap()
+    else {
+        return Err(actix_web::error::ErrorNotFound(""))
+    };
+
+    Ok(web::Json(tax_profile))
+}
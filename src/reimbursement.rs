@@ -0,0 +1,195 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{entity::{reimbursement_category, sea_orm_active_enums::ReimbursementStatus}, limit::{self, Headroom}};
+
+/// Most an employee may claim under a category, every limit is optional
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ReimbursementLimits {
+    pub per_claim_amount: Option<i64>,
+    pub period_amount: Option<i64>,
+    /// Within the calendar year the period starts in
+    pub yearly_amount: Option<i64>,
+}
+
+impl From<&reimbursement_category::Model> for ReimbursementLimits {
+    fn from(category: &reimbursement_category::Model) -> Self {
+        Self {
+            per_claim_amount: category.per_claim_max_amount,
+            period_amount: category.period_max_amount,
+            yearly_amount: category.yearly_max_amount,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ReimbursementLimitScope {
+    Claim,
+    Period,
+    Year,
+}
+
+/// Amount already claimed under a category within each scope
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ReimbursementClaimed {
+    pub period_amount: i64,
+    pub yearly_amount: i64,
+}
+
+pub type ReimbursementHeadroom = Headroom<ReimbursementLimitScope>;
+
+impl ReimbursementHeadroom {
+    /// Tells which limit of `category` was hit and how much of it is left
+    pub fn exceeded_message(&self, category: &str) -> String {
+        match self.scope {
+            ReimbursementLimitScope::Claim => format!("you cannot claim more than {} for {category} at once", self.limit),
+            ReimbursementLimitScope::Period => format!("you cannot claim more than {} for {category} in this period, {} remaining", self.limit, self.remaining),
+            ReimbursementLimitScope::Year => format!("you cannot claim more than {} for {category} a year, {} remaining", self.limit, self.remaining),
+        }
+    }
+}
+
+impl ReimbursementLimits {
+    /// Headroom of every limit set, from the narrowest scope
+    pub fn headroom(&self, claimed: ReimbursementClaimed) -> Vec<ReimbursementHeadroom> {
+        limit::headroom([
+            (ReimbursementLimitScope::Claim, self.per_claim_amount, 0),
+            (ReimbursementLimitScope::Period, self.period_amount, claimed.period_amount),
+            (ReimbursementLimitScope::Year, self.yearly_amount, claimed.yearly_amount),
+        ])
+    }
+
+    /// The tightest limit that claiming `amount` more would go past
+    pub fn exceeded(&self, claimed: ReimbursementClaimed, amount: i64) -> Option<ReimbursementHeadroom> {
+        limit::exceeded(self.headroom(claimed), amount)
+    }
+}
+
+/// Status of a claim approved for `approved_amount` out of the `amount` claimed, approving less
+/// than claimed is a partial approval
+pub fn approval_status(amount: i64, approved_amount: i64) -> Result<ReimbursementStatus, &'static str> {
+    if approved_amount <= 0 {
+        return Err("approved_amount must be more than 0")
+    }
+
+    if approved_amount > amount {
+        return Err("approved_amount must not be more than the claimed amount")
+    }
+
+    if approved_amount < amount {
+        Ok(ReimbursementStatus::PartiallyApproved)
+    } else {
+        Ok(ReimbursementStatus::Approved)
+    }
+}
+
+/// What claims are screened against when they're made, screening only flags a claim for the
+/// reviewer and never stops it from being made
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReimbursementScreening {
+    /// Claims of the same amount and description with transactions this many days apart are
+    /// likely the same expense claimed twice
+    pub duplicate_window_days: i64,
+    /// Claims over this percent of the typical claim are unusually large
+    pub outlier_percent: i64,
+    /// Claims needed before there's a typical claim to compare against
+    pub outlier_min_claims: usize,
+}
+
+impl Default for ReimbursementScreening {
+    fn default() -> Self {
+        Self {
+            duplicate_window_days: 7,
+            outlier_percent: 300,
+            outlier_min_claims: 3,
+        }
+    }
+}
+
+impl ReimbursementScreening {
+    /// The typical claim of `history` when `amount` is unusually large next to it, the median so a
+    /// single large claim in the history doesn't make the next one look typical
+    pub fn outlier(&self, amount: i64, history: &[i64]) -> Option<i64> {
+        if history.is_empty() || history.len() < self.outlier_min_claims {
+            return None
+        }
+
+        let mut history = history.to_vec();
+        history.sort_unstable();
+
+        let middle = history.len() / 2;
+        let median = if history.len().is_multiple_of(2) {
+            (history[middle - 1] + history[middle]) / 2
+        } else {
+            history[middle]
+        };
+
+        (amount as i128 * 100 > median as i128 * self.outlier_percent as i128).then_some(median)
+    }
+}
+
+/// Descriptions differing only in case or spacing describe the same expense
+pub fn normalize_description(description: &str) -> String {
+    description.split_whitespace()
+        .map(str::to_lowercase)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reimbursement_limits() {
+        let limits = ReimbursementLimits {
+            per_claim_amount: Some(500_000),
+            period_amount: None,
+            yearly_amount: Some(5_000_000),
+        };
+        let claimed = ReimbursementClaimed { period_amount: 700_000, yearly_amount: 4_900_000 };
+
+        assert_eq!(limits.headroom(claimed), vec![
+            ReimbursementHeadroom { scope: ReimbursementLimitScope::Claim, limit: 500_000, taken: 0, remaining: 500_000 },
+            ReimbursementHeadroom { scope: ReimbursementLimitScope::Year, limit: 5_000_000, taken: 4_900_000, remaining: 100_000 },
+        ]);
+    }
+
+    #[test]
+    fn test_reimbursement_limit_message() {
+        let headroom = ReimbursementHeadroom { scope: ReimbursementLimitScope::Claim, limit: 500_000, taken: 0, remaining: 500_000 };
+        assert_eq!(headroom.exceeded_message("Transport"), "you cannot claim more than 500000 for Transport at once");
+
+        let headroom = ReimbursementHeadroom { scope: ReimbursementLimitScope::Period, limit: 1_000_000, taken: 700_000, remaining: 300_000 };
+        assert_eq!(headroom.exceeded_message("Meals"), "you cannot claim more than 1000000 for Meals in this period, 300000 remaining");
+
+        let headroom = ReimbursementHeadroom { scope: ReimbursementLimitScope::Year, limit: 5_000_000, taken: 5_000_000, remaining: 0 };
+        assert_eq!(headroom.exceeded_message("Medical"), "you cannot claim more than 5000000 for Medical a year, 0 remaining");
+    }
+
+    #[test]
+    fn test_approval_status() {
+        assert_eq!(approval_status(100_000, 100_000), Ok(ReimbursementStatus::Approved));
+        assert_eq!(approval_status(100_000, 60_000), Ok(ReimbursementStatus::PartiallyApproved));
+        assert_eq!(approval_status(100_000, 0), Err("approved_amount must be more than 0"));
+        assert_eq!(approval_status(100_000, 100_001), Err("approved_amount must not be more than the claimed amount"));
+    }
+
+    #[test]
+    fn test_screening_outlier() {
+        let screening = ReimbursementScreening::default();
+
+        assert_eq!(screening.outlier(400_000, &[100_000, 120_000, 80_000]), Some(100_000));
+        assert_eq!(screening.outlier(300_000, &[100_000, 120_000, 80_000]), None);
+        // A single large claim doesn't move the typical claim
+        assert_eq!(screening.outlier(400_000, &[100_000, 120_000, 80_000, 5_000_000]), Some(110_000));
+        // Too few claims to tell what's typical
+        assert_eq!(screening.outlier(400_000, &[100_000, 120_000]), None);
+        assert_eq!(ReimbursementScreening { outlier_min_claims: 0, ..screening }.outlier(400_000, &[]), None);
+    }
+
+    #[test]
+    fn test_normalize_description() {
+        assert_eq!(normalize_description("  Taxi to   Client\tOffice "), "taxi to client office");
+        assert_eq!(normalize_description("Taxi to client office"), normalize_description("taxi to CLIENT office"));
+    }
+}
@@ -0,0 +1,303 @@
+use std::collections::HashSet;
+
+use chrono::{Datelike as _, Days, NaiveDate, NaiveDateTime, NaiveTime, TimeDelta, Weekday};
+use sea_orm::{ColumnTrait, ConnectionTrait, EntityTrait, QueryFilter, QueryOrder};
+use uuid::Uuid;
+
+use crate::{consts::WORKING_HOUR, entity::{employee_work_schedule, prelude::*, public_holiday, work_schedule}};
+
+/// Working pattern that applies to an employee on a given day
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Schedule {
+    /// Bitmask of working weekdays, bit 0 is Monday and bit 6 is Sunday
+    pub working_days: i16,
+    pub shift_start: NaiveTime,
+    pub shift_end: NaiveTime,
+    pub break_minutes: i64,
+}
+
+impl Default for Schedule {
+    /// Monday to Friday within [`WORKING_HOUR`], used when the employee has no schedule assigned
+    fn default() -> Self {
+        Self {
+            working_days: Self::weekdays_mask(&[Weekday::Mon, Weekday::Tue, Weekday::Wed, Weekday::Thu, Weekday::Fri]),
+            shift_start: NaiveTime::from_hms_opt(WORKING_HOUR.0, 0, 0).unwrap(),
+            shift_end: NaiveTime::from_hms_opt(WORKING_HOUR.1, 0, 0).unwrap(),
+            break_minutes: 0,
+        }
+    }
+}
+
+impl Schedule {
+    pub fn weekdays_mask(weekdays: &[Weekday]) -> i16 {
+        weekdays.iter().fold(0, |mask, weekday| mask | (1 << weekday.num_days_from_monday()))
+    }
+
+    pub fn is_working_day(&self, date: NaiveDate) -> bool {
+        self.working_days & (1 << date.weekday().num_days_from_monday()) != 0
+    }
+
+    /// Overnight shifts end on the calendar day after they started, e.g. 22:00 to 06:00
+    pub fn is_overnight(&self) -> bool {
+        self.shift_end <= self.shift_start
+    }
+
+    pub fn shift_minutes(&self) -> i64 {
+        let minutes = (self.shift_end - self.shift_start).num_minutes();
+
+        if self.is_overnight() {
+            minutes + 24 * 60
+        } else {
+            minutes
+        }
+    }
+
+    /// Paid minutes of a single working day, which is the shift length without the break
+    pub fn working_minutes(&self) -> i64 {
+        self.shift_minutes() - self.break_minutes
+    }
+
+    pub fn shift_start_at(&self, work_date: NaiveDate) -> NaiveDateTime {
+        work_date.and_time(self.shift_start)
+    }
+
+    pub fn shift_end_at(&self, work_date: NaiveDate) -> NaiveDateTime {
+        self.shift_start_at(work_date) + TimeDelta::minutes(self.shift_minutes())
+    }
+
+    /// The point in time where a shift started on `work_date` stops owning the clock,
+    /// which is halfway through the off-duty gap until the next shift starts
+    fn business_day_end_at(&self, work_date: NaiveDate) -> NaiveDateTime {
+        let off_duty_minutes = 24 * 60 - self.shift_minutes();
+
+        self.shift_end_at(work_date) + TimeDelta::minutes(off_duty_minutes / 2)
+    }
+}
+
+impl From<&work_schedule::Model> for Schedule {
+    fn from(model: &work_schedule::Model) -> Self {
+        Self {
+            working_days: model.working_days,
+            shift_start: model.shift_start,
+            shift_end: model.shift_end,
+            break_minutes: model.break_minutes as i64,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Assignment {
+    effective_from: NaiveDate,
+    effective_until: Option<NaiveDate>,
+    schedule: Schedule,
+}
+
+/// Every schedule assigned to an employee along with their effective dates
+#[derive(Debug, Clone, Default)]
+pub struct EmployeeSchedule {
+    assignments: Vec<Assignment>,
+    fallback: Schedule,
+    /// Nobody works on public holidays, whatever their schedule says
+    holidays: HashSet<NaiveDate>,
+}
+
+impl EmployeeSchedule {
+    /// Only knows about the public holidays from the day before `start` until `end`, which is
+    /// enough to tell the business day of any time within them
+    pub async fn load<C: ConnectionTrait>(db: &C, user_id: Uuid, start: NaiveDate, end: NaiveDate) -> Self {
+        let assignments = EmployeeWorkSchedule::find()
+            .find_also_related(WorkSchedule)
+            .filter(employee_work_schedule::Column::UserId.eq(user_id))
+            .order_by_asc(employee_work_schedule::Column::EffectiveFrom)
+            .all(db).await.unwrap();
+
+        let holidays = PublicHoliday::find()
+            .filter(public_holiday::Column::Date.between(start.pred_opt().unwrap_or(start), end))
+            .all(db).await.unwrap();
+
+        Self {
+            holidays: holidays.into_iter().map(|holiday| holiday.date).collect(),
+            assignments: assignments.into_iter()
+                .filter_map(|(assignment, schedule)|
+                    Some(Assignment {
+                        effective_from: assignment.effective_from,
+                        effective_until: assignment.effective_until,
+                        schedule: Schedule::from(&schedule?),
+                    })
+                )
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    /// Schedule in effect on `date`, the latest assignment wins if several of them overlap
+    pub fn for_date(&self, date: NaiveDate) -> &Schedule {
+        self.assignments.iter()
+            .rev()
+            .find(|a| a.effective_from <= date && a.effective_until.is_none_or(|until| date <= until))
+            .map(|a| &a.schedule)
+            .unwrap_or(&self.fallback)
+    }
+
+    pub fn is_holiday(&self, date: NaiveDate) -> bool {
+        self.holidays.contains(&date)
+    }
+
+    pub fn is_working_day(&self, date: NaiveDate) -> bool {
+        !self.is_holiday(date) && self.for_date(date).is_working_day(date)
+    }
+
+    /// Business day that `time` belongs to, a shift belongs to the day it started
+    ///
+    /// For example with a 22:00 to 06:00 shift, a check-in at 22:00 on Monday and
+    /// an overtime logged at 07:00 on Tuesday both belong to Monday
+    pub fn work_date(&self, time: NaiveDateTime) -> NaiveDate {
+        let today = time.date();
+        let yesterday = today.checked_sub_days(Days::new(1)).unwrap();
+        if self.is_working_day(yesterday) && time < self.for_date(yesterday).business_day_end_at(yesterday) {
+            yesterday
+        } else {
+            today
+        }
+    }
+
+    /// Iterates every working day between `start` and `end` (inclusive) with the schedule of that day
+    pub fn working_days(&self, start: NaiveDate, end: NaiveDate) -> impl Iterator<Item = (NaiveDate, &Schedule)> {
+        start.iter_days()
+            .take_while(move |date| *date <= end)
+            .filter(|date| self.is_working_day(*date))
+            .map(|date| (date, self.for_date(date)))
+    }
+}
+
+impl From<Schedule> for EmployeeSchedule {
+    fn from(schedule: Schedule) -> Self {
+        Self {
+            fallback: schedule,
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn retail_schedule() -> Schedule {
+        Schedule {
+            working_days: Schedule::weekdays_mask(&[Weekday::Tue, Weekday::Wed, Weekday::Thu, Weekday::Fri, Weekday::Sat]),
+            shift_start: NaiveTime::from_hms_opt(10, 0, 0).unwrap(),
+            shift_end: NaiveTime::from_hms_opt(19, 0, 0).unwrap(),
+            break_minutes: 60,
+        }
+    }
+
+    #[test]
+    fn test_default_schedule() {
+        let schedule = Schedule::default();
+
+        // Monday 3rd, June 2024
+        assert!(schedule.is_working_day(NaiveDate::from_ymd_opt(2024, 6, 3).unwrap()));
+        // Saturday 8th, June 2024
+        assert!(!schedule.is_working_day(NaiveDate::from_ymd_opt(2024, 6, 8).unwrap()));
+        assert_eq!(schedule.working_minutes(), 8 * 60);
+    }
+
+    #[test]
+    fn test_schedule_working_minutes() {
+        let schedule = retail_schedule();
+
+        assert_eq!(schedule.shift_minutes(), 9 * 60);
+        assert_eq!(schedule.working_minutes(), 8 * 60);
+    }
+
+    #[test]
+    fn test_overnight_schedule() {
+        let schedule = Schedule {
+            shift_start: NaiveTime::from_hms_opt(22, 0, 0).unwrap(),
+            shift_end: NaiveTime::from_hms_opt(6, 0, 0).unwrap(),
+            ..Default::default()
+        };
+        let monday = NaiveDate::from_ymd_opt(2024, 6, 3).unwrap();
+
+        assert!(schedule.is_overnight());
+        assert_eq!(schedule.shift_minutes(), 8 * 60);
+        assert_eq!(schedule.shift_end_at(monday), NaiveDate::from_ymd_opt(2024, 6, 4).unwrap().and_hms_opt(6, 0, 0).unwrap());
+    }
+
+    #[test]
+    fn test_work_date() {
+        let night_shift = EmployeeSchedule::from(Schedule {
+            shift_start: NaiveTime::from_hms_opt(22, 0, 0).unwrap(),
+            shift_end: NaiveTime::from_hms_opt(6, 0, 0).unwrap(),
+            ..Default::default()
+        });
+        let day_shift = EmployeeSchedule::default();
+
+        let monday = NaiveDate::from_ymd_opt(2024, 6, 3).unwrap();
+        let tuesday = NaiveDate::from_ymd_opt(2024, 6, 4).unwrap();
+
+        // Checks in on Monday night, logs overtime on Tuesday morning after the shift ends
+        assert_eq!(night_shift.work_date(monday.and_hms_opt(22, 0, 0).unwrap()), monday);
+        assert_eq!(night_shift.work_date(tuesday.and_hms_opt(2, 0, 0).unwrap()), monday);
+        assert_eq!(night_shift.work_date(tuesday.and_hms_opt(7, 30, 0).unwrap()), monday);
+        // Off-duty gap is 06:00 to 22:00, so Tuesday's business day begins at 14:00
+        assert_eq!(night_shift.work_date(tuesday.and_hms_opt(14, 0, 0).unwrap()), tuesday);
+        assert_eq!(night_shift.work_date(tuesday.and_hms_opt(21, 45, 0).unwrap()), tuesday);
+
+        // Nothing started on Sunday, so early Monday belongs to Monday
+        assert_eq!(night_shift.work_date(monday.and_hms_opt(2, 0, 0).unwrap()), monday);
+
+        assert_eq!(day_shift.work_date(monday.and_hms_opt(10, 0, 0).unwrap()), monday);
+        assert_eq!(day_shift.work_date(tuesday.and_hms_opt(0, 30, 0).unwrap()), monday);
+        assert_eq!(day_shift.work_date(tuesday.and_hms_opt(8, 0, 0).unwrap()), tuesday);
+    }
+
+    #[test]
+    fn test_employee_schedule_for_date() {
+        let employee_schedule = EmployeeSchedule {
+            assignments: vec![
+                Assignment {
+                    effective_from: NaiveDate::from_ymd_opt(2024, 6, 10).unwrap(),
+                    effective_until: Some(NaiveDate::from_ymd_opt(2024, 6, 16).unwrap()),
+                    schedule: retail_schedule(),
+                },
+            ],
+            ..Default::default()
+        };
+
+        assert_eq!(employee_schedule.for_date(NaiveDate::from_ymd_opt(2024, 6, 9).unwrap()), &Schedule::default());
+        assert_eq!(employee_schedule.for_date(NaiveDate::from_ymd_opt(2024, 6, 10).unwrap()), &retail_schedule());
+        assert_eq!(employee_schedule.for_date(NaiveDate::from_ymd_opt(2024, 6, 16).unwrap()), &retail_schedule());
+        assert_eq!(employee_schedule.for_date(NaiveDate::from_ymd_opt(2024, 6, 17).unwrap()), &Schedule::default());
+
+        // Week of 10th, June 2024 is Tuesday to Saturday, the rest of the month is Monday to Friday
+        let working_days = employee_schedule.working_days(
+            NaiveDate::from_ymd_opt(2024, 6, 1).unwrap(),
+            NaiveDate::from_ymd_opt(2024, 6, 30).unwrap(),
+        ).collect::<Vec<_>>();
+        assert_eq!(working_days.len(), 20);
+        assert!(working_days.iter().any(|(date, _)| *date == NaiveDate::from_ymd_opt(2024, 6, 15).unwrap()));
+        assert!(!working_days.iter().any(|(date, _)| *date == NaiveDate::from_ymd_opt(2024, 6, 10).unwrap()));
+    }
+
+    #[test]
+    fn test_employee_schedule_holidays() {
+        // Idul Adha on Monday 17th, June 2024
+        let idul_adha = NaiveDate::from_ymd_opt(2024, 6, 17).unwrap();
+        let employee_schedule = EmployeeSchedule {
+            holidays: HashSet::from([idul_adha]),
+            ..Default::default()
+        };
+
+        assert!(employee_schedule.is_holiday(idul_adha));
+        assert!(!employee_schedule.is_working_day(idul_adha));
+        assert!(employee_schedule.is_working_day(NaiveDate::from_ymd_opt(2024, 6, 18).unwrap()));
+
+        let working_days = employee_schedule.working_days(
+            NaiveDate::from_ymd_opt(2024, 6, 1).unwrap(),
+            NaiveDate::from_ymd_opt(2024, 6, 30).unwrap(),
+        ).count();
+        assert_eq!(working_days, 19);
+    }
+}
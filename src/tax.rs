@@ -0,0 +1,316 @@
+use serde::{Deserialize, Serialize};
+
+use crate::entity::sea_orm_active_enums::PtkpStatus;
+
+/// Most of the gross income that may be set aside as occupational cost (biaya jabatan) a month
+const OCCUPATIONAL_COST_MONTHLY_MAX: i64 = 500_000;
+
+/// Monthly gross income up to which each rate applies, rates are in hundredths of a percent.
+/// From PP 58/2023, the last rate applies to everything above
+const TER_A: &[(i64, i64)] = &[
+    (5_400_000, 0), (5_650_000, 25), (5_950_000, 50), (6_300_000, 75), (6_750_000, 100),
+    (7_500_000, 125), (8_550_000, 150), (9_650_000, 175), (10_050_000, 200), (10_350_000, 225),
+    (10_700_000, 250), (11_050_000, 300), (11_600_000, 350), (12_500_000, 400), (13_750_000, 500),
+    (15_100_000, 600), (16_950_000, 700), (19_750_000, 800), (24_150_000, 900), (26_450_000, 1000),
+    (28_000_000, 1100), (30_050_000, 1200), (32_400_000, 1300), (35_400_000, 1400), (39_100_000, 1500),
+    (43_850_000, 1600), (47_800_000, 1700), (51_400_000, 1800), (56_300_000, 1900), (62_200_000, 2000),
+    (68_600_000, 2100), (77_500_000, 2200), (89_000_000, 2300), (103_000_000, 2400), (125_000_000, 2500),
+    (157_000_000, 2600), (206_000_000, 2700), (337_000_000, 2800), (454_000_000, 2900), (550_000_000, 3000),
+    (695_000_000, 3100), (910_000_000, 3200), (1_400_000_000, 3300), (i64::MAX, 3400),
+];
+
+const TER_B: &[(i64, i64)] = &[
+    (6_200_000, 0), (6_500_000, 25), (6_850_000, 50), (7_300_000, 75), (9_200_000, 100),
+    (10_750_000, 150), (11_250_000, 200), (11_600_000, 250), (12_600_000, 300), (13_600_000, 400),
+    (14_950_000, 500), (16_400_000, 600), (18_450_000, 700), (21_850_000, 800), (26_000_000, 900),
+    (27_700_000, 1000), (29_350_000, 1100), (31_450_000, 1200), (33_950_000, 1300), (37_100_000, 1400),
+    (41_100_000, 1500), (45_800_000, 1600), (49_500_000, 1700), (53_800_000, 1800), (58_500_000, 1900),
+    (64_000_000, 2000), (71_000_000, 2100), (80_000_000, 2200), (93_000_000, 2300), (109_000_000, 2400),
+    (129_000_000, 2500), (163_000_000, 2600), (211_000_000, 2700), (374_000_000, 2800), (459_000_000, 2900),
+    (555_000_000, 3000), (704_000_000, 3100), (957_000_000, 3200), (1_405_000_000, 3300), (i64::MAX, 3400),
+];
+
+const TER_C: &[(i64, i64)] = &[
+    (6_600_000, 0), (6_950_000, 25), (7_350_000, 50), (7_800_000, 75), (8_850_000, 100),
+    (9_800_000, 125), (10_950_000, 150), (11_200_000, 175), (12_050_000, 200), (12_950_000, 300),
+    (14_150_000, 400), (15_550_000, 500), (17_050_000, 600), (19_500_000, 700), (22_700_000, 800),
+    (26_600_000, 900), (28_100_000, 1000), (30_100_000, 1100), (32_600_000, 1200), (35_400_000, 1300),
+    (38_900_000, 1400), (43_000_000, 1500), (47_400_000, 1600), (51_200_000, 1700), (55_800_000, 1800),
+    (60_400_000, 1900), (66_700_000, 2000), (74_500_000, 2100), (83_200_000, 2200), (95_600_000, 2300),
+    (110_000_000, 2400), (134_000_000, 2500), (169_000_000, 2600), (221_000_000, 2700), (390_000_000, 2800),
+    (463_000_000, 2900), (561_000_000, 3000), (709_000_000, 3100), (965_000_000, 3200), (1_419_000_000, 3300),
+    (i64::MAX, 3400),
+];
+
+/// Yearly taxable income each rate applies to, in order, from article 17 of the income tax law
+const ANNUAL_BRACKETS: &[(Option<i64>, i64)] = &[
+    (Some(60_000_000), 500),
+    (Some(190_000_000), 1500),
+    (Some(250_000_000), 2500),
+    (Some(4_500_000_000), 3000),
+    (None, 3500),
+];
+
+/// Which TER table applies, by the PTKP status
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TerCategory {
+    A,
+    B,
+    C,
+}
+
+impl TerCategory {
+    /// Rate applied to a month's gross income, in hundredths of a percent
+    pub fn rate(&self, monthly_income: i64) -> i64 {
+        let table = match self {
+            TerCategory::A => TER_A,
+            TerCategory::B => TER_B,
+            TerCategory::C => TER_C,
+        };
+
+        table.iter()
+            .find(|(max_income, _)| monthly_income <= *max_income)
+            .map(|(_, rate)| *rate)
+            .unwrap_or_default()
+    }
+}
+
+impl PtkpStatus {
+    /// Income that isn't taxed a year (penghasilan tidak kena pajak)
+    pub fn annual_ptkp(&self) -> i64 {
+        let (married, dependants) = self.family();
+
+        54_000_000 + if married { 4_500_000 } else { 0 } + dependants * 4_500_000
+    }
+
+    pub fn ter_category(&self) -> TerCategory {
+        match self {
+            PtkpStatus::Tk0 | PtkpStatus::Tk1 | PtkpStatus::K0 => TerCategory::A,
+            PtkpStatus::Tk2 | PtkpStatus::Tk3 | PtkpStatus::K1 | PtkpStatus::K2 => TerCategory::B,
+            PtkpStatus::K3 => TerCategory::C,
+        }
+    }
+
+    fn family(&self) -> (bool, i64) {
+        match self {
+            PtkpStatus::Tk0 => (false, 0),
+            PtkpStatus::Tk1 => (false, 1),
+            PtkpStatus::Tk2 => (false, 2),
+            PtkpStatus::Tk3 => (false, 3),
+            PtkpStatus::K0 => (true, 0),
+            PtkpStatus::K1 => (true, 1),
+            PtkpStatus::K2 => (true, 2),
+            PtkpStatus::K3 => (true, 3),
+        }
+    }
+}
+
+/// Who the tax is withheld from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TaxProfile {
+    pub ptkp_status: PtkpStatus,
+    /// Employees without a taxpayer number are withheld 20% more
+    pub has_npwp: bool,
+}
+
+/// Taxable income and tax withheld before the period, earlier in the month or the year
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ToDate {
+    pub taxable_income: i64,
+    pub withheld: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TaxBracket {
+    pub taxable_income: i64,
+    /// In hundredths of a percent
+    pub rate: i64,
+    pub amount: i64,
+}
+
+/// The tax due for the whole year, which December's withholding settles
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AnnualTax {
+    /// Months of the year with income, which the occupational cost is capped by
+    pub months: i64,
+    pub gross_income: i64,
+    pub occupational_cost: i64,
+    pub ptkp: i64,
+    /// Rounded down to the thousand
+    pub taxable_income: i64,
+    pub brackets: Vec<TaxBracket>,
+    pub withheld_before: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WithholdingMethod {
+    /// Every month but December, a flat rate of the month's gross income less what was withheld
+    /// earlier in the month
+    Ter { category: TerCategory, rate: i64, monthly_income: i64, withheld_before: i64 },
+    /// December, what's left of the year's tax after what was withheld the other months
+    AnnualReconciliation(AnnualTax),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Withholding {
+    pub method: WithholdingMethod,
+    pub tax: i64,
+    /// Added on top of `tax` without a taxpayer number
+    pub surcharge: i64,
+    /// What's left to withhold of `tax` and `surcharge`. Negative when more was withheld earlier
+    /// than is due, which is paid back
+    pub amount: i64,
+}
+
+/// PPh 21 withheld from a period's gross income. The rate is of the whole month's income, so
+/// periods ending in the same month are taxed together
+pub fn monthly_withholding(profile: TaxProfile, income: i64, month_to_date: ToDate) -> Withholding {
+    let monthly_income = month_to_date.taxable_income + income;
+    let category = profile.ptkp_status.ter_category();
+    let rate = category.rate(monthly_income);
+    let tax = monthly_income * rate / 10_000;
+    let surcharge = surcharge(profile, tax);
+
+    Withholding {
+        method: WithholdingMethod::Ter { category, rate, monthly_income, withheld_before: month_to_date.withheld },
+        tax,
+        surcharge,
+        amount: tax + surcharge - month_to_date.withheld,
+    }
+}
+
+/// PPh 21 withheld in December, settling the year's tax. `months` counts every month of the year
+/// with income, December included
+pub fn december_withholding(profile: TaxProfile, income: i64, year_to_date: ToDate, months: i64) -> Withholding {
+    let gross_income = year_to_date.taxable_income + income;
+    let occupational_cost = (gross_income * 5 / 100).min(OCCUPATIONAL_COST_MONTHLY_MAX * months);
+    let ptkp = profile.ptkp_status.annual_ptkp();
+    let taxable_income = (gross_income - occupational_cost - ptkp).max(0) / 1000 * 1000;
+
+    let brackets = annual_brackets(taxable_income);
+    let tax = brackets.iter().map(|bracket| bracket.amount).sum::<i64>();
+    let surcharge = surcharge(profile, tax);
+
+    Withholding {
+        method: WithholdingMethod::AnnualReconciliation(AnnualTax {
+            months,
+            gross_income,
+            occupational_cost,
+            ptkp,
+            taxable_income,
+            brackets,
+            withheld_before: year_to_date.withheld,
+        }),
+        tax,
+        surcharge,
+        amount: tax + surcharge - year_to_date.withheld,
+    }
+}
+
+fn surcharge(profile: TaxProfile, tax: i64) -> i64 {
+    if profile.has_npwp { 0 } else { tax * 20 / 100 }
+}
+
+/// Splits the yearly taxable income into the progressive brackets it reaches
+fn annual_brackets(mut taxable_income: i64) -> Vec<TaxBracket> {
+    let mut res = Vec::new();
+
+    for (length, rate) in ANNUAL_BRACKETS {
+        if taxable_income <= 0 {
+            break
+        }
+
+        let bracket_income = length.map_or(taxable_income, |length| taxable_income.min(length));
+        res.push(TaxBracket { taxable_income: bracket_income, rate: *rate, amount: bracket_income * rate / 10_000 });
+        taxable_income -= bracket_income;
+    }
+
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ptkp_adds_up_marriage_and_dependants() {
+        assert_eq!(PtkpStatus::Tk0.annual_ptkp(), 54_000_000);
+        assert_eq!(PtkpStatus::Tk2.annual_ptkp(), 63_000_000);
+        assert_eq!(PtkpStatus::K0.annual_ptkp(), 58_500_000);
+        assert_eq!(PtkpStatus::K3.annual_ptkp(), 72_000_000);
+    }
+
+    #[test]
+    fn test_ter_rate_by_category_and_income() {
+        assert_eq!(TerCategory::A.rate(5_400_000), 0);
+        assert_eq!(TerCategory::A.rate(5_400_001), 25);
+        assert_eq!(TerCategory::A.rate(10_000_000), 200);
+        assert_eq!(TerCategory::B.rate(10_000_000), 150);
+        assert_eq!(TerCategory::C.rate(10_000_000), 150);
+        assert_eq!(TerCategory::C.rate(2_000_000_000), 3400);
+    }
+
+    #[test]
+    fn test_monthly_withholding_adds_surcharge_without_npwp() {
+        let with_npwp = monthly_withholding(TaxProfile { ptkp_status: PtkpStatus::Tk0, has_npwp: true }, 10_000_000, ToDate::default());
+        assert_eq!(with_npwp.method, WithholdingMethod::Ter { category: TerCategory::A, rate: 200, monthly_income: 10_000_000, withheld_before: 0 });
+        assert_eq!(with_npwp.amount, 200_000);
+
+        let without_npwp = monthly_withholding(TaxProfile { ptkp_status: PtkpStatus::Tk0, has_npwp: false }, 10_000_000, ToDate::default());
+        assert_eq!(without_npwp.surcharge, 40_000);
+        assert_eq!(without_npwp.amount, 240_000);
+    }
+
+    #[test]
+    fn test_monthly_withholding_rates_the_whole_month() {
+        let profile = TaxProfile { ptkp_status: PtkpStatus::Tk0, has_npwp: true };
+
+        // Each half alone would be under the 5.4M the rate starts at
+        let first_half = monthly_withholding(profile, 5_000_000, ToDate::default());
+        assert_eq!(first_half.amount, 0);
+
+        let second_half = monthly_withholding(profile, 5_000_000, ToDate { taxable_income: 5_000_000, withheld: first_half.amount });
+        assert_eq!(second_half.method, WithholdingMethod::Ter { category: TerCategory::A, rate: 200, monthly_income: 10_000_000, withheld_before: 0 });
+        assert_eq!(second_half.amount, 200_000);
+
+        let after_withheld = monthly_withholding(profile, 5_000_000, ToDate { taxable_income: 5_000_000, withheld: 50_000 });
+        assert_eq!(after_withheld.tax, 200_000);
+        assert_eq!(after_withheld.amount, 150_000);
+    }
+
+    #[test]
+    fn test_december_settles_the_year() {
+        let profile = TaxProfile { ptkp_status: PtkpStatus::Tk0, has_npwp: true };
+        let year_to_date = ToDate { taxable_income: 11 * 10_000_000, withheld: 11 * 200_000 };
+
+        let withholding = december_withholding(profile, 10_000_000, year_to_date, 12);
+        let WithholdingMethod::AnnualReconciliation(annual) = &withholding.method else {
+            panic!("december must reconcile")
+        };
+
+        // 120M gross, 6M occupational cost capped, 54M PTKP
+        assert_eq!(annual.occupational_cost, 6_000_000);
+        assert_eq!(annual.taxable_income, 60_000_000);
+        assert_eq!(withholding.tax, 3_000_000);
+        assert_eq!(withholding.amount, 800_000);
+    }
+
+    #[test]
+    fn test_december_pays_back_what_was_over_withheld() {
+        let profile = TaxProfile { ptkp_status: PtkpStatus::K3, has_npwp: true };
+        let year_to_date = ToDate { taxable_income: 5_000_000, withheld: 100_000 };
+
+        let withholding = december_withholding(profile, 5_000_000, year_to_date, 2);
+        assert_eq!(withholding.tax, 0);
+        assert_eq!(withholding.amount, -100_000);
+    }
+
+    #[test]
+    fn test_annual_brackets_are_progressive() {
+        assert_eq!(annual_brackets(0), vec![]);
+        assert_eq!(
+            annual_brackets(300_000_000).iter().map(|b| (b.taxable_income, b.amount)).collect::<Vec<_>>(),
+            vec![(60_000_000, 3_000_000), (190_000_000, 28_500_000), (50_000_000, 12_500_000)],
+        );
+    }
+}
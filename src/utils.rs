@@ -1,47 +1,70 @@
-use chrono::{DateTime, Datelike as _, Days, FixedOffset, Timelike as _, Weekday};
+use chrono::{DateTime, FixedOffset};
 
-pub fn get_today_range(time: &DateTime<FixedOffset>) -> (DateTime<FixedOffset>, DateTime<FixedOffset>) {
-    let start_of_day = time.with_hour(0).unwrap().with_minute(0).unwrap().with_second(0).unwrap();
-    let end_of_day = time.with_hour(23).unwrap().with_minute(59).unwrap().with_second(59).unwrap();
-    
-    (start_of_day, end_of_day)
-}
+use crate::schedule::EmployeeSchedule;
 
-pub fn count_working_days(mut start: DateTime<FixedOffset>, end: DateTime<FixedOffset>) -> i64 {
-    let mut working_days = 0;
+pub fn count_working_days(start: DateTime<FixedOffset>, end: DateTime<FixedOffset>, schedule: &EmployeeSchedule) -> i64 {
+    schedule.working_days(start.date_naive(), end.date_naive()).count() as i64
+}
 
-    while start <= end {
-        if start.weekday() != Weekday::Sat && start.weekday() != Weekday::Sun {
-            working_days += 1;
-        }
+/// Minutes between check-in and check-out without the break
+pub fn count_worked_minutes(checked_in_at: DateTime<FixedOffset>, checked_out_at: DateTime<FixedOffset>, break_minutes: i64) -> i64 {
+    ((checked_out_at - checked_in_at).num_minutes() - break_minutes).max(0)
+}
 
-        start = start.checked_add_days(Days::new(1)).unwrap();
-    }
-    
-    working_days
+/// Sum of paid minutes over every working day between `start` and `end`
+pub fn count_working_minutes(start: DateTime<FixedOffset>, end: DateTime<FixedOffset>, schedule: &EmployeeSchedule) -> i64 {
+    schedule.working_days(start.date_naive(), end.date_naive())
+        .map(|(_, schedule)| schedule.working_minutes())
+        .sum()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    use chrono::{Local, TimeZone as _};
+    use chrono::{Local, NaiveTime, TimeZone as _, Weekday};
+
+    use crate::schedule::Schedule;
 
     #[test]
-    fn test_get_today_range() {
-        let time = Local.with_ymd_and_hms(2023, 10, 10, 8, 30, 0).unwrap().fixed_offset();
+    fn test_count_working_days() {
+        let period_start = Local.with_ymd_and_hms(2024, 6, 1, 8, 30, 0).unwrap().fixed_offset();
+        let period_end = Local.with_ymd_and_hms(2024, 6, 30, 8, 30, 0).unwrap().fixed_offset();
         
-        let (start, end) = get_today_range(&time);
+        assert_eq!(count_working_days(period_start, period_end, &EmployeeSchedule::default()), 20);
+
+        let retail = EmployeeSchedule::from(Schedule {
+            working_days: Schedule::weekdays_mask(&[Weekday::Tue, Weekday::Wed, Weekday::Thu, Weekday::Fri, Weekday::Sat]),
+            ..Default::default()
+        });
         
-        assert_eq!(start, Local.with_ymd_and_hms(2023, 10, 10, 0, 0, 0).unwrap().fixed_offset());
-        assert_eq!(end, Local.with_ymd_and_hms(2023, 10, 10, 23, 59, 59).unwrap().fixed_offset());
+        assert_eq!(count_working_days(period_start, period_end, &retail), 21);
     }
     
     #[test]
-    fn test_count_working_days() {
-        let period_start = Local.with_ymd_and_hms(2024, 6, 1, 8, 30, 0).unwrap().fixed_offset();
-        let period_end = Local.with_ymd_and_hms(2024, 6, 30, 8, 30, 0).unwrap().fixed_offset();
+    fn test_count_worked_minutes() {
+        let checked_in_at = Local.with_ymd_and_hms(2024, 6, 3, 9, 5, 0).unwrap().fixed_offset();
+        let checked_out_at = Local.with_ymd_and_hms(2024, 6, 3, 17, 30, 0).unwrap().fixed_offset();
+        
+        assert_eq!(count_worked_minutes(checked_in_at, checked_out_at, 0), 8 * 60 + 25);
+        assert_eq!(count_worked_minutes(checked_in_at, checked_out_at, 60), 7 * 60 + 25);
+        assert_eq!(count_worked_minutes(checked_in_at, checked_in_at, 60), 0);
+    }
+    
+    #[test]
+    fn test_count_working_minutes() {
+        let period_start = Local.with_ymd_and_hms(2024, 6, 3, 0, 0, 0).unwrap().fixed_offset();
+        let period_end = Local.with_ymd_and_hms(2024, 6, 5, 0, 0, 0).unwrap().fixed_offset();
         
-        assert_eq!(count_working_days(period_start, period_end), 20);
+        assert_eq!(count_working_minutes(period_start, period_end, &EmployeeSchedule::default()), 3 * 8 * 60);
+        
+        let support = EmployeeSchedule::from(Schedule {
+            shift_start: NaiveTime::from_hms_opt(7, 0, 0).unwrap(),
+            shift_end: NaiveTime::from_hms_opt(13, 0, 0).unwrap(),
+            break_minutes: 30,
+            ..Default::default()
+        });
+
+        assert_eq!(count_working_minutes(period_start, period_end, &support), 3 * (6 * 60 - 30));
     }
 }
@@ -1,4 +1,4 @@
-from datetime import datetime, timezone
+from datetime import datetime, timedelta, timezone
 import string
 from dotenv import load_dotenv
 import docker
@@ -12,6 +12,8 @@ import pytz
 import requests
 import pytest
 import random
+import boto3
+from concurrent.futures import ThreadPoolExecutor
 
 # Pretty hacky way of getting an available port
 def get_available_port():
@@ -66,7 +68,8 @@ def build_docker_image(dockerfile, image_name, label):
 
 be_img = build_docker_image("be.dockerfile", "backend-test:latest", "Backend-Test")
 
-def spin_environment(faketime: datetime, pg_vol: str):
+# Without `faketime` the backend runs on the real clock, for what checks the time like S3's signatures
+def spin_environment(faketime: datetime | None, pg_vol: str, env: dict = {}):
     containers = []
 
     test_db_port = get_available_port()
@@ -121,7 +124,8 @@ def spin_environment(faketime: datetime, pg_vol: str):
             "DATABASE_URL": f"postgres://postgres:postgres@{gateway_ip}:{test_db_port}/postgres",
             "HOST_ADDRESS": backend_host.replace("127.0.0.1", "0.0.0.0"),
             "JWT_SECRET": "secret",
-            "FAKETIME": faketime.strftime('%Y-%m-%d %H:%M:%S'),
+            **({ "FAKETIME": faketime.strftime('%Y-%m-%d %H:%M:%S') } if faketime else {}),
+            **env,
         },
         volumes={
             f"{pg_vol}": { "bind": "/var/lib/postgresql/data", "mode": "rw" },
@@ -141,10 +145,48 @@ def spin_environment(faketime: datetime, pg_vol: str):
         print("Waiting for test backend to spin up")
         time.sleep(1)
         
+    faketime = faketime or datetime.now()
+
     print("A new environment is spun up:", (test_db_url, backend_host, faketime))
         
     return (test_db_url, f"http://{backend_host}", faketime, containers)
 
+def spin_minio(bucket: str):
+    minio_port = get_available_port()
+
+    minio_container = docker_client.containers.run(
+        image="minio/minio:RELEASE.2025-04-22T22-12-26Z",
+        command="server /data",
+        ports={ "9000/tcp": int(minio_port) },
+        environment={
+            "MINIO_ROOT_USER": "minio",
+            "MINIO_ROOT_PASSWORD": "minio-secret",
+        },
+        network="bridge",
+        auto_remove=True,
+        detach=True
+    )
+
+    s3 = boto3.client(
+        "s3",
+        endpoint_url=f"http://127.0.0.1:{minio_port}",
+        aws_access_key_id="minio",
+        aws_secret_access_key="minio-secret",
+        region_name="us-east-1",
+    )
+
+    while True:
+        try:
+            s3.create_bucket(Bucket=bucket)
+            break
+        except Exception:
+            print("Waiting for test object storage to spin up")
+            time.sleep(1)
+
+    gateway_ip = docker_client.networks.get("bridge").attrs["IPAM"]["Config"][0]["Gateway"]
+
+    return (s3, f"http://{gateway_ip}:{minio_port}", minio_container)
+
 def generate_random_string(length, char_set=string.ascii_uppercase + string.digits):
     return ''.join(random.choice(char_set) for _ in range(length))
 
@@ -179,6 +221,14 @@ def create_and_get_random_user(pg_conn, backend_host, faketime, role_type, salar
 
     return res.text
 
+def get_reimbursement_category(backend_host, token, name):
+    res = requests.get(f"{backend_host}/reimbursement_category", headers={
+        "Authorization": f"JWT {token}"
+    })
+    assert res.status_code == 200
+
+    return next(category["id"] for category in res.json() if category["name"] == name)
+
 ### === The actual tests starts from here === ###
     
 def test_auth(tmp_path):
@@ -280,13 +330,13 @@ def test_attendance_weekend(tmp_path):
         "Authorization": f"JWT {employee}"
     })
     assert res_attended.status_code == 400
-    assert res_attended.text == "cannot attend on weekend"
+    assert res_attended.text == "cannot attend on a rest day"
     
     stop_containers(containers)
 
 def test_employee_overtime(tmp_path):
-    # Test on Monday 3rd, June 2024 5 PM (After work hour)
-    test_db_url, backend_host, faketime, containers = spin_environment(datetime(2024, 6, 3, 18, 0, 0), tmp_path)
+    # Test on Monday 3rd, June 2024 10 PM (After work hour)
+    test_db_url, backend_host, faketime, containers = spin_environment(datetime(2024, 6, 3, 22, 0, 0), tmp_path)
     pg_conn = psycopg2.connect(test_db_url)
 
     employee = create_and_get_random_user(pg_conn, backend_host, faketime, "employee", 5000000)
@@ -302,13 +352,17 @@ def test_employee_overtime(tmp_path):
     
     attendance_id = res_created.json()["id"]
 
-    res_overtime_has_not_checked_in = requests.post(f"{backend_host}/attendance/{attendance_id}/overtime", headers={
-        "Authorization": f"JWT {employee}"
-    }, json={
-        "extra_hours": 3
-    })
+    def overtime(started_at, ended_at):
+        return requests.post(f"{backend_host}/attendance/{attendance_id}/overtime", headers={
+            "Authorization": f"JWT {employee}"
+        }, json={
+            "started_at": started_at.replace(tzinfo=pytz.UTC).isoformat(),
+            "ended_at": ended_at.replace(tzinfo=pytz.UTC).isoformat(),
+        })
+
+    res_overtime_has_not_checked_in = overtime(datetime(2024, 6, 3, 17, 0), datetime(2024, 6, 3, 18, 0))
     assert res_overtime_has_not_checked_in.status_code == 400
-    assert res_overtime_has_not_checked_in.text == "you have not checked-in today"
+    assert res_overtime_has_not_checked_in.text == "you have not checked-in on that day"
 
     res_attended = requests.post(f"{backend_host}/attendance/{attendance_id}", headers={
         "Authorization": f"JWT {employee}"
@@ -316,37 +370,62 @@ def test_employee_overtime(tmp_path):
     # Still works because the requirement says that "No rules for late or early check-ins or check-outs; check-in at any time that day counts"
     assert res_attended.status_code == 201
 
-    res_overtime_too_much_hours = requests.post(f"{backend_host}/attendance/{attendance_id}/overtime", headers={
-        "Authorization": f"JWT {employee}"
-    }, json={
-        "extra_hours": 6
-    })
+    res_overtime_too_much_hours = overtime(datetime(2024, 6, 3, 17, 0), datetime(2024, 6, 3, 21, 30))
     assert res_overtime_too_much_hours.status_code == 400
-    assert res_overtime_too_much_hours.text == "you cannot take overtime for more than 3 hours a day"
+    assert res_overtime_too_much_hours.text == "you cannot take overtime for more than 4 hours a day, 4 hours remaining"
 
-    res_overtime = requests.post(f"{backend_host}/attendance/{attendance_id}/overtime", headers={
-        "Authorization": f"JWT {employee}"
-    }, json={
-        "extra_hours": 1
-    })
+    res_overtime_negative = overtime(datetime(2024, 6, 3, 18, 0), datetime(2024, 6, 3, 17, 0))
+    assert res_overtime_negative.status_code == 400
+    assert res_overtime_negative.text == "ended_at must be later than started_at"
+
+    res_overtime_during_shift = overtime(datetime(2024, 6, 3, 16, 30), datetime(2024, 6, 3, 17, 30))
+    assert res_overtime_during_shift.status_code == 400
+    assert res_overtime_during_shift.text == "overtime must start after your shift ends"
+
+    res_overtime_in_future = overtime(datetime(2024, 6, 3, 21, 30), datetime(2024, 6, 3, 22, 30))
+    assert res_overtime_in_future.status_code == 400
+    assert res_overtime_in_future.text == "ended_at must not be in the future"
+
+    res_overtime = overtime(datetime(2024, 6, 3, 17, 0), datetime(2024, 6, 3, 17, 30))
     assert res_overtime.status_code == 201
     assert res_overtime.json()["attendance_period_id"] == attendance_id
-    
-    res_overtime_update = requests.post(f"{backend_host}/attendance/{attendance_id}/overtime", headers={
+    assert res_overtime.json()["work_date"] == "2024-06-03"
+    assert res_overtime.json()["minutes"] == 30
+
+    res_overtime_overlap = overtime(datetime(2024, 6, 3, 17, 15), datetime(2024, 6, 3, 18, 0))
+    assert res_overtime_overlap.status_code == 400
+    assert res_overtime_overlap.text == "overtime overlaps with another entry"
+
+    res_overtime_later = overtime(datetime(2024, 6, 3, 18, 0), datetime(2024, 6, 3, 20, 0))
+    assert res_overtime_later.status_code == 201
+    assert res_overtime_later.json()["id"] != res_overtime.json()["id"]
+
+    res_overtime_update_too_much_hours = overtime(datetime(2024, 6, 3, 20, 0), datetime(2024, 6, 3, 21, 45))
+    assert res_overtime_update_too_much_hours.status_code == 400
+    assert res_overtime_update_too_much_hours.text == "you cannot take overtime for more than 4 hours a day, 1 hour 30 minutes remaining"
+
+    # Shortening an entry makes room for another one
+    res_overtime_edited = requests.put(f"{backend_host}/attendance/{attendance_id}/overtime/{res_overtime_later.json()['id']}", headers={
         "Authorization": f"JWT {employee}"
     }, json={
-        "extra_hours": 2
+        "started_at": datetime(2024, 6, 3, 18, 0).replace(tzinfo=pytz.UTC).isoformat(),
+        "ended_at": datetime(2024, 6, 3, 19, 30).replace(tzinfo=pytz.UTC).isoformat(),
     })
-    assert res_overtime_update.status_code == 200 # Because it updates existing overtime entry
-    assert res_overtime_update.json()["id"] == res_overtime.json()["id"]
+    assert res_overtime_edited.status_code == 200
+    assert res_overtime_edited.json()["minutes"] == 90
+
+    res_overtime_more = overtime(datetime(2024, 6, 3, 20, 0), datetime(2024, 6, 3, 20, 45))
+    assert res_overtime_more.status_code == 201
 
-    res_overtime_update_too_much_hours = requests.post(f"{backend_host}/attendance/{attendance_id}/overtime", headers={
+    res_overtime_deleted = requests.delete(f"{backend_host}/attendance/{attendance_id}/overtime/{res_overtime_more.json()['id']}", headers={
         "Authorization": f"JWT {employee}"
-    }, json={
-        "extra_hours": 1
     })
-    assert res_overtime_update_too_much_hours.status_code == 400
-    assert res_overtime_update_too_much_hours.text == "you cannot take overtime for more than 3 hours a day"
+    assert res_overtime_deleted.status_code == 204
+
+    pg_curr = pg_conn.cursor()
+    pg_curr.execute("SELECT COUNT(*), SUM(minutes) FROM employee_overtime WHERE attendance_period_id = %s", (attendance_id,))
+    assert pg_curr.fetchone() == (2, 120)
+    pg_curr.close()
     
     stop_containers(containers)
 
@@ -371,6 +450,7 @@ def test_employee_reimburse(tmp_path):
     res_reimburse = requests.post(f"{backend_host}/attendance/{attendance_id}/reimburse", headers={
         "Authorization": f"JWT {employee}"
     }, json={
+        "category_id": get_reimbursement_category(backend_host, employee, "Transport"),
         "description": "Commute",
         "amount": 20_000
     })
@@ -415,6 +495,7 @@ def test_payroll(tmp_path):
         res_reimburse_e_1 = requests.post(f"{backend_host}/attendance/{attendance_id}/reimburse", headers={
             "Authorization": f"JWT {employee_1}"
         }, json={
+            "category_id": get_reimbursement_category(backend_host, employee_1, "Transport"),
             "description": "Commute",
             "amount": 20_000
         })
@@ -426,6 +507,7 @@ def test_payroll(tmp_path):
         res_reimburse_e_2 = requests.post(f"{backend_host}/attendance/{attendance_id}/reimburse", headers={
             "Authorization": f"JWT {employee_2}"
         }, json={
+            "category_id": get_reimbursement_category(backend_host, employee_2, "Equipment"),
             "description": "Office supplies",
             "amount": 100_000
         })
@@ -454,6 +536,7 @@ def test_payroll(tmp_path):
         res_reimburse_e_1 = requests.post(f"{backend_host}/attendance/{attendance_id}/reimburse", headers={
             "Authorization": f"JWT {employee_1}"
         }, json={
+            "category_id": get_reimbursement_category(backend_host, employee_1, "Transport"),
             "description": "Commute",
             "amount": 20_000
         })
@@ -462,15 +545,37 @@ def test_payroll(tmp_path):
         assert res_reimburse_e_1.json()["amount"] == 20_000
         assert res_reimburse_e_1.json()["attendance_period_id"] == attendance_id
         
-        # So employee 1 took some overtime
+        # So employee 1 took some overtime on the first day
 
         res_overtime = requests.post(f"{backend_host}/attendance/{attendance_id}/overtime", headers={
             "Authorization": f"JWT {employee_1}"
         }, json={
-            "extra_hours": 3
+            "started_at": datetime(2024, 6, 3, 17, 0).replace(tzinfo=pytz.UTC).isoformat(),
+            "ended_at": datetime(2024, 6, 3, 20, 0).replace(tzinfo=pytz.UTC).isoformat(),
         })
         assert res_overtime.status_code == 201
         assert res_overtime.json()["attendance_period_id"] == attendance_id
+
+        # Overtime is only paid once it's approved
+        res_overtime_approved = requests.post(f"{backend_host}/attendance/{attendance_id}/overtime/{res_overtime.json()['id']}/approve", headers={
+            "Authorization": f"JWT {admin}"
+        })
+        assert res_overtime_approved.status_code == 200
+        assert res_overtime_approved.json()["status"] == "Approved"
+
+        # So are the reimbursement claims
+        res_submitted = requests.get(f"{backend_host}/attendance/{attendance_id}/reimburse?status=Submitted", headers={
+            "Authorization": f"JWT {admin}"
+        })
+        assert res_submitted.status_code == 200
+        assert len(res_submitted.json()) == 3
+
+        for reimbursement in res_submitted.json():
+            res_reimburse_approved = requests.post(f"{backend_host}/attendance/{attendance_id}/reimburse/{reimbursement['id']}/approve", headers={
+                "Authorization": f"JWT {admin}"
+            })
+            assert res_reimburse_approved.status_code == 200
+            assert res_reimburse_approved.json()["status"] == "Approved"
         
         return (backend_host, containers)
         
@@ -531,3 +636,2670 @@ def test_payroll(tmp_path):
     assert res_employee_payslips.json()["total_take_home"] == res_payslip_e_1.json()["summary"]["take_home_pay"] + res_payslip_e_2.json()["summary"]["take_home_pay"]
 
     stop_containers(containers)
+
+def test_work_schedule(tmp_path):
+    # Test on Saturday 8th, June 2024
+    test_db_url, backend_host, faketime, containers = spin_environment(datetime(2024, 6, 8, 11, 0, 0), tmp_path)
+    pg_conn = psycopg2.connect(test_db_url)
+
+    office_employee = create_and_get_random_user(pg_conn, backend_host, faketime, "employee", 5000000)
+    retail_employee = create_and_get_random_user(pg_conn, backend_host, faketime, "employee", 5000000)
+    admin = create_and_get_random_user(pg_conn, backend_host, faketime, "admin", 0)
+
+    retail_employee_id = requests.get(f"{backend_host}/auth", headers={
+        "Authorization": f"JWT {retail_employee}"
+    }).json()["id"]
+
+    res_forbidden = requests.post(f"{backend_host}/schedule", headers={
+        "Authorization": f"JWT {retail_employee}"
+    }, json={
+        "name": "Retail",
+        "working_days": ["Tue", "Wed", "Thu", "Fri", "Sat"],
+        "shift_start": "10:00:00",
+        "shift_end": "19:00:00",
+        "break_minutes": 60,
+    })
+    assert res_forbidden.status_code == 403
+
+    res_schedule = requests.post(f"{backend_host}/schedule", headers={
+        "Authorization": f"JWT {admin}"
+    }, json={
+        "name": "Retail",
+        "working_days": ["Tue", "Wed", "Thu", "Fri", "Sat"],
+        "shift_start": "10:00:00",
+        "shift_end": "19:00:00",
+        "break_minutes": 60,
+    })
+    assert res_schedule.status_code == 201
+
+    schedule_id = res_schedule.json()["id"]
+
+    res_assigned = requests.post(f"{backend_host}/schedule/{schedule_id}/assign", headers={
+        "Authorization": f"JWT {admin}"
+    }, json={
+        "user_id": retail_employee_id,
+        "effective_from": "2024-06-01",
+    })
+    assert res_assigned.status_code == 201
+
+    res_created = requests.post(f"{backend_host}/attendance", headers={
+        "Authorization": f"JWT {admin}"
+    }, json={
+        "start_at": datetime(2024, 6, 1, 0, 0).replace(tzinfo=pytz.UTC).isoformat(),
+        "end_at": datetime(2024, 6, 30, 0, 0).replace(tzinfo=pytz.UTC).isoformat(),
+    })
+    assert res_created.status_code == 201
+
+    attendance_id = res_created.json()["id"]
+
+    res_office_attended = requests.post(f"{backend_host}/attendance/{attendance_id}", headers={
+        "Authorization": f"JWT {office_employee}"
+    })
+    assert res_office_attended.status_code == 400
+    assert res_office_attended.text == "cannot attend on a rest day"
+
+    res_retail_attended = requests.post(f"{backend_host}/attendance/{attendance_id}", headers={
+        "Authorization": f"JWT {retail_employee}"
+    })
+    assert res_retail_attended.status_code == 201
+
+    # Retail shift ends at 7 PM, so it's too early to take overtime
+    res_overtime = requests.post(f"{backend_host}/attendance/{attendance_id}/overtime", headers={
+        "Authorization": f"JWT {retail_employee}"
+    }, json={
+        "started_at": datetime(2024, 6, 8, 10, 0).replace(tzinfo=pytz.UTC).isoformat(),
+        "ended_at": datetime(2024, 6, 8, 11, 0).replace(tzinfo=pytz.UTC).isoformat(),
+    })
+    assert res_overtime.status_code == 400
+    assert res_overtime.text == "overtime must start after your shift ends"
+
+    stop_containers(containers)
+
+def test_attendance_checkout(tmp_path):
+    def morning():
+        # Test on Monday 3rd, June 2024 9 AM
+        test_db_url, backend_host, faketime, containers = spin_environment(datetime(2024, 6, 3, 9, 0, 0), tmp_path)
+        pg_conn = psycopg2.connect(test_db_url)
+
+        employee = create_and_get_random_user(pg_conn, backend_host, faketime, "employee", 5000000)
+        forgetful_employee = create_and_get_random_user(pg_conn, backend_host, faketime, "employee", 5000000)
+        admin = create_and_get_random_user(pg_conn, backend_host, faketime, "admin", 0)
+
+        res_created = requests.post(f"{backend_host}/attendance", headers={
+            "Authorization": f"JWT {admin}"
+        }, json={
+            "start_at": datetime(2024, 6, 3, 0, 0).replace(tzinfo=pytz.UTC).isoformat(),
+            "end_at": datetime(2024, 6, 5, 0, 0).replace(tzinfo=pytz.UTC).isoformat(),
+        })
+        assert res_created.status_code == 201
+
+        attendance_id = res_created.json()["id"]
+
+        res_checkout_not_checked_in = requests.post(f"{backend_host}/attendance/{attendance_id}/checkout", headers={
+            "Authorization": f"JWT {employee}"
+        })
+        assert res_checkout_not_checked_in.status_code == 400
+        assert res_checkout_not_checked_in.text == "you have not checked-in today"
+
+        for token in [employee, forgetful_employee]:
+            res_attended = requests.post(f"{backend_host}/attendance/{attendance_id}", headers={
+                "Authorization": f"JWT {token}"
+            })
+            assert res_attended.status_code == 201
+
+        stop_containers(containers)
+
+        return (employee, forgetful_employee, admin, attendance_id)
+
+    employee, forgetful_employee, admin, attendance_id = morning()
+
+    # Test on Monday 3rd, June 2024 5:30 PM
+    _, backend_host, _, containers = spin_environment(datetime(2024, 6, 3, 17, 30, 0), tmp_path)
+
+    res_checkout_invalid_break = requests.post(f"{backend_host}/attendance/{attendance_id}/checkout", headers={
+        "Authorization": f"JWT {employee}"
+    }, json={
+        "break_started_at": datetime(2024, 6, 3, 8, 0).replace(tzinfo=pytz.UTC).isoformat(),
+        "break_ended_at": datetime(2024, 6, 3, 9, 0).replace(tzinfo=pytz.UTC).isoformat(),
+    })
+    assert res_checkout_invalid_break.status_code == 400
+    assert res_checkout_invalid_break.text == "break must be taken between check-in and check-out"
+
+    res_checkout = requests.post(f"{backend_host}/attendance/{attendance_id}/checkout", headers={
+        "Authorization": f"JWT {employee}"
+    }, json={
+        "break_started_at": datetime(2024, 6, 3, 12, 0).replace(tzinfo=pytz.UTC).isoformat(),
+        "break_ended_at": datetime(2024, 6, 3, 13, 0).replace(tzinfo=pytz.UTC).isoformat(),
+    })
+    assert res_checkout.status_code == 200
+    assert res_checkout.json()["worked_minutes"] == 7 * 60 + 30
+    assert res_checkout.json()["auto_checked_out"] == False
+
+    # Forgetful employee never checks-out, so processing the payroll closes it at the end of the shift
+    res_process_payroll = requests.post(f"{backend_host}/attendance/{attendance_id}/process_payroll", headers={
+        "Authorization": f"JWT {admin}"
+    })
+    assert res_process_payroll.status_code == 200
+
+    res_payslip = requests.get(f"{backend_host}/attendance/{attendance_id}/payslip", headers={
+        "Authorization": f"JWT {employee}"
+    })
+    assert res_payslip.status_code == 200
+    assert res_payslip.json()["attendance"]["worked_minutes"] == 7 * 60 + 30
+    assert res_payslip.json()["attendance"]["worked_hours"] == 7.5
+    assert res_payslip.json()["attendance"]["auto_checked_out_days"] == 0
+
+    res_forgetful_payslip = requests.get(f"{backend_host}/attendance/{attendance_id}/payslip", headers={
+        "Authorization": f"JWT {forgetful_employee}"
+    })
+    assert res_forgetful_payslip.status_code == 200
+    assert res_forgetful_payslip.json()["attendance"]["worked_minutes"] == 8 * 60
+    assert res_forgetful_payslip.json()["attendance"]["auto_checked_out_days"] == 1
+
+    stop_containers(containers)
+
+def test_leave(tmp_path):
+    # Test on Friday 31st, May 2024
+    test_db_url, backend_host, faketime, containers = spin_environment(datetime(2024, 5, 31, 10, 0, 0), tmp_path)
+    pg_conn = psycopg2.connect(test_db_url)
+
+    employee = create_and_get_random_user(pg_conn, backend_host, faketime, "employee", 5000000)
+    admin = create_and_get_random_user(pg_conn, backend_host, faketime, "admin", 0)
+
+    res_weekend_leave = requests.post(f"{backend_host}/leave", headers={
+        "Authorization": f"JWT {employee}"
+    }, json={
+        "leave_type": "Annual",
+        "start_date": "2024-06-01",
+        "end_date": "2024-06-02",
+        "reason": "Weekend",
+    })
+    assert res_weekend_leave.status_code == 400
+    assert res_weekend_leave.text == "leave does not cover any working day"
+
+    res_too_long_leave = requests.post(f"{backend_host}/leave", headers={
+        "Authorization": f"JWT {employee}"
+    }, json={
+        "leave_type": "Annual",
+        "start_date": "2024-06-03",
+        "end_date": "2024-06-28",
+        "reason": "Holiday",
+    })
+    assert res_too_long_leave.status_code == 400
+    assert res_too_long_leave.text == "insufficient leave balance"
+
+    # Monday 3rd to Tuesday 4th, June 2024 on annual leave
+    res_annual_leave = requests.post(f"{backend_host}/leave", headers={
+        "Authorization": f"JWT {employee}"
+    }, json={
+        "leave_type": "Annual",
+        "start_date": "2024-06-03",
+        "end_date": "2024-06-04",
+        "reason": "Family trip",
+    })
+    assert res_annual_leave.status_code == 201
+    assert res_annual_leave.json()["days"] == 2
+    assert res_annual_leave.json()["status"] == "Pending"
+
+    res_overlapping_leave = requests.post(f"{backend_host}/leave", headers={
+        "Authorization": f"JWT {employee}"
+    }, json={
+        "leave_type": "Unpaid",
+        "start_date": "2024-06-04",
+        "end_date": "2024-06-05",
+        "reason": "Errands",
+    })
+    assert res_overlapping_leave.status_code == 400
+    assert res_overlapping_leave.text == "leave overlaps an existing one"
+
+    # Wednesday 5th, June 2024 on unpaid leave
+    res_unpaid_leave = requests.post(f"{backend_host}/leave", headers={
+        "Authorization": f"JWT {employee}"
+    }, json={
+        "leave_type": "Unpaid",
+        "start_date": "2024-06-05",
+        "end_date": "2024-06-05",
+        "reason": "Errands",
+    })
+    assert res_unpaid_leave.status_code == 201
+
+    res_balance = requests.get(f"{backend_host}/leave/balance", headers={
+        "Authorization": f"JWT {employee}"
+    })
+    assert res_balance.status_code == 200
+    annual_balance = next(b for b in res_balance.json() if b["leave_type"] == "Annual")
+    assert annual_balance["pending_days"] == 2
+    assert annual_balance["remaining_days"] == 10
+    assert annual_balance["carried_over_days"] == 0
+
+    # Joined last year without taking any leave, so the most that can be is carried over
+    veteran = create_and_get_random_user(pg_conn, backend_host, faketime, "employee", 5000000)
+    veteran_id = requests.get(f"{backend_host}/auth", headers={
+        "Authorization": f"JWT {veteran}"
+    }).json()["id"]
+    pg_curr = pg_conn.cursor()
+    pg_curr.execute("UPDATE \"user\" SET created_at = '2023-03-01' WHERE id = %s", (veteran_id,))
+    pg_conn.commit()
+    pg_curr.close()
+
+    res_veteran_balance = requests.get(f"{backend_host}/leave/balance", headers={
+        "Authorization": f"JWT {veteran}"
+    })
+    assert res_veteran_balance.status_code == 200
+    veteran_annual_balance = next(b for b in res_veteran_balance.json() if b["leave_type"] == "Annual")
+    assert veteran_annual_balance["carried_over_days"] == 6
+    assert veteran_annual_balance["remaining_days"] == 18
+
+    res_approve_by_employee = requests.post(f"{backend_host}/leave/{res_annual_leave.json()['id']}/approve", headers={
+        "Authorization": f"JWT {employee}"
+    }, json={})
+    assert res_approve_by_employee.status_code == 403
+
+    for leave in [res_annual_leave, res_unpaid_leave]:
+        res_approved = requests.post(f"{backend_host}/leave/{leave.json()['id']}/approve", headers={
+            "Authorization": f"JWT {admin}"
+        }, json={})
+        assert res_approved.status_code == 200
+        assert res_approved.json()["status"] == "Approved"
+
+    res_approve_again = requests.post(f"{backend_host}/leave/{res_annual_leave.json()['id']}/reject", headers={
+        "Authorization": f"JWT {admin}"
+    }, json={})
+    assert res_approve_again.status_code == 400
+    assert res_approve_again.text == "leave is already reviewed"
+
+    # Payroll period of Monday 3rd to Friday 7th, June 2024 without any attendance
+    res_created = requests.post(f"{backend_host}/attendance", headers={
+        "Authorization": f"JWT {admin}"
+    }, json={
+        "start_at": datetime(2024, 6, 3, 0, 0).replace(tzinfo=pytz.UTC).isoformat(),
+        "end_at": datetime(2024, 6, 7, 0, 0).replace(tzinfo=pytz.UTC).isoformat(),
+    })
+    assert res_created.status_code == 201
+
+    attendance_id = res_created.json()["id"]
+
+    res_process_payroll = requests.post(f"{backend_host}/attendance/{attendance_id}/process_payroll", headers={
+        "Authorization": f"JWT {admin}"
+    })
+    assert res_process_payroll.status_code == 200
+
+    res_payslip = requests.get(f"{backend_host}/attendance/{attendance_id}/payslip", headers={
+        "Authorization": f"JWT {employee}"
+    })
+    assert res_payslip.status_code == 200
+    assert res_payslip.json()["attendance"]["paid_leave_days"] == 2
+    assert res_payslip.json()["attendance"]["unpaid_leave_days"] == 1
+    # Only the paid leave counts as attended
+    assert res_payslip.json()["attendance"]["prorated_amount"] == (5000000 * 2) // 5
+
+    stop_containers(containers)
+
+def test_lateness(tmp_path):
+    lateness_env = { "LATENESS_GRACE_MINUTES": "15", "LATENESS_WARNING_MINUTES": "60", "LATENESS_DEDUCTION_MINUTES": "30" }
+
+    def morning():
+        # Test on Monday 3rd, June 2024 9:40 AM
+        test_db_url, backend_host, faketime, containers = spin_environment(datetime(2024, 6, 3, 9, 40, 0), tmp_path, lateness_env)
+        pg_conn = psycopg2.connect(test_db_url)
+
+        employee = create_and_get_random_user(pg_conn, backend_host, faketime, "employee", 4800000)
+        punctual_employee = create_and_get_random_user(pg_conn, backend_host, faketime, "employee", 4800000)
+        admin = create_and_get_random_user(pg_conn, backend_host, faketime, "admin", 0)
+
+        res_created = requests.post(f"{backend_host}/attendance", headers={
+            "Authorization": f"JWT {admin}"
+        }, json={
+            "start_at": datetime(2024, 6, 3, 0, 0).replace(tzinfo=pytz.UTC).isoformat(),
+            "end_at": datetime(2024, 6, 3, 23, 0).replace(tzinfo=pytz.UTC).isoformat(),
+        })
+        assert res_created.status_code == 201
+
+        attendance_id = res_created.json()["id"]
+
+        res_attended = requests.post(f"{backend_host}/attendance/{attendance_id}", headers={
+            "Authorization": f"JWT {employee}"
+        })
+        assert res_attended.status_code == 201
+        assert res_attended.json()["late_minutes"] == 40
+
+        stop_containers(containers)
+
+        return (employee, punctual_employee, admin, attendance_id)
+
+    employee, punctual_employee, admin, attendance_id = morning()
+
+    def within_grace():
+        # Test on Monday 3rd, June 2024 9:10 AM, which is still within the grace period
+        _, backend_host, _, containers = spin_environment(datetime(2024, 6, 3, 9, 10, 0), tmp_path, lateness_env)
+
+        res_attended = requests.post(f"{backend_host}/attendance/{attendance_id}", headers={
+            "Authorization": f"JWT {punctual_employee}"
+        })
+        assert res_attended.status_code == 201
+        assert res_attended.json()["late_minutes"] == 10
+
+        stop_containers(containers)
+
+    within_grace()
+
+    # Test on Monday 3rd, June 2024 4:30 PM
+    _, backend_host, _, containers = spin_environment(datetime(2024, 6, 3, 16, 30, 0), tmp_path, lateness_env)
+
+    res_checkout = requests.post(f"{backend_host}/attendance/{attendance_id}/checkout", headers={
+        "Authorization": f"JWT {employee}"
+    })
+    assert res_checkout.status_code == 200
+    assert res_checkout.json()["early_leave_minutes"] == 30
+
+    res_process_payroll = requests.post(f"{backend_host}/attendance/{attendance_id}/process_payroll", headers={
+        "Authorization": f"JWT {admin}"
+    })
+    assert res_process_payroll.status_code == 200
+
+    res_payslip = requests.get(f"{backend_host}/attendance/{attendance_id}/payslip", headers={
+        "Authorization": f"JWT {employee}"
+    })
+    assert res_payslip.status_code == 200
+    # 40 minutes late and 30 minutes early, 30 of them are tolerated
+    assert res_payslip.json()["lateness"]["counted_minutes"] == 70
+    assert res_payslip.json()["lateness"]["warning"] == True
+    assert res_payslip.json()["lateness"]["deducted_minutes"] == 40
+    assert res_payslip.json()["summary"]["deduction_total"] == (4800000 * 40) // (8 * 60)
+    assert res_payslip.json()["summary"]["take_home_pay"] == 4800000 - (4800000 * 40) // (8 * 60)
+
+    res_punctual_payslip = requests.get(f"{backend_host}/attendance/{attendance_id}/payslip", headers={
+        "Authorization": f"JWT {punctual_employee}"
+    })
+    assert res_punctual_payslip.status_code == 200
+    assert res_punctual_payslip.json()["lateness"]["counted_minutes"] == 0
+    assert res_punctual_payslip.json()["lateness"]["warning"] == False
+    assert res_punctual_payslip.json()["deductions"] == []
+    assert res_punctual_payslip.json()["summary"]["take_home_pay"] == 4800000
+
+    stop_containers(containers)
+
+def test_checkin_policy(tmp_path):
+    # Requests reach the backend container through the Docker bridge, which acts as the proxy here
+    # Test on Monday 3rd, June 2024 9 AM
+    test_db_url, backend_host, faketime, containers = spin_environment(datetime(2024, 6, 3, 9, 0, 0), tmp_path, { "TRUSTED_PROXIES": "0.0.0.0/0" })
+    pg_conn = psycopg2.connect(test_db_url)
+
+    office_employee = create_and_get_random_user(pg_conn, backend_host, faketime, "employee", 5000000)
+    field_employee = create_and_get_random_user(pg_conn, backend_host, faketime, "employee", 5000000)
+    admin = create_and_get_random_user(pg_conn, backend_host, faketime, "admin", 0)
+
+    res_created = requests.post(f"{backend_host}/attendance", headers={
+        "Authorization": f"JWT {admin}"
+    }, json={
+        "start_at": datetime(2024, 6, 3, 0, 0).replace(tzinfo=pytz.UTC).isoformat(),
+        "end_at": datetime(2024, 6, 5, 0, 0).replace(tzinfo=pytz.UTC).isoformat(),
+    })
+    assert res_created.status_code == 201
+
+    attendance_id = res_created.json()["id"]
+
+    res_invalid_policy = requests.post(f"{backend_host}/checkin_policy", headers={
+        "Authorization": f"JWT {admin}"
+    }, json={
+        "name": "Office",
+        "allowed_networks": ["not-a-network"],
+    })
+    assert res_invalid_policy.status_code == 400
+
+    res_office_policy = requests.post(f"{backend_host}/checkin_policy", headers={
+        "Authorization": f"JWT {admin}"
+    }, json={
+        "name": "Office",
+        "attendance_period_id": attendance_id,
+        "allowed_networks": ["10.1.0.0/16"],
+    })
+    assert res_office_policy.status_code == 201
+
+    # Anywhere within 200 meters of Monas
+    res_site_policy = requests.post(f"{backend_host}/checkin_policy", headers={
+        "Authorization": f"JWT {admin}"
+    }, json={
+        "name": "Site",
+        "geofence": { "latitude": -6.175392, "longitude": 106.827153, "radius_meters": 200 },
+    })
+    assert res_site_policy.status_code == 201
+
+    res_outside_network = requests.post(f"{backend_host}/attendance/{attendance_id}", headers={
+        "Authorization": f"JWT {office_employee}",
+        "X-Forwarded-For": "203.0.113.7",
+    })
+    assert res_outside_network.status_code == 403
+    assert res_outside_network.text == "check-in is not allowed from this network"
+
+    res_office = requests.post(f"{backend_host}/attendance/{attendance_id}", headers={
+        "Authorization": f"JWT {office_employee}",
+        "X-Forwarded-For": "10.1.2.3",
+    })
+    assert res_office.status_code == 201
+    assert res_office.json()["client_ip"] == "10.1.2.3"
+
+    # Bundaran HI is about 2 km away from Monas
+    res_outside_geofence = requests.post(f"{backend_host}/attendance/{attendance_id}", headers={
+        "Authorization": f"JWT {field_employee}",
+        "X-Forwarded-For": "203.0.113.8",
+    }, json={
+        "latitude": -6.194927,
+        "longitude": 106.823023,
+    })
+    assert res_outside_geofence.status_code == 403
+
+    res_site = requests.post(f"{backend_host}/attendance/{attendance_id}", headers={
+        "Authorization": f"JWT {field_employee}",
+        "X-Forwarded-For": "203.0.113.8",
+    }, json={
+        "latitude": -6.1755,
+        "longitude": 106.8272,
+    })
+    assert res_site.status_code == 201
+    assert res_site.json()["latitude"] == -6.1755
+    assert res_site.json()["longitude"] == 106.8272
+
+    res_rejections_by_employee = requests.get(f"{backend_host}/attendance/{attendance_id}/checkin_rejections", headers={
+        "Authorization": f"JWT {office_employee}"
+    })
+    assert res_rejections_by_employee.status_code == 403
+
+    res_rejections = requests.get(f"{backend_host}/attendance/{attendance_id}/checkin_rejections", headers={
+        "Authorization": f"JWT {admin}"
+    })
+    assert res_rejections.status_code == 200
+    assert len(res_rejections.json()) == 2
+    assert res_rejections.json()[0]["client_ip"] == "203.0.113.7"
+    assert res_rejections.json()[1]["client_ip"] == "203.0.113.8"
+
+    stop_containers(containers)
+
+def test_attendance_correction(tmp_path):
+    # Test on Wednesday 5th, June 2024 10 AM
+    test_db_url, backend_host, faketime, containers = spin_environment(datetime(2024, 6, 5, 10, 0, 0), tmp_path)
+    pg_conn = psycopg2.connect(test_db_url)
+
+    employee = create_and_get_random_user(pg_conn, backend_host, faketime, "employee", 5000000)
+    admin = create_and_get_random_user(pg_conn, backend_host, faketime, "admin", 0)
+
+    res_created = requests.post(f"{backend_host}/attendance", headers={
+        "Authorization": f"JWT {admin}"
+    }, json={
+        "start_at": datetime(2024, 6, 3, 0, 0).replace(tzinfo=pytz.UTC).isoformat(),
+        "end_at": datetime(2024, 6, 7, 0, 0).replace(tzinfo=pytz.UTC).isoformat(),
+    })
+    assert res_created.status_code == 201
+
+    attendance_id = res_created.json()["id"]
+
+    res_attended = requests.post(f"{backend_host}/attendance/{attendance_id}", headers={
+        "Authorization": f"JWT {employee}"
+    })
+    assert res_attended.status_code == 201
+
+    res_future_correction = requests.post(f"{backend_host}/attendance/{attendance_id}/correction", headers={
+        "Authorization": f"JWT {employee}"
+    }, json={
+        "work_date": "2024-06-06",
+        "reason": "Forgot to check-in",
+    })
+    assert res_future_correction.status_code == 400
+    assert res_future_correction.text == "work_date must be in the past"
+
+    res_attended_correction = requests.post(f"{backend_host}/attendance/{attendance_id}/correction", headers={
+        "Authorization": f"JWT {employee}"
+    }, json={
+        "work_date": "2024-06-05",
+        "reason": "Forgot to check-in",
+    })
+    assert res_attended_correction.status_code == 400
+
+    # Forgot to check-in on Monday, but arrived at 9:30 AM
+    res_correction = requests.post(f"{backend_host}/attendance/{attendance_id}/correction", headers={
+        "Authorization": f"JWT {employee}"
+    }, json={
+        "work_date": "2024-06-03",
+        "checked_in_at": datetime(2024, 6, 3, 9, 30).replace(tzinfo=pytz.UTC).isoformat(),
+        "reason": "Forgot to check-in",
+    })
+    assert res_correction.status_code == 201
+    assert res_correction.json()["status"] == "Pending"
+
+    # Forgot to check-in on Tuesday too, but it wasn't true
+    res_false_correction = requests.post(f"{backend_host}/attendance/{attendance_id}/correction", headers={
+        "Authorization": f"JWT {employee}"
+    }, json={
+        "work_date": "2024-06-04",
+        "reason": "Forgot to check-in",
+    })
+    assert res_false_correction.status_code == 201
+
+    res_approve_by_employee = requests.post(f"{backend_host}/attendance/{attendance_id}/correction/{res_correction.json()['id']}/approve", headers={
+        "Authorization": f"JWT {employee}"
+    })
+    assert res_approve_by_employee.status_code == 403
+
+    res_approved = requests.post(f"{backend_host}/attendance/{attendance_id}/correction/{res_correction.json()['id']}/approve", headers={
+        "Authorization": f"JWT {admin}"
+    }, json={
+        "comment": "Confirmed with the team lead",
+    })
+    assert res_approved.status_code == 200
+    assert res_approved.json()["status"] == "Approved"
+    assert res_approved.json()["review_comment"] == "Confirmed with the team lead"
+
+    res_rejected = requests.post(f"{backend_host}/attendance/{attendance_id}/correction/{res_false_correction.json()['id']}/reject", headers={
+        "Authorization": f"JWT {admin}"
+    })
+    assert res_rejected.status_code == 200
+    assert res_rejected.json()["status"] == "Rejected"
+
+    res_approve_again = requests.post(f"{backend_host}/attendance/{attendance_id}/correction/{res_false_correction.json()['id']}/approve", headers={
+        "Authorization": f"JWT {admin}"
+    })
+    assert res_approve_again.status_code == 400
+    assert res_approve_again.text == "correction is already reviewed"
+
+    with pg_conn.cursor() as cur:
+        cur.execute("SELECT created_at, approved_by, attendance_correction_id FROM employee_attendance WHERE work_date = '2024-06-03'")
+        checked_in_at, approved_by, correction_id = cur.fetchone()
+        assert checked_in_at == datetime(2024, 6, 3, 9, 30).replace(tzinfo=pytz.UTC)
+        assert str(correction_id) == res_correction.json()["id"]
+        assert str(approved_by) == res_approved.json()["reviewed_by"]
+
+    res_process_payroll = requests.post(f"{backend_host}/attendance/{attendance_id}/process_payroll", headers={
+        "Authorization": f"JWT {admin}"
+    })
+    assert res_process_payroll.status_code == 200
+
+    res_payslip = requests.get(f"{backend_host}/attendance/{attendance_id}/payslip", headers={
+        "Authorization": f"JWT {employee}"
+    })
+    assert res_payslip.status_code == 200
+    assert res_payslip.json()["attendance"]["total_days"] == 2
+
+    stop_containers(containers)
+
+def test_attendance_calendar(tmp_path):
+    # Test on Wednesday 5th, June 2024 10 AM
+    test_db_url, backend_host, faketime, containers = spin_environment(datetime(2024, 6, 5, 10, 0, 0), tmp_path)
+    pg_conn = psycopg2.connect(test_db_url)
+
+    employee = create_and_get_random_user(pg_conn, backend_host, faketime, "employee", 4000000)
+    admin = create_and_get_random_user(pg_conn, backend_host, faketime, "admin", 0)
+
+    res_created = requests.post(f"{backend_host}/attendance", headers={
+        "Authorization": f"JWT {admin}"
+    }, json={
+        "start_at": datetime(2024, 6, 3, 0, 0).replace(tzinfo=pytz.UTC).isoformat(),
+        "end_at": datetime(2024, 6, 9, 0, 0).replace(tzinfo=pytz.UTC).isoformat(),
+    })
+    assert res_created.status_code == 201
+
+    attendance_id = res_created.json()["id"]
+
+    res_holiday = requests.post(f"{backend_host}/holiday", headers={
+        "Authorization": f"JWT {admin}"
+    }, json={
+        "date": "2024-06-06",
+        "name": "Company Anniversary",
+    })
+    assert res_holiday.status_code == 201
+
+    res_holiday_by_employee = requests.post(f"{backend_host}/holiday", headers={
+        "Authorization": f"JWT {employee}"
+    }, json={
+        "date": "2024-06-07",
+        "name": "Day Off",
+    })
+    assert res_holiday_by_employee.status_code == 403
+
+    res_leave = requests.post(f"{backend_host}/leave", headers={
+        "Authorization": f"JWT {employee}"
+    }, json={
+        "leave_type": "Sick",
+        "start_date": "2024-06-03",
+        "end_date": "2024-06-03",
+        "reason": "Flu",
+    })
+    assert res_leave.status_code == 201
+
+    res_approved = requests.post(f"{backend_host}/leave/{res_leave.json()['id']}/approve", headers={
+        "Authorization": f"JWT {admin}"
+    })
+    assert res_approved.status_code == 200
+
+    res_attended = requests.post(f"{backend_host}/attendance/{attendance_id}", headers={
+        "Authorization": f"JWT {employee}"
+    })
+    assert res_attended.status_code == 201
+
+    res_calendar = requests.get(f"{backend_host}/attendance/{attendance_id}/me", headers={
+        "Authorization": f"JWT {employee}"
+    })
+    assert res_calendar.status_code == 200
+
+    days = { day["date"]: day for day in res_calendar.json()["days"] }
+    assert len(days) == 7
+    assert days["2024-06-03"]["leave"] == "Sick"
+    assert days["2024-06-04"]["checked_in"] == False
+    assert days["2024-06-05"]["checked_in"] == True
+    assert days["2024-06-06"]["working_day"] == False
+    assert days["2024-06-06"]["holiday"] == "Company Anniversary"
+    assert days["2024-06-08"]["working_day"] == False
+
+    summary = res_calendar.json()["summary"]
+    assert summary["working_days"] == 4
+    assert summary["attended_days"] == 1
+    assert summary["paid_leave_days"] == 1
+    # Tuesday is missing, Friday hasn't come yet
+    assert summary["missing_days"] == 1
+
+    # Would leave the check-in paid on top of one working day less
+    res_attended_holiday = requests.post(f"{backend_host}/holiday", headers={
+        "Authorization": f"JWT {admin}"
+    }, json={
+        "date": "2024-06-05",
+        "name": "Sudden Holiday",
+    })
+    assert res_attended_holiday.status_code == 400
+    assert res_attended_holiday.text == "employees have already checked-in on date"
+
+    res_holidays = requests.get(f"{backend_host}/holiday?year=2024", headers={
+        "Authorization": f"JWT {employee}"
+    })
+    assert res_holidays.status_code == 200
+    assert [holiday["date"] for holiday in res_holidays.json()] == ["2024-06-06"]
+
+    assert requests.get(f"{backend_host}/holiday?year=2147483647", headers={
+        "Authorization": f"JWT {employee}"
+    }).status_code == 400
+
+    res_process_payroll = requests.post(f"{backend_host}/attendance/{attendance_id}/process_payroll", headers={
+        "Authorization": f"JWT {admin}"
+    })
+    assert res_process_payroll.status_code == 200
+
+    res_payslip = requests.get(f"{backend_host}/attendance/{attendance_id}/payslip", headers={
+        "Authorization": f"JWT {employee}"
+    })
+    assert res_payslip.status_code == 200
+    assert res_payslip.json()["attendance"]["prorated_amount"] == (4000000 * 2) // summary["working_days"]
+
+    stop_containers(containers)
+
+def test_attendance_dashboard(tmp_path):
+    # Test on Wednesday 5th, June 2024 10 AM
+    test_db_url, backend_host, faketime, containers = spin_environment(datetime(2024, 6, 5, 10, 0, 0), tmp_path)
+    pg_conn = psycopg2.connect(test_db_url)
+
+    employee_present = create_and_get_random_user(pg_conn, backend_host, faketime, "employee", 4000000)
+    employee_on_leave = create_and_get_random_user(pg_conn, backend_host, faketime, "employee", 4000000)
+    admin = create_and_get_random_user(pg_conn, backend_host, faketime, "admin", 0)
+
+    res_created = requests.post(f"{backend_host}/attendance", headers={
+        "Authorization": f"JWT {admin}"
+    }, json={
+        "start_at": datetime(2024, 6, 3, 0, 0).replace(tzinfo=pytz.UTC).isoformat(),
+        "end_at": datetime(2024, 6, 7, 0, 0).replace(tzinfo=pytz.UTC).isoformat(),
+    })
+    assert res_created.status_code == 201
+
+    attendance_id = res_created.json()["id"]
+
+    res_attended = requests.post(f"{backend_host}/attendance/{attendance_id}", headers={
+        "Authorization": f"JWT {employee_present}"
+    })
+    assert res_attended.status_code == 201
+    employee_present_id = res_attended.json()["created_by"]
+
+    res_leave = requests.post(f"{backend_host}/leave", headers={
+        "Authorization": f"JWT {employee_on_leave}"
+    }, json={
+        "leave_type": "Unpaid",
+        "start_date": "2024-06-03",
+        "end_date": "2024-06-03",
+        "reason": "Moving house",
+    })
+    assert res_leave.status_code == 201
+    employee_on_leave_id = res_leave.json()["created_by"]
+
+    res_approved = requests.post(f"{backend_host}/leave/{res_leave.json()['id']}/approve", headers={
+        "Authorization": f"JWT {admin}"
+    })
+    assert res_approved.status_code == 200
+
+    res_dashboard_by_employee = requests.get(f"{backend_host}/attendance/{attendance_id}/dashboard", headers={
+        "Authorization": f"JWT {employee_present}"
+    })
+    assert res_dashboard_by_employee.status_code == 403
+
+    res_dashboard = requests.get(f"{backend_host}/attendance/{attendance_id}/dashboard", headers={
+        "Authorization": f"JWT {admin}"
+    })
+    assert res_dashboard.status_code == 200
+    assert res_dashboard.json()["threshold"] == 0.8
+
+    days = { day["date"]: day for day in res_dashboard.json()["days"] }
+    assert days["2024-06-03"]["on_leave"] == 1
+    assert days["2024-06-05"]["checked_in"] == 1
+    assert days["2024-06-05"]["missing"] == 0
+    assert days["2024-06-04"]["missing"] == days["2024-06-04"]["expected"]
+
+    employees = { employee["id"]: employee for employee in res_dashboard.json()["employees"] }
+    assert employees[employee_present_id]["expected_days"] == 5
+    assert employees[employee_present_id]["attended_days"] == 1
+    assert employees[employee_present_id]["missing_dates"] == ["2024-06-03", "2024-06-04"]
+    assert employees[employee_present_id]["projected_ratio"] == 0.6
+    assert employees[employee_present_id]["below_threshold"] == True
+
+    # Today isn't over yet, so it isn't missing
+    assert employees[employee_on_leave_id]["unpaid_leave_days"] == 1
+    assert employees[employee_on_leave_id]["missing_dates"] == ["2024-06-04"]
+
+    res_missing = requests.get(f"{backend_host}/attendance/{attendance_id}/missing?threshold=0.5", headers={
+        "Authorization": f"JWT {admin}"
+    })
+    assert res_missing.status_code == 200
+
+    missing = { employee["id"]: employee for employee in res_missing.json() }
+    assert employee_present_id in missing
+    assert missing[employee_present_id]["below_threshold"] == False
+
+    res_invalid_threshold = requests.get(f"{backend_host}/attendance/{attendance_id}/missing?threshold=1.5", headers={
+        "Authorization": f"JWT {admin}"
+    })
+    assert res_invalid_threshold.status_code == 400
+
+    stop_containers(containers)
+
+def test_concurrent_attendance(tmp_path):
+    # Test on Monday 3rd, June 2024 6 PM (After work hour)
+    test_db_url, backend_host, faketime, containers = spin_environment(datetime(2024, 6, 3, 18, 0, 0), tmp_path)
+    pg_conn = psycopg2.connect(test_db_url)
+
+    employee = create_and_get_random_user(pg_conn, backend_host, faketime, "employee", 5000000)
+    admin = create_and_get_random_user(pg_conn, backend_host, faketime, "admin", 0)
+
+    res_created = requests.post(f"{backend_host}/attendance", headers={
+        "Authorization": f"JWT {admin}"
+    }, json={
+        "start_at": datetime(2024, 6, 1, 0, 0).replace(tzinfo=pytz.UTC).isoformat(),
+        "end_at": datetime(2024, 6, 30, 0, 0).replace(tzinfo=pytz.UTC).isoformat(),
+    })
+    assert res_created.status_code == 201
+
+    attendance_id = res_created.json()["id"]
+
+    # Double-taps and retries arriving at the same time
+    with ThreadPoolExecutor(max_workers=10) as executor:
+        res_attended = list(executor.map(lambda _: requests.post(f"{backend_host}/attendance/{attendance_id}", headers={
+            "Authorization": f"JWT {employee}"
+        }), range(10)))
+
+    assert all(res.status_code in (200, 201) for res in res_attended)
+    assert [res.status_code for res in res_attended].count(201) == 1
+    assert len({ res.json()["id"] for res in res_attended }) == 1
+
+    with ThreadPoolExecutor(max_workers=10) as executor:
+        res_overtimes = list(executor.map(lambda _: requests.post(f"{backend_host}/attendance/{attendance_id}/overtime", headers={
+            "Authorization": f"JWT {employee}"
+        }, json={
+            "started_at": datetime(2024, 6, 3, 17, 0).replace(tzinfo=pytz.UTC).isoformat(),
+            "ended_at": datetime(2024, 6, 3, 18, 0).replace(tzinfo=pytz.UTC).isoformat(),
+        }), range(10)))
+
+    # The same entry is only taken once
+    assert [res.status_code for res in res_overtimes].count(201) == 1
+    assert all(res.text == "overtime overlaps with another entry" for res in res_overtimes if res.status_code == 400)
+
+    pg_curr = pg_conn.cursor()
+    pg_curr.execute("SELECT COUNT(*) FROM employee_attendance WHERE attendance_period_id = %s", (attendance_id,))
+    assert pg_curr.fetchone()[0] == 1
+    pg_curr.execute("SELECT COUNT(*), SUM(minutes) FROM employee_overtime WHERE attendance_period_id = %s", (attendance_id,))
+    assert pg_curr.fetchone() == (1, 60)
+    pg_curr.close()
+
+    stop_containers(containers)
+
+def test_attendance_type(tmp_path):
+    # Test on Wednesday 12th, June 2024 10 AM
+    test_db_url, backend_host, faketime, containers = spin_environment(datetime(2024, 6, 12, 10, 0, 0), tmp_path)
+    pg_conn = psycopg2.connect(test_db_url)
+
+    employee_half_day = create_and_get_random_user(pg_conn, backend_host, faketime, "employee", 4000000)
+    employee_remote = create_and_get_random_user(pg_conn, backend_host, faketime, "employee", 4000000)
+    employee_remote_often = create_and_get_random_user(pg_conn, backend_host, faketime, "employee", 4000000)
+    admin = create_and_get_random_user(pg_conn, backend_host, faketime, "admin", 0)
+
+    res_created = requests.post(f"{backend_host}/attendance", headers={
+        "Authorization": f"JWT {admin}"
+    }, json={
+        "start_at": datetime(2024, 6, 1, 0, 0).replace(tzinfo=pytz.UTC).isoformat(),
+        "end_at": datetime(2024, 6, 30, 0, 0).replace(tzinfo=pytz.UTC).isoformat(),
+    })
+    assert res_created.status_code == 201
+
+    attendance_id = res_created.json()["id"]
+
+    res_policy = requests.post(f"{backend_host}/checkin_policy", headers={
+        "Authorization": f"JWT {admin}"
+    }, json={
+        "name": "Office network",
+        "allowed_networks": ["10.10.0.0/16"],
+    })
+    assert res_policy.status_code == 201
+
+    res_half_day_outside_office = requests.post(f"{backend_host}/attendance/{attendance_id}", headers={
+        "Authorization": f"JWT {employee_half_day}"
+    }, json={
+        "attendance_type": "HalfDay",
+    })
+    assert res_half_day_outside_office.status_code == 403
+
+    # Working remotely doesn't need to be at the office
+    res_remote = requests.post(f"{backend_host}/attendance/{attendance_id}", headers={
+        "Authorization": f"JWT {employee_remote}"
+    }, json={
+        "attendance_type": "Remote",
+    })
+    assert res_remote.status_code == 201
+    assert res_remote.json()["attendance_type"] == "Remote"
+
+    res_deleted = requests.delete(f"{backend_host}/checkin_policy/{res_policy.json()['id']}", headers={
+        "Authorization": f"JWT {admin}"
+    })
+    assert res_deleted.status_code == 204
+
+    res_half_day = requests.post(f"{backend_host}/attendance/{attendance_id}", headers={
+        "Authorization": f"JWT {employee_half_day}"
+    }, json={
+        "attendance_type": "HalfDay",
+    })
+    assert res_half_day.status_code == 201
+    assert res_half_day.json()["attendance_type"] == "HalfDay"
+
+    res_office = requests.post(f"{backend_host}/attendance/{attendance_id}", headers={
+        "Authorization": f"JWT {employee_remote_often}"
+    })
+    assert res_office.status_code == 201
+    assert res_office.json()["attendance_type"] == "Office"
+    employee_remote_often_id = res_office.json()["created_by"]
+
+    # Every other day of the period so far was spent working remotely
+    pg_curr = pg_conn.cursor()
+    for day in [1, 3, 4, 5, 6, 7, 10, 11]:
+        pg_curr.execute("INSERT INTO employee_attendance (created_at, updated_at, created_by, attendance_period_id, work_date, attendance_type) VALUES (%s, %s, %s, %s, %s, 'remote')", (
+            datetime(2024, 6, day, 9, 0), datetime(2024, 6, day, 9, 0), employee_remote_often_id, attendance_id, datetime(2024, 6, day).date()
+        ))
+    pg_conn.commit()
+    pg_curr.close()
+
+    res_remote_too_many = requests.post(f"{backend_host}/attendance/{attendance_id}", headers={
+        "Authorization": f"JWT {employee_remote_often}"
+    }, json={
+        "attendance_type": "Remote",
+    })
+    assert res_remote_too_many.status_code == 400
+    assert res_remote_too_many.text == "you cannot take more than 8 remote days in this period"
+
+    # Business trips skip the check-in policies as well, so they're capped too
+    employee_remote_id = res_remote.json()["created_by"]
+    pg_curr = pg_conn.cursor()
+    for day in [3, 4, 5, 6, 7]:
+        pg_curr.execute("INSERT INTO employee_attendance (created_at, updated_at, created_by, attendance_period_id, work_date, attendance_type) VALUES (%s, %s, %s, %s, %s, 'business_trip')", (
+            datetime(2024, 6, day, 9, 0), datetime(2024, 6, day, 9, 0), employee_remote_id, attendance_id, datetime(2024, 6, day).date()
+        ))
+    pg_conn.commit()
+    pg_curr.close()
+
+    res_business_trip_too_many = requests.post(f"{backend_host}/attendance/{attendance_id}", headers={
+        "Authorization": f"JWT {employee_remote}"
+    }, json={
+        "attendance_type": "BusinessTrip",
+        "latitude": 1.3521,
+        "longitude": 103.8198,
+    })
+    assert res_business_trip_too_many.status_code == 400
+    assert res_business_trip_too_many.text == "you cannot take more than 5 business_trip days in this period"
+
+    # Capped attempts are kept along with the rejected ones
+    res_rejections = requests.get(f"{backend_host}/attendance/{attendance_id}/checkin_rejections", headers={
+        "Authorization": f"JWT {admin}"
+    })
+    assert res_rejections.status_code == 200
+    assert [r["reason"] for r in res_rejections.json()][1:] == [
+        "you cannot take more than 8 remote days in this period",
+        "you cannot take more than 5 business_trip days in this period",
+    ]
+    assert res_rejections.json()[-1]["latitude"] == 1.3521
+
+    res_process_payroll = requests.post(f"{backend_host}/attendance/{attendance_id}/process_payroll", headers={
+        "Authorization": f"JWT {admin}"
+    })
+    assert res_process_payroll.status_code == 200
+
+    res_payslip = requests.get(f"{backend_host}/attendance/{attendance_id}/payslip", headers={
+        "Authorization": f"JWT {employee_half_day}"
+    })
+    assert res_payslip.status_code == 200
+    assert res_payslip.json()["attendance"]["weighted_days"] == 0.5
+    assert res_payslip.json()["attendance"]["types"] == [{
+        "attendance_type": "HalfDay",
+        "days": 1,
+        "weight": 0.5,
+        "weighted_days": 0.5,
+    }]
+    # There are 20 working days in June 2024
+    assert res_payslip.json()["attendance"]["prorated_amount"] == 4000000 // 2 // 20
+
+    res_payslip = requests.get(f"{backend_host}/attendance/{attendance_id}/payslip", headers={
+        "Authorization": f"JWT {employee_remote_often}"
+    })
+    assert res_payslip.status_code == 200
+    assert { t["attendance_type"]: t["days"] for t in res_payslip.json()["attendance"]["types"] } == { "Office": 1, "Remote": 8 }
+    assert res_payslip.json()["attendance"]["prorated_amount"] == 4000000 * 9 // 20
+
+    stop_containers(containers)
+
+def test_overtime_approval(tmp_path):
+    # Test on Monday 3rd, June 2024 6 PM (After work hour)
+    test_db_url, backend_host, faketime, containers = spin_environment(datetime(2024, 6, 3, 18, 0, 0), tmp_path)
+    pg_conn = psycopg2.connect(test_db_url)
+
+    employee_1 = create_and_get_random_user(pg_conn, backend_host, faketime, "employee", 5000000)
+    employee_2 = create_and_get_random_user(pg_conn, backend_host, faketime, "employee", 5000000)
+    admin = create_and_get_random_user(pg_conn, backend_host, faketime, "admin", 0)
+
+    res_created = requests.post(f"{backend_host}/attendance", headers={
+        "Authorization": f"JWT {admin}"
+    }, json={
+        "start_at": datetime(2024, 6, 3, 0, 0).replace(tzinfo=pytz.UTC).isoformat(),
+        "end_at": datetime(2024, 6, 5, 0, 0).replace(tzinfo=pytz.UTC).isoformat(),
+    })
+    assert res_created.status_code == 201
+
+    attendance_id = res_created.json()["id"]
+
+    overtime_ids = []
+    for employee in [employee_1, employee_2]:
+        res_attended = requests.post(f"{backend_host}/attendance/{attendance_id}", headers={
+            "Authorization": f"JWT {employee}"
+        })
+        assert res_attended.status_code == 201
+
+        res_overtime = requests.post(f"{backend_host}/attendance/{attendance_id}/overtime", headers={
+            "Authorization": f"JWT {employee}"
+        }, json={
+            "started_at": datetime(2024, 6, 3, 17, 0).replace(tzinfo=pytz.UTC).isoformat(),
+            "ended_at": datetime(2024, 6, 3, 17, 45).replace(tzinfo=pytz.UTC).isoformat(),
+        })
+        assert res_overtime.status_code == 201
+        assert res_overtime.json()["status"] == "Pending"
+        overtime_ids.append(res_overtime.json()["id"])
+
+    res_pending = requests.get(f"{backend_host}/attendance/{attendance_id}/overtime?status=Pending", headers={
+        "Authorization": f"JWT {admin}"
+    })
+    assert res_pending.status_code == 200
+    assert sorted(overtime["id"] for overtime in res_pending.json()) == sorted(overtime_ids)
+
+    res_approved_by_employee = requests.post(f"{backend_host}/attendance/{attendance_id}/overtime/{overtime_ids[0]}/approve", headers={
+        "Authorization": f"JWT {employee_1}"
+    })
+    assert res_approved_by_employee.status_code == 403
+
+    res_process_payroll_pending = requests.post(f"{backend_host}/attendance/{attendance_id}/process_payroll", headers={
+        "Authorization": f"JWT {admin}"
+    })
+    assert res_process_payroll_pending.status_code == 400
+
+    res_approved = requests.post(f"{backend_host}/attendance/{attendance_id}/overtime/approve", headers={
+        "Authorization": f"JWT {admin}"
+    }, json={
+        "overtime_ids": overtime_ids,
+        "comment": "Release crunch",
+    })
+    assert res_approved.status_code == 200
+    assert all(overtime["status"] == "Approved" for overtime in res_approved.json())
+    assert all(overtime["review_comment"] == "Release crunch" for overtime in res_approved.json())
+
+    res_approved_again = requests.post(f"{backend_host}/attendance/{attendance_id}/overtime/{overtime_ids[0]}/approve", headers={
+        "Authorization": f"JWT {admin}"
+    })
+    assert res_approved_again.status_code == 400
+    assert res_approved_again.text == "overtime is already reviewed"
+
+    # Editing an entry needs another sign-off
+    res_overtime_more = requests.put(f"{backend_host}/attendance/{attendance_id}/overtime/{overtime_ids[1]}", headers={
+        "Authorization": f"JWT {employee_2}"
+    }, json={
+        "started_at": datetime(2024, 6, 3, 17, 0).replace(tzinfo=pytz.UTC).isoformat(),
+        "ended_at": datetime(2024, 6, 3, 18, 0).replace(tzinfo=pytz.UTC).isoformat(),
+    })
+    assert res_overtime_more.status_code == 200
+    assert res_overtime_more.json()["status"] == "Pending"
+    assert res_overtime_more.json()["minutes"] == 60
+
+    res_rejected = requests.post(f"{backend_host}/attendance/{attendance_id}/overtime/{overtime_ids[1]}/reject", headers={
+        "Authorization": f"JWT {admin}"
+    }, json={
+        "comment": "Not requested by the team lead",
+    })
+    assert res_rejected.status_code == 200
+    assert res_rejected.json()["status"] == "Rejected"
+
+    res_process_payroll = requests.post(f"{backend_host}/attendance/{attendance_id}/process_payroll", headers={
+        "Authorization": f"JWT {admin}"
+    })
+    assert res_process_payroll.status_code == 200
+
+    res_payslip_e_1 = requests.get(f"{backend_host}/attendance/{attendance_id}/payslip", headers={
+        "Authorization": f"JWT {employee_1}"
+    })
+    assert res_payslip_e_1.status_code == 200
+    assert len(res_payslip_e_1.json()["overtimes"]) == 1
+    assert res_payslip_e_1.json()["summary"]["overtime_total"] > 0
+
+    res_payslip_e_2 = requests.get(f"{backend_host}/attendance/{attendance_id}/payslip", headers={
+        "Authorization": f"JWT {employee_2}"
+    })
+    assert res_payslip_e_2.status_code == 200
+    assert res_payslip_e_2.json()["overtimes"] == []
+    assert res_payslip_e_2.json()["summary"]["overtime_total"] == 0
+
+    stop_containers(containers)
+
+def test_process_payroll_with_pending_overtime(tmp_path):
+    # Test on Monday 3rd, June 2024 6 PM (After work hour)
+    test_db_url, backend_host, faketime, containers = spin_environment(datetime(2024, 6, 3, 18, 0, 0), tmp_path)
+    pg_conn = psycopg2.connect(test_db_url)
+
+    employee = create_and_get_random_user(pg_conn, backend_host, faketime, "employee", 5000000)
+    admin = create_and_get_random_user(pg_conn, backend_host, faketime, "admin", 0)
+
+    res_created = requests.post(f"{backend_host}/attendance", headers={
+        "Authorization": f"JWT {admin}"
+    }, json={
+        "start_at": datetime(2024, 6, 3, 0, 0).replace(tzinfo=pytz.UTC).isoformat(),
+        "end_at": datetime(2024, 6, 5, 0, 0).replace(tzinfo=pytz.UTC).isoformat(),
+    })
+    assert res_created.status_code == 201
+
+    attendance_id = res_created.json()["id"]
+
+    res_attended = requests.post(f"{backend_host}/attendance/{attendance_id}", headers={
+        "Authorization": f"JWT {employee}"
+    })
+    assert res_attended.status_code == 201
+
+    res_overtime = requests.post(f"{backend_host}/attendance/{attendance_id}/overtime", headers={
+        "Authorization": f"JWT {employee}"
+    }, json={
+        "started_at": datetime(2024, 6, 3, 17, 0).replace(tzinfo=pytz.UTC).isoformat(),
+        "ended_at": datetime(2024, 6, 3, 18, 0).replace(tzinfo=pytz.UTC).isoformat(),
+    })
+    assert res_overtime.status_code == 201
+
+    res_process_payroll = requests.post(f"{backend_host}/attendance/{attendance_id}/process_payroll?force=true", headers={
+        "Authorization": f"JWT {admin}"
+    })
+    assert res_process_payroll.status_code == 200
+
+    res_payslip = requests.get(f"{backend_host}/attendance/{attendance_id}/payslip", headers={
+        "Authorization": f"JWT {employee}"
+    })
+    assert res_payslip.status_code == 200
+    assert res_payslip.json()["summary"]["overtime_total"] == 0
+
+    stop_containers(containers)
+
+def test_statutory_overtime(tmp_path):
+    # Test on Monday 3rd, June 2024 9 PM (After work hour)
+    test_db_url, backend_host, faketime, containers = spin_environment(datetime(2024, 6, 3, 21, 0, 0), tmp_path, { "OVERTIME_RATE_POLICY": "statutory" })
+    pg_conn = psycopg2.connect(test_db_url)
+
+    # 30k per hour
+    employee = create_and_get_random_user(pg_conn, backend_host, faketime, "employee", 5190000)
+    admin = create_and_get_random_user(pg_conn, backend_host, faketime, "admin", 0)
+
+    res_created = requests.post(f"{backend_host}/attendance", headers={
+        "Authorization": f"JWT {admin}"
+    }, json={
+        "start_at": datetime(2024, 6, 3, 0, 0).replace(tzinfo=pytz.UTC).isoformat(),
+        "end_at": datetime(2024, 6, 5, 0, 0).replace(tzinfo=pytz.UTC).isoformat(),
+    })
+    assert res_created.status_code == 201
+
+    attendance_id = res_created.json()["id"]
+
+    res_attended = requests.post(f"{backend_host}/attendance/{attendance_id}", headers={
+        "Authorization": f"JWT {employee}"
+    })
+    assert res_attended.status_code == 201
+
+    # The day's entries are paid together, so only the first hour of the day is paid less
+    overtime_ids = []
+    for started_at, ended_at in [(datetime(2024, 6, 3, 17, 0), datetime(2024, 6, 3, 17, 30)), (datetime(2024, 6, 3, 18, 0), datetime(2024, 6, 3, 20, 30))]:
+        res_overtime = requests.post(f"{backend_host}/attendance/{attendance_id}/overtime", headers={
+            "Authorization": f"JWT {employee}"
+        }, json={
+            "started_at": started_at.replace(tzinfo=pytz.UTC).isoformat(),
+            "ended_at": ended_at.replace(tzinfo=pytz.UTC).isoformat(),
+        })
+        assert res_overtime.status_code == 201
+        overtime_ids.append(res_overtime.json()["id"])
+
+    res_approved = requests.post(f"{backend_host}/attendance/{attendance_id}/overtime/approve", headers={
+        "Authorization": f"JWT {admin}"
+    }, json={
+        "overtime_ids": overtime_ids,
+    })
+    assert res_approved.status_code == 200
+
+    res_process_payroll = requests.post(f"{backend_host}/attendance/{attendance_id}/process_payroll", headers={
+        "Authorization": f"JWT {admin}"
+    })
+    assert res_process_payroll.status_code == 200
+
+    res_payslip = requests.get(f"{backend_host}/attendance/{attendance_id}/payslip", headers={
+        "Authorization": f"JWT {employee}"
+    })
+    assert res_payslip.status_code == 200
+
+    # First hour at 1.5x, the rest at 2x
+    overtime = res_payslip.json()["overtimes"][0]
+    assert overtime["day"] == "Workday"
+    assert overtime["minutes"] == 180
+    assert len(overtime["entries"]) == 2
+    assert [(tier["hours"], tier["multiplier"], tier["amount"]) for tier in overtime["tiers"]] == [
+        (1, 1.5, 45000),
+        (2, 2, 120000),
+    ]
+    assert overtime["amount"] == 165000
+    assert res_payslip.json()["summary"]["overtime_total"] == 165000
+
+    stop_containers(containers)
+
+def test_non_working_day_overtime(tmp_path):
+    # Test on Saturday 8th, June 2024 2 PM
+    test_db_url, backend_host, faketime, containers = spin_environment(datetime(2024, 6, 8, 14, 0, 0), tmp_path, { "NON_WORKING_DAY_OVERTIME_PREAPPROVAL": "true" })
+    pg_conn = psycopg2.connect(test_db_url)
+
+    employee = create_and_get_random_user(pg_conn, backend_host, faketime, "employee", 5000000)
+    admin = create_and_get_random_user(pg_conn, backend_host, faketime, "admin", 0)
+
+    res_created = requests.post(f"{backend_host}/attendance", headers={
+        "Authorization": f"JWT {admin}"
+    }, json={
+        "start_at": datetime(2024, 6, 1, 0, 0).replace(tzinfo=pytz.UTC).isoformat(),
+        "end_at": datetime(2024, 6, 30, 0, 0).replace(tzinfo=pytz.UTC).isoformat(),
+    })
+    assert res_created.status_code == 201
+
+    attendance_id = res_created.json()["id"]
+
+    res_attended = requests.post(f"{backend_host}/attendance/{attendance_id}", headers={
+        "Authorization": f"JWT {employee}"
+    })
+    assert res_attended.status_code == 400
+
+    # No check-in is needed, but the day must be pre-approved
+    res_overtime_not_preapproved = requests.post(f"{backend_host}/attendance/{attendance_id}/overtime", headers={
+        "Authorization": f"JWT {employee}"
+    }, json={
+        "started_at": datetime(2024, 6, 8, 10, 0).replace(tzinfo=pytz.UTC).isoformat(),
+        "ended_at": datetime(2024, 6, 8, 14, 0).replace(tzinfo=pytz.UTC).isoformat(),
+    })
+    assert res_overtime_not_preapproved.status_code == 400
+    assert res_overtime_not_preapproved.text == "your overtime on that day has not been pre-approved"
+
+    res_preapproval_on_workday = requests.post(f"{backend_host}/attendance/{attendance_id}/overtime/preapproval", headers={
+        "Authorization": f"JWT {employee}"
+    }, json={
+        "work_date": "2024-06-10",
+        "reason": "Release",
+    })
+    assert res_preapproval_on_workday.status_code == 400
+
+    res_preapproval = requests.post(f"{backend_host}/attendance/{attendance_id}/overtime/preapproval", headers={
+        "Authorization": f"JWT {employee}"
+    }, json={
+        "work_date": "2024-06-08",
+        "reason": "Release",
+    })
+    assert res_preapproval.status_code == 201
+    assert res_preapproval.json()["status"] == "Pending"
+
+    res_preapproved = requests.post(f"{backend_host}/attendance/{attendance_id}/overtime/preapproval/{res_preapproval.json()['id']}/approve", headers={
+        "Authorization": f"JWT {admin}"
+    })
+    assert res_preapproved.status_code == 200
+    assert res_preapproved.json()["status"] == "Approved"
+
+    res_overtime_over_limit = requests.post(f"{backend_host}/attendance/{attendance_id}/overtime", headers={
+        "Authorization": f"JWT {employee}"
+    }, json={
+        "started_at": datetime(2024, 6, 8, 5, 0).replace(tzinfo=pytz.UTC).isoformat(),
+        "ended_at": datetime(2024, 6, 8, 14, 0).replace(tzinfo=pytz.UTC).isoformat(),
+    })
+    assert res_overtime_over_limit.status_code == 400
+    assert res_overtime_over_limit.text == "you cannot take overtime for more than 8 hours a day, 8 hours remaining"
+
+    res_overtime = requests.post(f"{backend_host}/attendance/{attendance_id}/overtime", headers={
+        "Authorization": f"JWT {employee}"
+    }, json={
+        "started_at": datetime(2024, 6, 8, 6, 0).replace(tzinfo=pytz.UTC).isoformat(),
+        "ended_at": datetime(2024, 6, 8, 14, 0).replace(tzinfo=pytz.UTC).isoformat(),
+    })
+    assert res_overtime.status_code == 201
+
+    res_approved = requests.post(f"{backend_host}/attendance/{attendance_id}/overtime/{res_overtime.json()['id']}/approve", headers={
+        "Authorization": f"JWT {admin}"
+    })
+    assert res_approved.status_code == 200
+
+    res_process_payroll = requests.post(f"{backend_host}/attendance/{attendance_id}/process_payroll", headers={
+        "Authorization": f"JWT {admin}"
+    })
+    assert res_process_payroll.status_code == 200
+
+    res_payslip = requests.get(f"{backend_host}/attendance/{attendance_id}/payslip", headers={
+        "Authorization": f"JWT {employee}"
+    })
+    assert res_payslip.status_code == 200
+
+    # Rest days are paid thrice the hourly wage
+    overtime = res_payslip.json()["overtimes"][0]
+    assert overtime["day"] == "RestDay"
+    assert [(tier["hours"], tier["multiplier"]) for tier in overtime["tiers"]] == [(8, 3)]
+
+    stop_containers(containers)
+
+def test_overtime_limits(tmp_path):
+    # Test on Monday 3rd, June 2024 9 PM (After work hour)
+    test_db_url, backend_host, faketime, containers = spin_environment(datetime(2024, 6, 3, 21, 0, 0), tmp_path, {
+        "OVERTIME_WEEKLY_MAX_MINUTES": "120",
+        "OVERTIME_PERIOD_MAX_MINUTES": "600",
+    })
+    pg_conn = psycopg2.connect(test_db_url)
+
+    employee = create_and_get_random_user(pg_conn, backend_host, faketime, "employee", 5000000)
+    admin = create_and_get_random_user(pg_conn, backend_host, faketime, "admin", 0)
+
+    res_created = requests.post(f"{backend_host}/attendance", headers={
+        "Authorization": f"JWT {admin}"
+    }, json={
+        "start_at": datetime(2024, 6, 1, 0, 0).replace(tzinfo=pytz.UTC).isoformat(),
+        "end_at": datetime(2024, 6, 30, 0, 0).replace(tzinfo=pytz.UTC).isoformat(),
+    })
+    assert res_created.status_code == 201
+
+    attendance_id = res_created.json()["id"]
+
+    res_attended = requests.post(f"{backend_host}/attendance/{attendance_id}", headers={
+        "Authorization": f"JWT {employee}"
+    })
+    assert res_attended.status_code == 201
+
+    res_overtime = requests.post(f"{backend_host}/attendance/{attendance_id}/overtime", headers={
+        "Authorization": f"JWT {employee}"
+    }, json={
+        "started_at": datetime(2024, 6, 3, 17, 0).replace(tzinfo=pytz.UTC).isoformat(),
+        "ended_at": datetime(2024, 6, 3, 18, 30).replace(tzinfo=pytz.UTC).isoformat(),
+    })
+    assert res_overtime.status_code == 201
+
+    # Still within the daily limit, but not the weekly one
+    res_overtime_over_week = requests.post(f"{backend_host}/attendance/{attendance_id}/overtime", headers={
+        "Authorization": f"JWT {employee}"
+    }, json={
+        "started_at": datetime(2024, 6, 3, 19, 0).replace(tzinfo=pytz.UTC).isoformat(),
+        "ended_at": datetime(2024, 6, 3, 20, 0).replace(tzinfo=pytz.UTC).isoformat(),
+    })
+    assert res_overtime_over_week.status_code == 400
+    assert res_overtime_over_week.text == "you cannot take overtime for more than 2 hours a week, 30 minutes remaining"
+
+    res_headroom = requests.get(f"{backend_host}/attendance/{attendance_id}/overtime/headroom", headers={
+        "Authorization": f"JWT {employee}"
+    })
+    assert res_headroom.status_code == 200
+    assert res_headroom.json()["work_date"] == "2024-06-03"
+    assert res_headroom.json()["remaining_minutes"] == 30
+    assert { limit["scope"]: limit["remaining_minutes"] for limit in res_headroom.json()["limits"] } == {
+        "Day": 90,
+        "Week": 30,
+        "Period": 510,
+    }
+
+    # Next week starts over
+    res_headroom_next_week = requests.get(f"{backend_host}/attendance/{attendance_id}/overtime/headroom?date=2024-06-10", headers={
+        "Authorization": f"JWT {employee}"
+    })
+    assert res_headroom_next_week.status_code == 200
+    assert res_headroom_next_week.json()["remaining_minutes"] == 120
+
+    # Overtime on rest days isn't limited by the week
+    res_headroom_rest_day = requests.get(f"{backend_host}/attendance/{attendance_id}/overtime/headroom?date=2024-06-08", headers={
+        "Authorization": f"JWT {employee}"
+    })
+    assert res_headroom_rest_day.status_code == 200
+    assert { limit["scope"]: limit["remaining_minutes"] for limit in res_headroom_rest_day.json()["limits"] } == {
+        "Day": 480,
+        "Period": 510,
+    }
+
+    stop_containers(containers)
+
+PNG_RECEIPT = b"\x89PNG\r\n\x1a\n" + b"\x00" * 64
+PDF_RECEIPT = b"%PDF-1.7\n" + b"\x00" * 64
+
+def test_reimbursement_receipts(tmp_path):
+    test_db_url, backend_host, faketime, containers = spin_environment(datetime(2024, 6, 3, 10, 0, 0), tmp_path, {
+        "RECEIPT_MAX_BYTES": "1024",
+        "RECEIPT_MAX_FILES": "2",
+        "RECEIPT_MAX_TOTAL_BYTES": "1536",
+    })
+    pg_conn = psycopg2.connect(test_db_url)
+
+    employee = create_and_get_random_user(pg_conn, backend_host, faketime, "employee", 5000000)
+    other_employee = create_and_get_random_user(pg_conn, backend_host, faketime, "employee", 5000000)
+    admin = create_and_get_random_user(pg_conn, backend_host, faketime, "admin", 0)
+
+    res_created = requests.post(f"{backend_host}/attendance", headers={
+        "Authorization": f"JWT {admin}"
+    }, json={
+        "start_at": datetime(2024, 6, 1, 0, 0).replace(tzinfo=pytz.UTC).isoformat(),
+        "end_at": datetime(2024, 6, 30, 0, 0).replace(tzinfo=pytz.UTC).isoformat(),
+    })
+    assert res_created.status_code == 201
+
+    attendance_id = res_created.json()["id"]
+
+    res_reimburse = requests.post(f"{backend_host}/attendance/{attendance_id}/reimburse", headers={
+        "Authorization": f"JWT {employee}"
+    }, json={
+        "category_id": get_reimbursement_category(backend_host, employee, "Transport"),
+        "description": "Taxi to the client",
+        "amount": 150000,
+    })
+    assert res_reimburse.status_code == 201
+
+    reimbursement_id = res_reimburse.json()["id"]
+
+    # What the client claims the file is doesn't matter
+    res_uploaded = requests.post(f"{backend_host}/attendance/{attendance_id}/reimburse/{reimbursement_id}/receipt", headers={
+        "Authorization": f"JWT {employee}"
+    }, files=[
+        ("file", ("taxi.png", PNG_RECEIPT, "application/octet-stream")),
+        ("file", ("invoice.pdf", PDF_RECEIPT, "image/png")),
+    ])
+    assert res_uploaded.status_code == 201
+    assert [(r["file_name"], r["content_type"], r["size_bytes"]) for r in res_uploaded.json()] == [
+        ("taxi.png", "image/png", len(PNG_RECEIPT)),
+        ("invoice.pdf", "application/pdf", len(PDF_RECEIPT)),
+    ]
+    assert res_uploaded.json()[0]["sha256"] == hashlib.sha256(PNG_RECEIPT).hexdigest()
+
+    # Uploading the same file again gives back the attached one
+    res_uploaded_again = requests.post(f"{backend_host}/attendance/{attendance_id}/reimburse/{reimbursement_id}/receipt", headers={
+        "Authorization": f"JWT {employee}"
+    }, files=[
+        ("file", ("taxi-copy.png", PNG_RECEIPT, "image/png")),
+    ])
+    assert res_uploaded_again.status_code == 201
+    assert res_uploaded_again.json()[0]["id"] == res_uploaded.json()[0]["id"]
+
+    res_not_receipt = requests.post(f"{backend_host}/attendance/{attendance_id}/reimburse/{reimbursement_id}/receipt", headers={
+        "Authorization": f"JWT {employee}"
+    }, files=[
+        ("file", ("receipt.png", b"<html><script>alert(1)</script></html>", "image/png")),
+    ])
+    assert res_not_receipt.status_code == 415
+    assert res_not_receipt.text == "receipt must be a JPEG, PNG or WebP image, or a PDF"
+
+    res_too_large = requests.post(f"{backend_host}/attendance/{attendance_id}/reimburse/{reimbursement_id}/receipt", headers={
+        "Authorization": f"JWT {employee}"
+    }, files=[
+        ("file", ("scan.pdf", b"%PDF-1.7\n" + b"\x00" * 2048, "application/pdf")),
+    ])
+    assert res_too_large.status_code == 413
+    assert res_too_large.text == "receipt must not be larger than 1 KiB"
+
+    res_too_many = requests.post(f"{backend_host}/attendance/{attendance_id}/reimburse/{reimbursement_id}/receipt", headers={
+        "Authorization": f"JWT {employee}"
+    }, files=[
+        ("file", ("taxi.png", PNG_RECEIPT, "image/png")),
+        ("file", ("invoice.pdf", PDF_RECEIPT, "application/pdf")),
+        ("file", ("parking.png", PNG_RECEIPT + b"\x01", "image/png")),
+    ])
+    assert res_too_many.status_code == 413
+    assert res_too_many.text == "only 2 receipts can be uploaded at once"
+
+    # Each is small enough, but not both together
+    res_too_large_altogether = requests.post(f"{backend_host}/attendance/{attendance_id}/reimburse/{reimbursement_id}/receipt", headers={
+        "Authorization": f"JWT {employee}"
+    }, files=[
+        ("file", ("scan-1.pdf", b"%PDF-1.7\n" + b"\x00" * 1000, "application/pdf")),
+        ("file", ("scan-2.pdf", b"%PDF-1.7\n" + b"\x01" * 1000, "application/pdf")),
+    ])
+    assert res_too_large_altogether.status_code == 413
+    assert res_too_large_altogether.text == "receipts must not be larger than 1536 bytes altogether"
+
+    # Only the claimant attaches receipts
+    res_other_upload = requests.post(f"{backend_host}/attendance/{attendance_id}/reimburse/{reimbursement_id}/receipt", headers={
+        "Authorization": f"JWT {other_employee}"
+    }, files=[
+        ("file", ("taxi.png", PNG_RECEIPT, "image/png")),
+    ])
+    assert res_other_upload.status_code == 404
+
+    res_receipts = requests.get(f"{backend_host}/attendance/{attendance_id}/reimburse/{reimbursement_id}/receipt", headers={
+        "Authorization": f"JWT {admin}"
+    })
+    assert res_receipts.status_code == 200
+    assert len(res_receipts.json()) == 2
+
+    receipt_id = res_receipts.json()[1]["id"]
+
+    for user in [employee, admin]:
+        res_download = requests.get(f"{backend_host}/attendance/{attendance_id}/reimburse/{reimbursement_id}/receipt/{receipt_id}", headers={
+            "Authorization": f"JWT {user}"
+        })
+        assert res_download.status_code == 200
+        assert res_download.headers["Content-Type"] == "application/pdf"
+        assert res_download.headers["Content-Disposition"] == 'attachment; filename="invoice.pdf"'
+        assert res_download.content == PDF_RECEIPT
+
+    res_other_download = requests.get(f"{backend_host}/attendance/{attendance_id}/reimburse/{reimbursement_id}/receipt/{receipt_id}", headers={
+        "Authorization": f"JWT {other_employee}"
+    })
+    assert res_other_download.status_code == 404
+
+    res_other_receipts = requests.get(f"{backend_host}/attendance/{attendance_id}/reimburse/{reimbursement_id}/receipt", headers={
+        "Authorization": f"JWT {other_employee}"
+    })
+    assert res_other_receipts.status_code == 404
+
+    # A reviewed claim is kept with the receipts it was reviewed with
+    res_approved = requests.post(f"{backend_host}/attendance/{attendance_id}/reimburse/{reimbursement_id}/approve", headers={
+        "Authorization": f"JWT {admin}"
+    })
+    assert res_approved.status_code == 200
+
+    res_reviewed_upload = requests.post(f"{backend_host}/attendance/{attendance_id}/reimburse/{reimbursement_id}/receipt", headers={
+        "Authorization": f"JWT {employee}"
+    }, files=[
+        ("file", ("taxi.png", PNG_RECEIPT, "image/png")),
+    ])
+    assert res_reviewed_upload.status_code == 400
+    assert res_reviewed_upload.text == "claim is already reviewed"
+
+    stop_containers(containers)
+
+def test_reimbursement_receipts_s3(tmp_path):
+    s3, s3_endpoint, minio_container = spin_minio("receipts")
+
+    # Signatures are only accepted within minutes of the storage's clock, so the time can't be faked
+    test_db_url, backend_host, faketime, containers = spin_environment(None, tmp_path, {
+        "RECEIPT_STORAGE": "s3",
+        "RECEIPT_S3_ENDPOINT": s3_endpoint,
+        "RECEIPT_S3_BUCKET": "receipts",
+        "RECEIPT_S3_ACCESS_KEY_ID": "minio",
+        "RECEIPT_S3_SECRET_ACCESS_KEY": "minio-secret",
+    })
+    containers.append(minio_container)
+    pg_conn = psycopg2.connect(test_db_url)
+
+    employee = create_and_get_random_user(pg_conn, backend_host, faketime, "employee", 5000000)
+    other_employee = create_and_get_random_user(pg_conn, backend_host, faketime, "employee", 5000000)
+    admin = create_and_get_random_user(pg_conn, backend_host, faketime, "admin", 0)
+
+    res_created = requests.post(f"{backend_host}/attendance", headers={
+        "Authorization": f"JWT {admin}"
+    }, json={
+        "start_at": (datetime.now(timezone.utc) - timedelta(days=15)).isoformat(),
+        "end_at": (datetime.now(timezone.utc) + timedelta(days=15)).isoformat(),
+    })
+    assert res_created.status_code == 201
+
+    attendance_id = res_created.json()["id"]
+
+    receipts = []
+    for user in [employee, other_employee]:
+        res_reimburse = requests.post(f"{backend_host}/attendance/{attendance_id}/reimburse", headers={
+            "Authorization": f"JWT {user}"
+        }, json={
+            "category_id": get_reimbursement_category(backend_host, user, "Meals"),
+            "description": "Team lunch",
+            "amount": 300000,
+        })
+        assert res_reimburse.status_code == 201
+
+        reimbursement_id = res_reimburse.json()["id"]
+
+        res_uploaded = requests.post(f"{backend_host}/attendance/{attendance_id}/reimburse/{reimbursement_id}/receipt", headers={
+            "Authorization": f"JWT {user}"
+        }, files=[
+            ("file", ("lunch.png", PNG_RECEIPT, "image/png")),
+        ])
+        assert res_uploaded.status_code == 201
+
+        receipts.append((user, reimbursement_id, res_uploaded.json()[0]["id"]))
+
+    # Both claims share the one stored object
+    objects = s3.list_objects_v2(Bucket="receipts")["Contents"]
+    assert [o["Key"] for o in objects] == [f"receipts/{hashlib.sha256(PNG_RECEIPT).hexdigest()}"]
+
+    for user, reimbursement_id, receipt_id in receipts:
+        res_download = requests.get(f"{backend_host}/attendance/{attendance_id}/reimburse/{reimbursement_id}/receipt/{receipt_id}", headers={
+            "Authorization": f"JWT {user}"
+        })
+        assert res_download.status_code == 200
+        assert res_download.headers["Content-Type"] == "image/png"
+        assert res_download.content == PNG_RECEIPT
+
+    stop_containers(containers)
+
+def test_reimbursement_categories(tmp_path):
+    test_db_url, backend_host, faketime, containers = spin_environment(datetime(2024, 6, 3, 10, 0, 0), tmp_path)
+    pg_conn = psycopg2.connect(test_db_url)
+
+    employee = create_and_get_random_user(pg_conn, backend_host, faketime, "employee", 5000000)
+    admin = create_and_get_random_user(pg_conn, backend_host, faketime, "admin", 0)
+
+    res_not_admin = requests.post(f"{backend_host}/reimbursement_category", headers={
+        "Authorization": f"JWT {employee}"
+    }, json={
+        "name": "Internet",
+    })
+    assert res_not_admin.status_code == 403
+
+    res_category = requests.post(f"{backend_host}/reimbursement_category", headers={
+        "Authorization": f"JWT {admin}"
+    }, json={
+        "name": "Internet",
+        "per_claim_max_amount": 300_000,
+        "period_max_amount": 500_000,
+        "yearly_max_amount": 700_000,
+        "receipt_required": True,
+    })
+    assert res_category.status_code == 201
+
+    internet = res_category.json()["id"]
+
+    res_taken = requests.post(f"{backend_host}/reimbursement_category", headers={
+        "Authorization": f"JWT {admin}"
+    }, json={
+        "name": "Meals",
+    })
+    assert res_taken.status_code == 400
+    assert res_taken.text == "category name is already taken"
+
+    attendance_ids = []
+    for (start_at, end_at) in [(datetime(2024, 5, 1, 0, 0), datetime(2024, 5, 31, 0, 0)), (datetime(2024, 6, 1, 0, 0), datetime(2024, 6, 30, 0, 0))]:
+        res_created = requests.post(f"{backend_host}/attendance", headers={
+            "Authorization": f"JWT {admin}"
+        }, json={
+            "start_at": start_at.replace(tzinfo=pytz.UTC).isoformat(),
+            "end_at": end_at.replace(tzinfo=pytz.UTC).isoformat(),
+        })
+        assert res_created.status_code == 201
+
+        attendance_ids.append(res_created.json()["id"])
+
+    [may_attendance_id, june_attendance_id] = attendance_ids
+
+    def claim(attendance_id, category_id, amount):
+        return requests.post(f"{backend_host}/attendance/{attendance_id}/reimburse", headers={
+            "Authorization": f"JWT {employee}"
+        }, json={
+            "category_id": category_id,
+            "description": "Home internet",
+            "amount": amount,
+        })
+
+    res_zero = claim(june_attendance_id, internet, 0)
+    assert res_zero.status_code == 400
+    assert res_zero.text == "amount must be more than 0"
+
+    res_over_claim = claim(june_attendance_id, internet, 300_001)
+    assert res_over_claim.status_code == 400
+    assert res_over_claim.text == "you cannot claim more than 300000 for Internet at once"
+
+    assert claim(may_attendance_id, internet, 300_000).status_code == 201
+    assert claim(may_attendance_id, internet, 150_000).status_code == 201
+
+    res_over_period = claim(may_attendance_id, internet, 100_000)
+    assert res_over_period.status_code == 400
+    assert res_over_period.text == "you cannot claim more than 500000 for Internet in this period, 50000 remaining"
+
+    # Claims of the other periods in the year count as well
+    res_over_year = claim(june_attendance_id, internet, 300_000)
+    assert res_over_year.status_code == 400
+    assert res_over_year.text == "you cannot claim more than 700000 for Internet a year, 250000 remaining"
+
+    res_internet = claim(june_attendance_id, internet, 250_000)
+    assert res_internet.status_code == 201
+
+    res_transport = claim(june_attendance_id, get_reimbursement_category(backend_host, employee, "Transport"), 20_000)
+    assert res_transport.status_code == 201
+
+    def approve(reimbursement_id):
+        return requests.post(f"{backend_host}/attendance/{june_attendance_id}/reimburse/{reimbursement_id}/approve", headers={
+            "Authorization": f"JWT {admin}"
+        })
+
+    res_no_receipt = approve(res_internet.json()["id"])
+    assert res_no_receipt.status_code == 400
+    assert res_no_receipt.text == "Internet claims require a receipt to be approved"
+
+    res_uploaded = requests.post(f"{backend_host}/attendance/{june_attendance_id}/reimburse/{res_internet.json()['id']}/receipt", headers={
+        "Authorization": f"JWT {employee}"
+    }, files=[
+        ("file", ("bill.pdf", PDF_RECEIPT, "application/pdf")),
+    ])
+    assert res_uploaded.status_code == 201
+
+    assert approve(res_internet.json()["id"]).status_code == 200
+    assert approve(res_transport.json()["id"]).status_code == 200
+
+    res_process = requests.post(f"{backend_host}/attendance/{june_attendance_id}/process_payroll", headers={
+        "Authorization": f"JWT {admin}"
+    })
+    assert res_process.status_code == 200
+
+    res_payslip = requests.get(f"{backend_host}/attendance/{june_attendance_id}/payslip", headers={
+        "Authorization": f"JWT {employee}"
+    })
+    assert res_payslip.status_code == 200
+    assert [(c["name"], c["amount"], len(c["claims"])) for c in res_payslip.json()["reimbursements"]] == [
+        ("Internet", 250_000, 1),
+        ("Transport", 20_000, 1),
+    ]
+    assert res_payslip.json()["summary"]["reimbursement_total"] == 270_000
+
+    stop_containers(containers)
+
+def test_reimbursement_approval(tmp_path):
+    test_db_url, backend_host, faketime, containers = spin_environment(datetime(2024, 6, 3, 10, 0, 0), tmp_path)
+    pg_conn = psycopg2.connect(test_db_url)
+
+    employee = create_and_get_random_user(pg_conn, backend_host, faketime, "employee", 5000000)
+    admin = create_and_get_random_user(pg_conn, backend_host, faketime, "admin", 0)
+
+    res_created = requests.post(f"{backend_host}/attendance", headers={
+        "Authorization": f"JWT {admin}"
+    }, json={
+        "start_at": datetime(2024, 6, 1, 0, 0).replace(tzinfo=pytz.UTC).isoformat(),
+        "end_at": datetime(2024, 6, 30, 0, 0).replace(tzinfo=pytz.UTC).isoformat(),
+    })
+    assert res_created.status_code == 201
+
+    attendance_id = res_created.json()["id"]
+
+    claims = []
+    for (category, description, amount) in [("Transport", "Taxi", 150_000), ("Meals", "Team lunch", 300_000), ("Equipment", "Keyboard", 500_000)]:
+        res_reimburse = requests.post(f"{backend_host}/attendance/{attendance_id}/reimburse", headers={
+            "Authorization": f"JWT {employee}"
+        }, json={
+            "category_id": get_reimbursement_category(backend_host, employee, category),
+            "description": description,
+            "amount": amount,
+        })
+        assert res_reimburse.status_code == 201
+        assert res_reimburse.json()["status"] == "Submitted"
+        assert res_reimburse.json()["approved_amount"] is None
+
+        claims.append(res_reimburse.json()["id"])
+
+    [taxi, lunch, keyboard] = claims
+
+    res_not_admin = requests.get(f"{backend_host}/attendance/{attendance_id}/reimburse", headers={
+        "Authorization": f"JWT {employee}"
+    })
+    assert res_not_admin.status_code == 403
+
+    res_queue = requests.get(f"{backend_host}/attendance/{attendance_id}/reimburse?status=Submitted", headers={
+        "Authorization": f"JWT {admin}"
+    })
+    assert res_queue.status_code == 200
+    assert [r["id"] for r in res_queue.json()] == claims
+
+    def review(reimbursement_id, action, payload=None):
+        return requests.post(f"{backend_host}/attendance/{attendance_id}/reimburse/{reimbursement_id}/{action}", headers={
+            "Authorization": f"JWT {admin}"
+        }, json=payload)
+
+    res_too_much = review(taxi, "approve", {"approved_amount": 150_001})
+    assert res_too_much.status_code == 400
+    assert res_too_much.text == "approved_amount must not be more than the claimed amount"
+
+    res_partial = review(taxi, "approve", {"approved_amount": 100_000, "comment": "Only the way to the office is covered"})
+    assert res_partial.status_code == 200
+    assert res_partial.json()["status"] == "PartiallyApproved"
+    assert res_partial.json()["approved_amount"] == 100_000
+
+    res_approved = review(lunch, "approve")
+    assert res_approved.status_code == 200
+    assert res_approved.json()["status"] == "Approved"
+    assert res_approved.json()["approved_amount"] == 300_000
+
+    res_already = review(lunch, "reject")
+    assert res_already.status_code == 400
+    assert res_already.text == "claim is already reviewed"
+
+    res_process = requests.post(f"{backend_host}/attendance/{attendance_id}/process_payroll", headers={
+        "Authorization": f"JWT {admin}"
+    })
+    assert res_process.status_code == 400
+    assert res_process.text == "1 reimbursement claims are still submitted, review them first or process with `force=true` to leave them unpaid"
+
+    res_rejected = review(keyboard, "reject", {"comment": "Provided by the office"})
+    assert res_rejected.status_code == 200
+    assert res_rejected.json()["status"] == "Rejected"
+
+    res_process = requests.post(f"{backend_host}/attendance/{attendance_id}/process_payroll", headers={
+        "Authorization": f"JWT {admin}"
+    })
+    assert res_process.status_code == 200
+
+    res_reviewed = requests.get(f"{backend_host}/attendance/{attendance_id}/reimburse", headers={
+        "Authorization": f"JWT {admin}"
+    })
+    assert [r["status"] for r in res_reviewed.json()] == ["Paid", "Paid", "Rejected"]
+
+    res_payslip = requests.get(f"{backend_host}/attendance/{attendance_id}/payslip", headers={
+        "Authorization": f"JWT {employee}"
+    })
+    assert res_payslip.status_code == 200
+    assert res_payslip.json()["reimbursements"] == [
+        {
+            "category_id": get_reimbursement_category(backend_host, employee, "Meals"),
+            "name": "Meals",
+            "amount": 300_000,
+            "claims": [{"description": "Team lunch", "claimed_amount": 300_000, "amount": 300_000, "review_comment": None}],
+        },
+        {
+            "category_id": get_reimbursement_category(backend_host, employee, "Transport"),
+            "name": "Transport",
+            "amount": 100_000,
+            "claims": [{"description": "Taxi", "claimed_amount": 150_000, "amount": 100_000, "review_comment": "Only the way to the office is covered"}],
+        },
+    ]
+    assert res_payslip.json()["summary"]["reimbursement_total"] == 400_000
+
+    stop_containers(containers)
+
+def test_reimbursement_self_service(tmp_path):
+    test_db_url, backend_host, faketime, containers = spin_environment(datetime(2024, 6, 3, 10, 0, 0), tmp_path)
+    pg_conn = psycopg2.connect(test_db_url)
+
+    employee = create_and_get_random_user(pg_conn, backend_host, faketime, "employee", 5000000)
+    other_employee = create_and_get_random_user(pg_conn, backend_host, faketime, "employee", 5000000)
+    admin = create_and_get_random_user(pg_conn, backend_host, faketime, "admin", 0)
+
+    transport = get_reimbursement_category(backend_host, employee, "Transport")
+    meals = get_reimbursement_category(backend_host, employee, "Meals")
+
+    attendance_ids = []
+    for (start_at, end_at) in [(datetime(2024, 5, 1, 0, 0), datetime(2024, 5, 31, 0, 0)), (datetime(2024, 6, 1, 0, 0), datetime(2024, 6, 30, 0, 0))]:
+        res_created = requests.post(f"{backend_host}/attendance", headers={
+            "Authorization": f"JWT {admin}"
+        }, json={
+            "start_at": start_at.replace(tzinfo=pytz.UTC).isoformat(),
+            "end_at": end_at.replace(tzinfo=pytz.UTC).isoformat(),
+        })
+        assert res_created.status_code == 201
+
+        attendance_ids.append(res_created.json()["id"])
+
+    [may_attendance_id, june_attendance_id] = attendance_ids
+
+    def claim(attendance_id, description, amount):
+        res_reimburse = requests.post(f"{backend_host}/attendance/{attendance_id}/reimburse", headers={
+            "Authorization": f"JWT {employee}"
+        }, json={
+            "category_id": transport,
+            "description": description,
+            "amount": amount,
+        })
+        assert res_reimburse.status_code == 201
+
+        return res_reimburse.json()["id"]
+
+    may_taxi = claim(may_attendance_id, "Taxi", 50_000)
+    june_taxi = claim(june_attendance_id, "Taxi", 60_000)
+    june_train = claim(june_attendance_id, "Train", 70_000)
+
+    assert requests.post(f"{backend_host}/attendance/{may_attendance_id}/reimburse/{may_taxi}/approve", headers={
+        "Authorization": f"JWT {admin}"
+    }).status_code == 200
+    assert requests.post(f"{backend_host}/attendance/{may_attendance_id}/process_payroll", headers={
+        "Authorization": f"JWT {admin}"
+    }).status_code == 200
+
+    # Claims of every period, the most recent period first
+    res_mine = requests.get(f"{backend_host}/reimbursement/me", headers={
+        "Authorization": f"JWT {employee}"
+    })
+    assert res_mine.status_code == 200
+    assert [(r["id"], r["status"], r["attendance_period"]["id"], r["category"]["name"]) for r in res_mine.json()] == [
+        (june_taxi, "Submitted", june_attendance_id, "Transport"),
+        (june_train, "Submitted", june_attendance_id, "Transport"),
+        (may_taxi, "Paid", may_attendance_id, "Transport"),
+    ]
+
+    res_paid = requests.get(f"{backend_host}/reimbursement/me?status=Paid", headers={
+        "Authorization": f"JWT {employee}"
+    })
+    assert [r["id"] for r in res_paid.json()] == [may_taxi]
+
+    res_others = requests.get(f"{backend_host}/reimbursement/me", headers={
+        "Authorization": f"JWT {other_employee}"
+    })
+    assert res_others.json() == []
+
+    def edit(token, attendance_id, reimbursement_id, payload):
+        return requests.patch(f"{backend_host}/attendance/{attendance_id}/reimburse/{reimbursement_id}", headers={
+            "Authorization": f"JWT {token}"
+        }, json=payload)
+
+    res_processed = edit(employee, may_attendance_id, may_taxi, {"amount": 40_000})
+    assert res_processed.status_code == 400
+    assert res_processed.text == "attendance is already processed"
+
+    assert edit(other_employee, june_attendance_id, june_taxi, {"amount": 40_000}).status_code == 404
+
+    res_edited = edit(employee, june_attendance_id, june_taxi, {"category_id": meals, "description": "Dinner"})
+    assert res_edited.status_code == 200
+    assert res_edited.json()["category_id"] == meals
+    assert res_edited.json()["description"] == "Dinner"
+    assert res_edited.json()["amount"] == 60_000
+
+    res_invalid = edit(employee, june_attendance_id, june_taxi, {"amount": 0})
+    assert res_invalid.status_code == 400
+    assert res_invalid.text == "amount must be more than 0"
+
+    assert requests.post(f"{backend_host}/attendance/{june_attendance_id}/reimburse/{june_train}/reject", headers={
+        "Authorization": f"JWT {admin}"
+    }).status_code == 200
+
+    res_reviewed = edit(employee, june_attendance_id, june_train, {"amount": 10_000})
+    assert res_reviewed.status_code == 400
+    assert res_reviewed.text == "claim is already reviewed"
+
+    def withdraw(token, reimbursement_id):
+        return requests.delete(f"{backend_host}/attendance/{june_attendance_id}/reimburse/{reimbursement_id}", headers={
+            "Authorization": f"JWT {token}"
+        })
+
+    assert withdraw(employee, june_train).status_code == 400
+    assert withdraw(other_employee, june_taxi).status_code == 404
+    assert withdraw(employee, june_taxi).status_code == 204
+
+    res_withdrawn_again = withdraw(employee, june_taxi)
+    assert res_withdrawn_again.status_code == 400
+    assert res_withdrawn_again.text == "claim is withdrawn"
+
+    res_withdrawn_edit = edit(employee, june_attendance_id, june_taxi, {"amount": 10_000})
+    assert res_withdrawn_edit.status_code == 400
+    assert res_withdrawn_edit.text == "claim is withdrawn"
+
+    # Withdrawn claims are kept for who withdrew them and when
+    res_mine = requests.get(f"{backend_host}/reimbursement/me", headers={
+        "Authorization": f"JWT {employee}"
+    })
+    assert [(r["id"], r["status"]) for r in res_mine.json()] == [(june_taxi, "Withdrawn"), (june_train, "Rejected"), (may_taxi, "Paid")]
+    assert res_mine.json()[0]["updated_by"] == res_mine.json()[0]["created_by"]
+
+    # But they're out of the review queue unless asked for
+    res_queue = requests.get(f"{backend_host}/attendance/{june_attendance_id}/reimburse", headers={
+        "Authorization": f"JWT {admin}"
+    })
+    assert [r["id"] for r in res_queue.json()] == [june_train]
+
+    res_queue_withdrawn = requests.get(f"{backend_host}/attendance/{june_attendance_id}/reimburse?status=Withdrawn", headers={
+        "Authorization": f"JWT {admin}"
+    })
+    assert [r["id"] for r in res_queue_withdrawn.json()] == [june_taxi]
+
+    # Claiming the withdrawn expense again is how a withdrawn claim gets fixed, it isn't a duplicate
+    june_dinner = requests.post(f"{backend_host}/attendance/{june_attendance_id}/reimburse", headers={
+        "Authorization": f"JWT {employee}"
+    }, json={
+        "category_id": meals,
+        "description": "dinner",
+        "amount": 60_000,
+    })
+    assert june_dinner.status_code == 201
+
+    res_flagged = requests.get(f"{backend_host}/attendance/{june_attendance_id}/reimburse?flagged=true", headers={
+        "Authorization": f"JWT {admin}"
+    })
+    assert june_dinner.json()["id"] not in [r["id"] for r in res_flagged.json()]
+
+    stop_containers(containers)
+
+def test_reimbursement_currency(tmp_path):
+    test_db_url, backend_host, faketime, containers = spin_environment(datetime(2024, 6, 20, 10, 0, 0), tmp_path)
+    pg_conn = psycopg2.connect(test_db_url)
+
+    employee = create_and_get_random_user(pg_conn, backend_host, faketime, "employee", 5000000)
+    admin = create_and_get_random_user(pg_conn, backend_host, faketime, "admin", 0)
+
+    def set_rate(token, currency, effective_from, rate):
+        return requests.post(f"{backend_host}/exchange_rate", headers={
+            "Authorization": f"JWT {token}"
+        }, json={
+            "currency": currency,
+            "effective_from": effective_from,
+            "rate": rate,
+        })
+
+    assert set_rate(employee, "USD", "2024-06-01", 16_000_000_000).status_code == 403
+
+    res_par = set_rate(admin, "IDR", "2024-06-01", 1_000_000)
+    assert res_par.status_code == 400
+    assert res_par.text == "IDR is always at par"
+
+    res_unsupported = set_rate(admin, "XYZ", "2024-06-01", 1_000_000)
+    assert res_unsupported.status_code == 400
+    assert res_unsupported.text == "currency is not supported"
+
+    # USD 1 = IDR 15,850 from June 1st, then IDR 16,250.5 from June 10th
+    assert set_rate(admin, "USD", "2024-06-01", 15_850_000_000).status_code == 201
+    assert set_rate(admin, "USD", "2024-06-10", 16_250_500_000).status_code == 201
+    assert set_rate(admin, "SGD", "2024-06-01", 12_045_123_456).status_code == 201
+
+    res_taken = set_rate(admin, "USD", "2024-06-10", 16_000_000_000)
+    assert res_taken.status_code == 400
+    assert res_taken.text == "a USD rate is already effective from 2024-06-10"
+
+    res_rates = requests.get(f"{backend_host}/exchange_rate?currency=USD", headers={
+        "Authorization": f"JWT {employee}"
+    })
+    assert [(r["effective_from"], r["rate"]) for r in res_rates.json()] == [
+        ("2024-06-10", 16_250_500_000),
+        ("2024-06-01", 15_850_000_000),
+    ]
+
+    res_created = requests.post(f"{backend_host}/attendance", headers={
+        "Authorization": f"JWT {admin}"
+    }, json={
+        "start_at": datetime(2024, 6, 1, 0, 0).replace(tzinfo=pytz.UTC).isoformat(),
+        "end_at": datetime(2024, 6, 30, 0, 0).replace(tzinfo=pytz.UTC).isoformat(),
+    })
+    assert res_created.status_code == 201
+
+    attendance_id = res_created.json()["id"]
+
+    def claim(payload):
+        return requests.post(f"{backend_host}/attendance/{attendance_id}/reimburse", headers={
+            "Authorization": f"JWT {employee}"
+        }, json={
+            "category_id": get_reimbursement_category(backend_host, employee, "Transport"),
+            "description": "Airport taxi",
+            **payload,
+        })
+
+    res_no_rate = claim({"amount": 1_234, "currency": "USD", "transaction_date": "2024-05-31"})
+    assert res_no_rate.status_code == 400
+    assert res_no_rate.text == "no USD exchange rate is effective on 2024-05-31"
+
+    res_future = claim({"amount": 1_234, "currency": "USD", "transaction_date": "2024-06-21"})
+    assert res_future.status_code == 400
+    assert res_future.text == "transaction_date must not be in the future"
+
+    # USD 12.34 at 15,850 is exactly 195,589
+    res_early = claim({"amount": 1_234, "currency": "USD", "transaction_date": "2024-06-05"})
+    assert res_early.status_code == 201
+    assert res_early.json()["currency"] == "USD"
+    assert res_early.json()["original_amount"] == 1_234
+    assert res_early.json()["exchange_rate"] == 15_850_000_000
+    assert res_early.json()["amount"] == 195_589
+
+    # USD 12.34 at 16,250.5 is 200,531.17
+    res_late = claim({"amount": 1_234, "currency": "USD", "transaction_date": "2024-06-12"})
+    assert res_late.status_code == 201
+    assert res_late.json()["amount"] == 200_531
+
+    # Rupiah claims are made today unless told otherwise
+    res_rupiah = claim({"amount": 150_000})
+    assert res_rupiah.status_code == 201
+    assert res_rupiah.json()["currency"] == "IDR"
+    assert res_rupiah.json()["transaction_date"] == "2024-06-20"
+    assert res_rupiah.json()["amount"] == 150_000
+
+    # SGD 1,000.00 at 12,045.123456 is 12,045,123.456
+    res_edited = requests.patch(f"{backend_host}/attendance/{attendance_id}/reimburse/{res_rupiah.json()['id']}", headers={
+        "Authorization": f"JWT {employee}"
+    }, json={
+        "currency": "SGD",
+        "amount": 100_000,
+    })
+    assert res_edited.status_code == 200
+    assert res_edited.json()["amount"] == 12_045_123
+
+    # The later USD rate is corrected before the claims are approved
+    later_rate = next(r for r in res_rates.json() if r["effective_from"] == "2024-06-10")
+    assert requests.delete(f"{backend_host}/exchange_rate/{later_rate['id']}", headers={
+        "Authorization": f"JWT {admin}"
+    }).status_code == 204
+    assert set_rate(admin, "USD", "2024-06-10", 16_000_000_000).status_code == 201
+
+    def approve(reimbursement_id, payload=None):
+        return requests.post(f"{backend_host}/attendance/{attendance_id}/reimburse/{reimbursement_id}/approve", headers={
+            "Authorization": f"JWT {admin}"
+        }, json=payload)
+
+    res_approved = approve(res_late.json()["id"], {"approved_amount": 150_000})
+    assert res_approved.status_code == 200
+    assert res_approved.json()["status"] == "PartiallyApproved"
+    assert res_approved.json()["exchange_rate"] == 16_000_000_000
+    assert res_approved.json()["amount"] == 197_440
+    assert res_approved.json()["approved_amount"] == 150_000
+
+    assert approve(res_early.json()["id"]).status_code == 200
+    assert approve(res_rupiah.json()["id"]).status_code == 200
+
+    res_category = requests.post(f"{backend_host}/reimbursement_category", headers={
+        "Authorization": f"JWT {admin}"
+    }, json={
+        "name": "Travel",
+        "per_claim_max_amount": 200_000,
+    })
+    assert res_category.status_code == 201
+
+    # USD 12.34 at 16,000 fits the limit when it's claimed
+    res_travel = claim({"category_id": res_category.json()["id"], "amount": 1_234, "currency": "USD", "transaction_date": "2024-06-12"})
+    assert res_travel.status_code == 201
+    assert res_travel.json()["amount"] == 197_440
+
+    # But not once the rate is corrected to 16,500
+    corrected_rate = next(r for r in requests.get(f"{backend_host}/exchange_rate?currency=USD", headers={
+        "Authorization": f"JWT {admin}"
+    }).json() if r["effective_from"] == "2024-06-10")
+    assert requests.delete(f"{backend_host}/exchange_rate/{corrected_rate['id']}", headers={
+        "Authorization": f"JWT {admin}"
+    }).status_code == 204
+    assert set_rate(admin, "USD", "2024-06-10", 16_500_000_000).status_code == 201
+
+    res_over_limit = approve(res_travel.json()["id"])
+    assert res_over_limit.status_code == 400
+    assert res_over_limit.text == "you cannot claim more than 200000 for Travel at once"
+
+    assert requests.post(f"{backend_host}/attendance/{attendance_id}/reimburse/{res_travel.json()['id']}/reject", headers={
+        "Authorization": f"JWT {admin}"
+    }).status_code == 200
+
+    res_process = requests.post(f"{backend_host}/attendance/{attendance_id}/process_payroll", headers={
+        "Authorization": f"JWT {admin}"
+    })
+    assert res_process.status_code == 200
+
+    res_payslip = requests.get(f"{backend_host}/attendance/{attendance_id}/payslip", headers={
+        "Authorization": f"JWT {employee}"
+    })
+    assert res_payslip.status_code == 200
+    assert [
+        (c["currency"], c["original_amount"], c["exchange_rate"], c["claimed_amount"], c["amount"])
+        for c in res_payslip.json()["reimbursements"][0]["claims"]
+    ] == [
+        ("USD", 1_234, 15_850_000_000, 195_589, 195_589),
+        ("USD", 1_234, 16_000_000_000, 197_440, 150_000),
+        ("SGD", 100_000, 12_045_123_456, 12_045_123, 12_045_123),
+    ]
+    assert res_payslip.json()["summary"]["reimbursement_total"] == 12_390_712
+
+    stop_containers(containers)
+
+def test_reimbursement_screening(tmp_path):
+    test_db_url, backend_host, faketime, containers = spin_environment(datetime(2024, 6, 20, 10, 0, 0), tmp_path, {
+        "REIMBURSEMENT_DUPLICATE_WINDOW_DAYS": "3",
+    })
+    pg_conn = psycopg2.connect(test_db_url)
+
+    employee = create_and_get_random_user(pg_conn, backend_host, faketime, "employee", 5000000)
+    other_employee = create_and_get_random_user(pg_conn, backend_host, faketime, "employee", 5000000)
+    admin = create_and_get_random_user(pg_conn, backend_host, faketime, "admin", 0)
+
+    transport = get_reimbursement_category(backend_host, employee, "Transport")
+
+    res_created = requests.post(f"{backend_host}/attendance", headers={
+        "Authorization": f"JWT {admin}"
+    }, json={
+        "start_at": datetime(2024, 6, 1, 0, 0).replace(tzinfo=pytz.UTC).isoformat(),
+        "end_at": datetime(2024, 6, 30, 0, 0).replace(tzinfo=pytz.UTC).isoformat(),
+    })
+    assert res_created.status_code == 201
+    attendance_id = res_created.json()["id"]
+
+    def claim(token, description, amount, transaction_date):
+        res_reimburse = requests.post(f"{backend_host}/attendance/{attendance_id}/reimburse", headers={
+            "Authorization": f"JWT {token}"
+        }, json={
+            "category_id": transport,
+            "description": description,
+            "amount": amount,
+            "transaction_date": transaction_date,
+        })
+        assert res_reimburse.status_code == 201
+
+        return res_reimburse.json()["id"]
+
+    def upload(token, reimbursement_id, file_name):
+        res_upload = requests.post(f"{backend_host}/attendance/{attendance_id}/reimburse/{reimbursement_id}/receipt", headers={
+            "Authorization": f"JWT {token}"
+        }, files=[
+            ("file", (file_name, PNG_RECEIPT, "image/png")),
+        ])
+        assert res_upload.status_code == 201
+
+    def queue(query=""):
+        res_queue = requests.get(f"{backend_host}/attendance/{attendance_id}/reimburse{query}", headers={
+            "Authorization": f"JWT {admin}"
+        })
+        assert res_queue.status_code == 200
+
+        return {r["id"]: [(f["kind"], f["reason"], f["related_reimbursement_id"]) for f in r["flags"]] for r in res_queue.json()}
+
+    for (amount, transaction_date) in [(50_000, "2024-06-03"), (60_000, "2024-06-04"), (55_000, "2024-06-05")]:
+        claim(employee, f"Bus on {transaction_date}", amount, transaction_date)
+
+    taxi = claim(employee, "Taxi to client", 70_000, "2024-06-10")
+    # Case and spacing don't make a different expense, a claim outside the window does
+    taxi_again = claim(employee, "  taxi to CLIENT ", 70_000, "2024-06-12")
+    taxi_later = claim(employee, "Taxi to client", 70_000, "2024-06-16")
+    airport = claim(employee, "Airport", 400_000, "2024-06-11")
+
+    upload(employee, taxi, "taxi.png")
+    upload(employee, airport, "airport.png")
+    other_bus = claim(other_employee, "Bus", 10_000, "2024-06-11")
+    upload(other_employee, other_bus, "bus.png")
+
+    flags = queue()
+    assert flags[taxi_again] == [("Duplicate", "same amount and description as a claim made on 2024-06-10", taxi)]
+    assert flags[taxi_later] == []
+    assert sorted(flags[airport]) == [
+        ("CategoryOutlier", "400000 is over 300% of the typical Transport claim of 65000", None),
+        ("EmployeeOutlier", "400000 is over 300% of the employee's typical Transport claim of 65000", None),
+        ("ReceiptReused", "receipt `airport.png` is also attached to a claim made on 2024-06-10", taxi),
+    ]
+    assert sorted(flags[other_bus]) == sorted([
+        ("ReceiptReused", "receipt `bus.png` is also attached to another employee's claim", taxi),
+        ("ReceiptReused", "receipt `bus.png` is also attached to another employee's claim", airport),
+    ])
+
+    assert set(queue("?flagged=true")) == {taxi, taxi_again, airport, other_bus}
+    assert taxi_again not in queue("?flagged=false")
+
+    # Flags follow the claim as it's edited
+    res_edited = requests.patch(f"{backend_host}/attendance/{attendance_id}/reimburse/{taxi_again}", headers={
+        "Authorization": f"JWT {employee}"
+    }, json={"amount": 75_000})
+    assert res_edited.status_code == 200
+    assert queue()[taxi_again] == []
+
+    # Rejected claims can be made again
+    assert requests.post(f"{backend_host}/attendance/{attendance_id}/reimburse/{taxi_later}/reject", headers={
+        "Authorization": f"JWT {admin}"
+    }).status_code == 200
+    taxi_retry = claim(employee, "Taxi to client", 70_000, "2024-06-16")
+    assert queue()[taxi_retry] == []
+
+    # Screening only flags, flagged claims are approved as any other
+    res_approved = requests.post(f"{backend_host}/attendance/{attendance_id}/reimburse/{airport}/approve", headers={
+        "Authorization": f"JWT {admin}"
+    })
+    assert res_approved.status_code == 200
+    assert res_approved.json()["status"] == "Approved"
+
+    stop_containers(containers)
+
+def test_payroll_snapshot(tmp_path):
+    # Test on Wednesday 5th, June 2024 9 AM
+    test_db_url, backend_host, faketime, containers = spin_environment(datetime(2024, 6, 5, 9, 0, 0), tmp_path)
+    pg_conn = psycopg2.connect(test_db_url)
+
+    employee = create_and_get_random_user(pg_conn, backend_host, faketime, "employee", 3000000)
+    other_employee = create_and_get_random_user(pg_conn, backend_host, faketime, "employee", 3000000)
+    admin = create_and_get_random_user(pg_conn, backend_host, faketime, "admin", 0)
+
+    meals = get_reimbursement_category(backend_host, employee, "Meals")
+
+    res_created = requests.post(f"{backend_host}/attendance", headers={
+        "Authorization": f"JWT {admin}"
+    }, json={
+        "start_at": datetime(2024, 6, 3, 0, 0).replace(tzinfo=pytz.UTC).isoformat(),
+        "end_at": datetime(2024, 6, 5, 0, 0).replace(tzinfo=pytz.UTC).isoformat(),
+    })
+    assert res_created.status_code == 201
+    attendance_id = res_created.json()["id"]
+
+    res_checkin = requests.post(f"{backend_host}/attendance/{attendance_id}", headers={
+        "Authorization": f"JWT {employee}"
+    })
+    assert res_checkin.status_code == 201
+    employee_id = res_checkin.json()["created_by"]
+
+    res_reimburse = requests.post(f"{backend_host}/attendance/{attendance_id}/reimburse", headers={
+        "Authorization": f"JWT {employee}"
+    }, json={
+        "category_id": meals,
+        "description": "Lunch with client",
+        "amount": 80_000,
+    })
+    assert res_reimburse.status_code == 201
+    assert requests.post(f"{backend_host}/attendance/{attendance_id}/reimburse/{res_reimburse.json()['id']}/approve", headers={
+        "Authorization": f"JWT {admin}"
+    }).status_code == 200
+
+    res_process_payroll = requests.post(f"{backend_host}/attendance/{attendance_id}/process_payroll", headers={
+        "Authorization": f"JWT {admin}"
+    })
+    assert res_process_payroll.status_code == 200
+    assert res_process_payroll.json()["processed"] == True
+    payroll_run = res_process_payroll.json()["payroll_run"]
+    assert payroll_run["engine_version"] == 2
+    assert payroll_run["backfilled"] == False
+
+    res_payslip = requests.get(f"{backend_host}/attendance/{attendance_id}/payslip", headers={
+        "Authorization": f"JWT {employee}"
+    })
+    assert res_payslip.status_code == 200
+    assert res_payslip.json()["payroll_run_id"] == payroll_run["id"]
+    assert res_payslip.json()["engine_version"] == 2
+    # 1 of 3 working days attended
+    assert res_payslip.json()["summary"]["prorated_amount"] == 1000000
+    assert res_payslip.json()["summary"]["take_home_pay"] == 1080000
+    assert [(l["kind"], l["description"], l["amount"]) for l in res_payslip.json()["lines"]] == [
+        ("Salary", "Salary for 1 days attended or on paid leave", 1000000),
+        ("Reimbursement", "Meals reimbursements", 80000),
+    ]
+
+    # Whatever changes after the payroll is processed, what was paid stays the same
+    pg_curr = pg_conn.cursor()
+    pg_curr.execute("UPDATE \"user\" SET salary = 6000000 WHERE id = %s", (employee_id,))
+    pg_curr.execute("DELETE FROM employee_attendance WHERE created_by = %s", (employee_id,))
+    pg_conn.commit()
+    pg_curr.close()
+
+    res_payslip_again = requests.get(f"{backend_host}/attendance/{attendance_id}/payslip", headers={
+        "Authorization": f"JWT {employee}"
+    })
+    assert res_payslip_again.status_code == 200
+    assert res_payslip_again.json() == res_payslip.json()
+
+    res_employee_payslips = requests.get(f"{backend_host}/attendance/{attendance_id}/employee_payslips", headers={
+        "Authorization": f"JWT {admin}"
+    })
+    assert res_employee_payslips.status_code == 200
+    assert res_employee_payslips.json()["payroll_run"]["id"] == payroll_run["id"]
+    assert res_employee_payslips.json()["total_take_home"] == 1080000
+    assert len(res_employee_payslips.json()["payslips"]) == 2
+
+    assert requests.post(f"{backend_host}/attendance/{attendance_id}/process_payroll", headers={
+        "Authorization": f"JWT {admin}"
+    }).status_code == 400
+
+    # Nobody joining afterwards was paid in the period
+    late_joiner = create_and_get_random_user(pg_conn, backend_host, faketime, "employee", 3000000)
+    assert requests.get(f"{backend_host}/attendance/{attendance_id}/payslip", headers={
+        "Authorization": f"JWT {late_joiner}"
+    }).status_code == 404
+
+    # Periods processed before payroll runs were kept have none until an admin backfills it
+    pg_curr = pg_conn.cursor()
+    pg_curr.execute("DELETE FROM payroll_run")
+    pg_conn.commit()
+    pg_curr.close()
+
+    res_not_backfilled = requests.get(f"{backend_host}/attendance/{attendance_id}/payslip", headers={
+        "Authorization": f"JWT {other_employee}"
+    })
+    assert res_not_backfilled.status_code == 409
+    assert res_not_backfilled.text == "payroll run of the period isn't backfilled yet"
+
+    pg_curr = pg_conn.cursor()
+    pg_curr.execute("SELECT COUNT(*) FROM payroll_run")
+    assert pg_curr.fetchone()[0] == 0
+    pg_curr.close()
+
+    def backfill(token):
+        return requests.post(f"{backend_host}/attendance/{attendance_id}/backfill_payroll_run", headers={
+            "Authorization": f"JWT {token}"
+        })
+
+    assert backfill(other_employee).status_code == 403
+
+    res_backfill = backfill(admin)
+    assert res_backfill.status_code == 201
+    assert res_backfill.json()["backfilled"] == True
+
+    res_backfill_again = backfill(admin)
+    assert res_backfill_again.status_code == 409
+    assert res_backfill_again.text == "payroll run of the period is already kept"
+
+    res_backfilled = requests.get(f"{backend_host}/attendance/{attendance_id}/employee_payslips", headers={
+        "Authorization": f"JWT {admin}"
+    })
+    assert res_backfilled.status_code == 200
+    assert res_backfilled.json()["payroll_run"]["id"] == res_backfill.json()["id"]
+    assert len(res_backfilled.json()["payslips"]) == 3
+
+    res_other_payslip = requests.get(f"{backend_host}/attendance/{attendance_id}/payslip", headers={
+        "Authorization": f"JWT {other_employee}"
+    })
+    assert res_other_payslip.status_code == 200
+    assert res_other_payslip.json()["payroll_run_id"] == res_backfilled.json()["payroll_run"]["id"]
+
+    stop_containers(containers)
+
+def test_payroll_preview(tmp_path):
+    # Test on Wednesday 5th, June 2024 9 AM
+    test_db_url, backend_host, faketime, containers = spin_environment(datetime(2024, 6, 5, 9, 0, 0), tmp_path)
+    pg_conn = psycopg2.connect(test_db_url)
+
+    employee = create_and_get_random_user(pg_conn, backend_host, faketime, "employee", 5000000)
+    absent_employee = create_and_get_random_user(pg_conn, backend_host, faketime, "employee", 5000000)
+    admin = create_and_get_random_user(pg_conn, backend_host, faketime, "admin", 0)
+
+    meals = get_reimbursement_category(backend_host, employee, "Meals")
+
+    res_created = requests.post(f"{backend_host}/attendance", headers={
+        "Authorization": f"JWT {admin}"
+    }, json={
+        "start_at": datetime(2024, 6, 3, 0, 0).replace(tzinfo=pytz.UTC).isoformat(),
+        "end_at": datetime(2024, 6, 7, 0, 0).replace(tzinfo=pytz.UTC).isoformat(),
+    })
+    assert res_created.status_code == 201
+    attendance_id = res_created.json()["id"]
+
+    res_checkin = requests.post(f"{backend_host}/attendance/{attendance_id}", headers={
+        "Authorization": f"JWT {employee}"
+    })
+    assert res_checkin.status_code == 201
+    employee_id = res_checkin.json()["created_by"]
+
+    reimbursement_ids = []
+    for (description, amount) in [("Lunch", 50_000), ("Dinner", 70_000)]:
+        res_reimburse = requests.post(f"{backend_host}/attendance/{attendance_id}/reimburse", headers={
+            "Authorization": f"JWT {employee}"
+        }, json={
+            "category_id": meals,
+            "description": description,
+            "amount": amount,
+        })
+        assert res_reimburse.status_code == 201
+        reimbursement_ids.append(res_reimburse.json()["id"])
+
+    assert requests.post(f"{backend_host}/attendance/{attendance_id}/reimburse/{reimbursement_ids[0]}/approve", headers={
+        "Authorization": f"JWT {admin}"
+    }).status_code == 200
+
+    assert requests.get(f"{backend_host}/attendance/{attendance_id}/payroll_preview", headers={
+        "Authorization": f"JWT {employee}"
+    }).status_code == 403
+
+    res_preview = requests.get(f"{backend_host}/attendance/{attendance_id}/payroll_preview", headers={
+        "Authorization": f"JWT {admin}"
+    })
+    assert res_preview.status_code == 200
+    assert res_preview.json()["provisional"] == True
+    assert res_preview.json()["engine_version"] == 2
+    # 1 of 5 working days attended, and the approved claim is paid as if processed now
+    assert res_preview.json()["summary"] == {
+        "employees": 2,
+        "prorated_total": 1000000,
+        "overtime_total": 0,
+        "reimbursement_total": 50000,
+        "deduction_total": 0,
+        "total_take_home": 1050000,
+    }
+    assert [(w["kind"], w["message"]) for w in res_preview.json()["warnings"]] == [
+        ("MissingAttendance", "2 employees have working days without attendance nor leave"),
+        ("NoAttendedDays", "1 employees have no days attended nor on paid leave"),
+        ("MissingTaxProfile", "2 employees have no tax profile and aren't withheld income tax"),
+        ("SubmittedReimbursement", "1 reimbursement claims are still submitted"),
+    ]
+
+    res_employee_preview = requests.get(f"{backend_host}/attendance/{attendance_id}/payroll_preview?employee_id={employee_id}", headers={
+        "Authorization": f"JWT {admin}"
+    })
+    assert res_employee_preview.status_code == 200
+    assert res_employee_preview.json()["summary"]["employees"] == 1
+    [payslip] = res_employee_preview.json()["payslips"]
+    assert payslip["employee"]["id"] == employee_id
+    assert payslip["summary"]["take_home_pay"] == 1050000
+    assert [(w["kind"], w["message"]) for w in payslip["warnings"]] == [
+        ("MissingAttendance", "no attendance nor leave on 2024-06-03, 2024-06-04"),
+        ("MissingTaxProfile", "no tax profile, no income tax is withheld"),
+        ("SubmittedReimbursement", "1 reimbursement claims are still submitted"),
+    ]
+
+    assert requests.get(f"{backend_host}/attendance/{attendance_id}/payroll_preview?employee_id=00000000-0000-0000-0000-000000000000", headers={
+        "Authorization": f"JWT {admin}"
+    }).status_code == 404
+
+    # Previewing pays nothing
+    res_queue = requests.get(f"{backend_host}/attendance/{attendance_id}/reimburse", headers={
+        "Authorization": f"JWT {admin}"
+    })
+    assert [r["status"] for r in res_queue.json()] == ["Approved", "Submitted"]
+
+    res_process_payroll = requests.post(f"{backend_host}/attendance/{attendance_id}/process_payroll?force=true", headers={
+        "Authorization": f"JWT {admin}"
+    })
+    assert res_process_payroll.status_code == 200
+    assert res_process_payroll.json()["payroll_run"]["total_take_home"] == 1050000
+
+    res_processed_preview = requests.get(f"{backend_host}/attendance/{attendance_id}/payroll_preview", headers={
+        "Authorization": f"JWT {admin}"
+    })
+    assert res_processed_preview.status_code == 400
+    assert res_processed_preview.text == "attendance is already processed"
+
+    stop_containers(containers)
+
+def test_payroll_tax(tmp_path):
+    # Test on Wednesday 27th, November 2024 9 AM
+    test_db_url, backend_host, faketime, containers = spin_environment(datetime(2024, 11, 27, 9, 0, 0), tmp_path)
+    pg_conn = psycopg2.connect(test_db_url)
+
+    employee = create_and_get_random_user(pg_conn, backend_host, faketime, "employee", 10000000)
+    other_employee = create_and_get_random_user(pg_conn, backend_host, faketime, "employee", 10000000)
+    admin = create_and_get_random_user(pg_conn, backend_host, faketime, "admin", 0)
+
+    employee_id = requests.get(f"{backend_host}/auth", headers={
+        "Authorization": f"JWT {employee}"
+    }).json()["id"]
+    other_employee_id = requests.get(f"{backend_host}/auth", headers={
+        "Authorization": f"JWT {other_employee}"
+    }).json()["id"]
+
+    assert requests.get(f"{backend_host}/tax_profile/me", headers={
+        "Authorization": f"JWT {employee}"
+    }).status_code == 404
+
+    assert requests.put(f"{backend_host}/tax_profile", headers={
+        "Authorization": f"JWT {employee}"
+    }, json={
+        "user_id": employee_id,
+        "ptkp_status": "TK/0",
+        "npwp": None,
+    }).status_code == 403
+
+    res_invalid_npwp = requests.put(f"{backend_host}/tax_profile", headers={
+        "Authorization": f"JWT {admin}"
+    }, json={
+        "user_id": employee_id,
+        "ptkp_status": "TK/0",
+        "npwp": "12345",
+    })
+    assert res_invalid_npwp.status_code == 400
+    assert res_invalid_npwp.text == "npwp must be 15 or 16 digits"
+
+    res_profile = requests.put(f"{backend_host}/tax_profile", headers={
+        "Authorization": f"JWT {admin}"
+    }, json={
+        "user_id": employee_id,
+        "ptkp_status": "TK/0",
+        "npwp": "01.234.567.8-901.000",
+    })
+    assert res_profile.status_code == 200
+    assert res_profile.json()["npwp"] == "012345678901000"
+
+    # Married with 3 dependants, without a taxpayer number
+    assert requests.put(f"{backend_host}/tax_profile", headers={
+        "Authorization": f"JWT {admin}"
+    }, json={
+        "user_id": other_employee_id,
+        "ptkp_status": "K/3",
+        "npwp": None,
+    }).status_code == 200
+
+    res_my_profile = requests.get(f"{backend_host}/tax_profile/me", headers={
+        "Authorization": f"JWT {employee}"
+    })
+    assert res_my_profile.status_code == 200
+    assert res_my_profile.json()["ptkp_status"] == "TK/0"
+
+    # A payroll kept from earlier in November
+    pg_curr = pg_conn.cursor()
+    pg_curr.execute("INSERT INTO attendance_period (created_at, updated_at, start_at, end_at, processed) VALUES (NOW(), NOW(), '2024-11-01', '2024-11-15', TRUE) RETURNING id")
+    (early_november_id,) = pg_curr.fetchone()
+    pg_curr.execute("INSERT INTO payroll_run (created_at, updated_at, attendance_period_id, engine_version, total_take_home) VALUES (NOW(), NOW(), %s, 1, 5955000) RETURNING id", (early_november_id,))
+    (early_november_run_id,) = pg_curr.fetchone()
+    pg_curr.execute("INSERT INTO payslip (created_at, updated_at, payroll_run_id, employee_id, base_salary, prorated_amount, overtime_total, reimbursement_total, deduction_total, take_home_pay, snapshot, taxable_income, income_tax) VALUES (NOW(), NOW(), %s, %s, 10000000, 6000000, 0, 0, 45000, 5955000, '{}', 6000000, 45000)", (early_november_run_id, employee_id))
+    pg_conn.commit()
+    pg_curr.close()
+
+    res_november = requests.post(f"{backend_host}/attendance", headers={
+        "Authorization": f"JWT {admin}"
+    }, json={
+        "start_at": datetime(2024, 11, 27, 0, 0).replace(tzinfo=pytz.UTC).isoformat(),
+        "end_at": datetime(2024, 11, 27, 0, 0).replace(tzinfo=pytz.UTC).isoformat(),
+    })
+    assert res_november.status_code == 201
+    november_id = res_november.json()["id"]
+
+    for token in [employee, other_employee]:
+        assert requests.post(f"{backend_host}/attendance/{november_id}", headers={
+            "Authorization": f"JWT {token}"
+        }).status_code == 201
+
+    assert requests.post(f"{backend_host}/attendance/{november_id}/process_payroll", headers={
+        "Authorization": f"JWT {admin}"
+    }).status_code == 200
+
+    # Withheld at the TER rate of the month's gross income, less what the earlier run withheld
+    res_payslip = requests.get(f"{backend_host}/attendance/{november_id}/payslip", headers={
+        "Authorization": f"JWT {employee}"
+    })
+    assert res_payslip.status_code == 200
+    assert res_payslip.json()["tax"]["method"] == {
+        "Ter": {"category": "A", "rate": 7, "monthly_income": 16000000, "withheld_before": 45000},
+    }
+    assert res_payslip.json()["tax"]["tax"] == 1120000
+    assert res_payslip.json()["summary"]["income_tax"] == 1075000
+    assert res_payslip.json()["summary"]["take_home_pay"] == 8925000
+    assert res_payslip.json()["lines"][-1] == {
+        "kind": "Deduction",
+        "description": "PPh 21 at 7% of 16000000 earned this month, after what was withheld earlier",
+        "amount": 1075000,
+    }
+
+    # 20% more without a taxpayer number
+    res_other_payslip = requests.get(f"{backend_host}/attendance/{november_id}/payslip", headers={
+        "Authorization": f"JWT {other_employee}"
+    })
+    assert res_other_payslip.status_code == 200
+    assert res_other_payslip.json()["tax"]["method"] == {
+        "Ter": {"category": "C", "rate": 1.5, "monthly_income": 10000000, "withheld_before": 0},
+    }
+    assert res_other_payslip.json()["tax"]["tax"] == 150000
+    assert res_other_payslip.json()["tax"]["surcharge"] == 30000
+    assert res_other_payslip.json()["summary"]["take_home_pay"] == 9820000
+
+    # A payroll kept from January, before anything was withheld
+    pg_curr = pg_conn.cursor()
+    pg_curr.execute("INSERT INTO attendance_period (created_at, updated_at, start_at, end_at, processed) VALUES (NOW(), NOW(), '2024-01-01', '2024-01-31', TRUE) RETURNING id")
+    (january_id,) = pg_curr.fetchone()
+    pg_curr.execute("INSERT INTO payroll_run (created_at, updated_at, attendance_period_id, engine_version, total_take_home) VALUES (NOW(), NOW(), %s, 1, 100000000) RETURNING id", (january_id,))
+    (january_run_id,) = pg_curr.fetchone()
+    pg_curr.execute("INSERT INTO payslip (created_at, updated_at, payroll_run_id, employee_id, base_salary, prorated_amount, overtime_total, reimbursement_total, deduction_total, take_home_pay, snapshot, taxable_income) VALUES (NOW(), NOW(), %s, %s, 100000000, 100000000, 0, 0, 0, 100000000, '{}', 100000000)", (january_run_id, employee_id))
+    pg_conn.commit()
+    pg_curr.close()
+
+    # Monday 2nd, December 2024 on paid leave
+    res_leave = requests.post(f"{backend_host}/leave", headers={
+        "Authorization": f"JWT {employee}"
+    }, json={
+        "leave_type": "Annual",
+        "start_date": "2024-12-02",
+        "end_date": "2024-12-02",
+        "reason": "Family trip",
+    })
+    assert res_leave.status_code == 201
+    assert requests.post(f"{backend_host}/leave/{res_leave.json()['id']}/approve", headers={
+        "Authorization": f"JWT {admin}"
+    }).status_code == 200
+
+    res_december = requests.post(f"{backend_host}/attendance", headers={
+        "Authorization": f"JWT {admin}"
+    }, json={
+        "start_at": datetime(2024, 12, 2, 0, 0).replace(tzinfo=pytz.UTC).isoformat(),
+        "end_at": datetime(2024, 12, 2, 0, 0).replace(tzinfo=pytz.UTC).isoformat(),
+    })
+    assert res_december.status_code == 201
+    december_id = res_december.json()["id"]
+
+    assert requests.post(f"{backend_host}/attendance/{december_id}/process_payroll", headers={
+        "Authorization": f"JWT {admin}"
+    }).status_code == 200
+
+    # December settles the year: 126M gross, 1.5M occupational cost for 3 months, 54M PTKP
+    res_december_payslip = requests.get(f"{backend_host}/attendance/{december_id}/payslip", headers={
+        "Authorization": f"JWT {employee}"
+    })
+    assert res_december_payslip.status_code == 200
+    assert res_december_payslip.json()["tax"]["method"] == {
+        "AnnualReconciliation": {
+            "year": 2024,
+            "months": 3,
+            "gross_income": 126000000,
+            "occupational_cost": 1500000,
+            "ptkp": 54000000,
+            "taxable_income": 70500000,
+            "brackets": [
+                {"taxable_income": 60000000, "rate": 5, "amount": 3000000},
+                {"taxable_income": 10500000, "rate": 15, "amount": 1575000},
+            ],
+            "withheld_before": 1120000,
+        },
+    }
+    assert res_december_payslip.json()["summary"]["income_tax"] == 3455000
+    assert res_december_payslip.json()["summary"]["take_home_pay"] == 6545000
+    assert res_december_payslip.json()["lines"][-1] == {
+        "kind": "Deduction",
+        "description": "PPh 21 for 2024 after what was withheld earlier",
+        "amount": 3455000,
+    }
+
+    # Nothing earned in December, so what was withheld in November is paid back
+    res_other_december_payslip = requests.get(f"{backend_host}/attendance/{december_id}/payslip", headers={
+        "Authorization": f"JWT {other_employee}"
+    })
+    assert res_other_december_payslip.status_code == 200
+    assert res_other_december_payslip.json()["summary"]["income_tax"] == -180000
+    assert res_other_december_payslip.json()["summary"]["deduction_total"] == 0
+    assert res_other_december_payslip.json()["summary"]["take_home_pay"] == 180000
+    assert res_other_december_payslip.json()["lines"][-1] == {
+        "kind": "TaxRefund",
+        "description": "PPh 21 withheld over in 2024, paid back",
+        "amount": 180000,
+    }
+
+    stop_containers(containers)